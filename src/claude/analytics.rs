@@ -1,6 +1,12 @@
-use super::conversation::{Conversation, MessageRole};
+use super::conversation::{Conversation, MessageRole, Usage};
+use super::file_index::FileIndex;
+use super::latency::{response_turns, LatencyStats, ResponseTurn};
+use super::pricing::{billed_usage, conversation_usage, message_cost, ModelPricing};
+use super::prompt_patterns::PromptPatternAnalytics;
+use super::timezone::Timezone;
+use super::tool_outcomes::{tool_outcomes, ToolOutcomeCounts};
 use crate::errors::ClaudeToolsError;
-use chrono::{DateTime, Datelike, Timelike, Utc};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 
@@ -8,6 +14,8 @@ use std::collections::{BTreeMap, HashMap};
 pub struct AnalyticsEngine {
     conversations: Vec<Conversation>,
     cached_analytics: Option<ConversationAnalytics>,
+    /// Zone days, hours and weekdays are bucketed in
+    timezone: Timezone,
 }
 
 /// Complete analytics data structure
@@ -21,6 +29,15 @@ pub struct ConversationAnalytics {
     pub model_analytics: ModelAnalytics,
     /// Tool usage statistics
     pub tool_analytics: ToolAnalytics,
+    /// Prompt cache efficiency
+    #[serde(default)]
+    pub cache_analytics: CacheAnalytics,
+    /// Most edited files
+    #[serde(default)]
+    pub file_analytics: FileAnalytics,
+    /// Slash commands and repeated prompts
+    #[serde(default)]
+    pub prompt_patterns: PromptPatternAnalytics,
     /// Project-based analytics
     pub project_analytics: ProjectAnalytics,
     /// Conversation quality metrics
@@ -50,6 +67,12 @@ pub struct TemporalAnalysis {
     pub conversations_per_day: BTreeMap<String, usize>, // Date string -> count
     /// Messages per day over time
     pub messages_per_day: BTreeMap<String, usize>,
+    /// Tokens (input, output and cache) per day over time
+    #[serde(default)]
+    pub tokens_per_day: BTreeMap<String, u64>,
+    /// Estimated cost in USD per day over time
+    #[serde(default)]
+    pub cost_per_day: BTreeMap<String, f64>,
     /// Usage by hour of day (0-23)
     pub usage_by_hour: HashMap<u8, usize>,
     /// Usage by day of week (0-6, Sunday = 0)
@@ -78,8 +101,17 @@ pub struct ModelAnalytics {
 pub struct ToolAnalytics {
     /// Tool usage frequency
     pub tool_usage_count: HashMap<String, usize>,
-    /// Tool success rates (if determinable)
+    /// Success rate per tool, for tools with at least one result
     pub tool_success_rates: HashMap<String, f64>,
+    /// Outcomes of tool calls per tool
+    #[serde(default)]
+    pub tool_outcomes: HashMap<String, ToolOutcomeCounts>,
+    /// Outcomes of tool calls per project
+    #[serde(default)]
+    pub project_tool_outcomes: HashMap<String, ToolOutcomeCounts>,
+    /// Outcomes of tool calls per day
+    #[serde(default)]
+    pub tool_outcomes_over_time: BTreeMap<String, ToolOutcomeCounts>,
     /// Average tools per conversation
     pub average_tools_per_conversation: f64,
     /// Tool usage trends over time
@@ -88,6 +120,158 @@ pub struct ToolAnalytics {
     pub top_tools: Vec<ToolUsage>,
 }
 
+impl ToolAnalytics {
+    /// Tools with at least `min_calls` known outcomes and some failures,
+    /// lowest success rate first
+    pub fn worst_offenders(&self, min_calls: usize) -> Vec<(&str, &ToolOutcomeCounts)> {
+        let mut offenders: Vec<_> = self
+            .tool_outcomes
+            .iter()
+            .filter(|(_, counts)| counts.total() >= min_calls && counts.failures() > 0)
+            .map(|(tool, counts)| (tool.as_str(), counts))
+            .collect();
+        offenders.sort_by(|(a_tool, a), (b_tool, b)| {
+            // Compare success rates as exact fractions
+            (a.succeeded * b.total())
+                .cmp(&(b.succeeded * a.total()))
+                .then(b.failures().cmp(&a.failures()))
+                .then(a_tool.cmp(b_tool))
+        });
+        offenders
+    }
+}
+
+/// Prompt tokens below which a conversation isn't flagged for poor cache reuse
+pub const POOR_CACHE_REUSE_MIN_PROMPT_TOKENS: u64 = 100_000;
+
+/// Cache hit ratio (percent) under which a conversation has poor cache reuse
+pub const POOR_CACHE_HIT_RATIO: f64 = 50.0;
+
+/// Prompt token usage split by how the prompt cache handled it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheUsage {
+    /// Input tokens neither read from nor written to the cache
+    pub uncached_input_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// USD saved compared with billing cache reads and writes as plain
+    /// input, for models with known pricing
+    pub estimated_savings: f64,
+}
+
+impl CacheUsage {
+    pub fn record(&mut self, model: Option<&str>, usage: &Usage) {
+        self.uncached_input_tokens += usage.input_tokens as u64;
+        self.cache_write_tokens += usage.cache_creation_input_tokens.unwrap_or(0) as u64;
+        self.cache_read_tokens += usage.cache_read_input_tokens.unwrap_or(0) as u64;
+        self.estimated_savings += model
+            .and_then(ModelPricing::for_model)
+            .map_or(0.0, |pricing| pricing.cache_savings(usage));
+    }
+
+    pub fn add(&mut self, other: &CacheUsage) {
+        self.uncached_input_tokens += other.uncached_input_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.estimated_savings += other.estimated_savings;
+    }
+
+    /// Cache usage of every message in a conversation
+    pub fn for_conversation(conversation: &Conversation) -> Self {
+        let mut cache_usage = Self::default();
+        for (message, usage) in billed_usage(&conversation.messages) {
+            cache_usage.record(message.model.as_deref(), usage);
+        }
+        cache_usage
+    }
+
+    /// All input tokens sent with prompts, cached or not
+    pub fn prompt_tokens(&self) -> u64 {
+        self.uncached_input_tokens + self.cache_write_tokens + self.cache_read_tokens
+    }
+
+    /// Percentage of prompt tokens read from the cache
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let prompt_tokens = self.prompt_tokens();
+        (prompt_tokens > 0).then(|| self.cache_read_tokens as f64 / prompt_tokens as f64 * 100.0)
+    }
+}
+
+/// A conversation that sends a lot of prompt tokens but rarely hits the cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoorCacheReuse {
+    pub session_id: String,
+    pub project_path: String,
+    pub cache_usage: CacheUsage,
+    /// Estimated cost in USD of the whole conversation
+    pub estimated_cost: f64,
+}
+
+/// Prompt cache efficiency analytics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CacheAnalytics {
+    /// Cache usage across all conversations
+    pub overall: CacheUsage,
+    /// Cache usage per conversation (session ID)
+    pub per_conversation: HashMap<String, CacheUsage>,
+    /// Cache usage per project
+    pub per_project: HashMap<String, CacheUsage>,
+    /// Cache usage per model
+    pub per_model: HashMap<String, CacheUsage>,
+    /// Cache usage per day
+    pub per_day: BTreeMap<String, CacheUsage>,
+    /// Conversations with poor cache reuse, most prompt tokens first
+    pub poor_reuse: Vec<PoorCacheReuse>,
+}
+
+/// Files kept in each hotspot list
+pub const FILE_HOTSPOT_LIMIT: usize = 20;
+
+/// Edits of one file across sessions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileHotspot {
+    pub path: String,
+    /// Edit, MultiEdit, Write and NotebookEdit calls on the file
+    pub edits: usize,
+    /// Sessions that edited the file
+    pub sessions: usize,
+    /// Edits beyond the first in each session
+    pub re_edits: usize,
+    /// Most edits of the file in a single session
+    pub max_edits_in_session: usize,
+}
+
+impl FileHotspot {
+    fn record_session(&mut self, edits: usize) {
+        self.edits += edits;
+        self.sessions += 1;
+        self.re_edits += edits.saturating_sub(1);
+        self.max_edits_in_session = self.max_edits_in_session.max(edits);
+    }
+
+    /// Average edits per session that touched the file; 1.0 means every
+    /// session got it right first time
+    pub fn churn(&self) -> f64 {
+        if self.sessions == 0 {
+            0.0
+        } else {
+            self.edits as f64 / self.sessions as f64
+        }
+    }
+}
+
+/// Where in the codebase agents make the most edits
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileAnalytics {
+    /// Distinct files edited
+    pub files_edited: usize,
+    pub total_edits: usize,
+    /// Most edited files across all projects, most edits first
+    pub top_files: Vec<FileHotspot>,
+    /// Most edited files in each project, most edits first
+    pub top_files_per_project: HashMap<String, Vec<FileHotspot>>,
+}
+
 /// Project-based analytics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectAnalytics {
@@ -166,7 +350,8 @@ pub struct ToolUsage {
     pub tool_name: String,
     pub usage_count: usize,
     pub percentage: f64,
-    pub success_rate: f64,
+    /// None when no call of the tool has a result yet
+    pub success_rate: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -179,8 +364,25 @@ pub struct ProjectUsage {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponsePatterns {
+    /// Mean time from a prompt to the first assistant reply
     pub average_response_time_minutes: Option<f64>,
+    /// Time from a prompt to the first assistant reply, in seconds
     pub response_time_distribution: Option<LengthDistribution>,
+    /// First-reply and end-of-turn latency across all turns
+    #[serde(default)]
+    pub latency: Option<LatencyStats>,
+    /// Latency per model of the first reply
+    #[serde(default)]
+    pub latency_per_model: HashMap<String, LatencyStats>,
+    /// Latency per project
+    #[serde(default)]
+    pub latency_per_project: HashMap<String, LatencyStats>,
+    /// Latency per hour of day (0-23) the prompt was sent
+    #[serde(default)]
+    pub latency_per_hour: BTreeMap<u8, LatencyStats>,
+    /// Turns left out because they waited on a permission prompt
+    #[serde(default)]
+    pub permission_wait_turns: usize,
 }
 
 impl AnalyticsEngine {
@@ -189,9 +391,18 @@ impl AnalyticsEngine {
         Self {
             conversations,
             cached_analytics: None,
+            timezone: Timezone::default(),
         }
     }
 
+    /// Bucket by day, hour and weekday in a specific time zone instead of the
+    /// local one
+    pub fn with_timezone(mut self, timezone: Timezone) -> Self {
+        self.timezone = timezone;
+        self.cached_analytics = None;
+        self
+    }
+
     /// Generate comprehensive analytics
     pub fn generate_analytics(&mut self) -> Result<&ConversationAnalytics, ClaudeToolsError> {
         if self.cached_analytics.is_none() {
@@ -213,6 +424,12 @@ impl AnalyticsEngine {
             temporal_analysis: self.compute_temporal_analysis(),
             model_analytics: self.compute_model_analytics(),
             tool_analytics: self.compute_tool_analytics(),
+            cache_analytics: self.compute_cache_analytics(),
+            file_analytics: self.compute_file_analytics(),
+            prompt_patterns: PromptPatternAnalytics::from_conversations(
+                &self.conversations,
+                &self.timezone,
+            ),
             project_analytics: self.compute_project_analytics(),
             quality_metrics: self.compute_quality_metrics(),
             generated_at: Utc::now(),
@@ -278,24 +495,33 @@ impl AnalyticsEngine {
     fn compute_temporal_analysis(&self) -> TemporalAnalysis {
         let mut conversations_per_day = BTreeMap::new();
         let mut messages_per_day = BTreeMap::new();
+        let mut tokens_per_day = BTreeMap::new();
+        let mut cost_per_day = BTreeMap::new();
         let mut usage_by_hour = HashMap::new();
         let mut usage_by_weekday = HashMap::new();
 
         for conversation in &self.conversations {
             if let Some(started_at) = conversation.started_at {
                 // Daily aggregations
-                let date_key = started_at.format("%Y-%m-%d").to_string();
+                let date_key = self.timezone.day_key(started_at);
                 *conversations_per_day.entry(date_key.clone()).or_insert(0) += 1;
                 *messages_per_day.entry(date_key).or_insert(0) += conversation.messages.len();
 
                 // Hourly aggregation
-                let hour = started_at.hour() as u8;
+                let hour = self.timezone.hour(started_at) as u8;
                 *usage_by_hour.entry(hour).or_insert(0) += 1;
 
                 // Weekday aggregation (Sunday = 0)
-                let weekday = started_at.weekday().num_days_from_sunday() as u8;
+                let weekday = self.timezone.weekday(started_at).num_days_from_sunday() as u8;
                 *usage_by_weekday.entry(weekday).or_insert(0) += 1;
             }
+
+            // Usage falls on the day of each response, not of the conversation
+            for (message, usage) in billed_usage(&conversation.messages) {
+                let date_key = self.timezone.day_key(message.timestamp);
+                *tokens_per_day.entry(date_key.clone()).or_insert(0) += usage.total_tokens();
+                *cost_per_day.entry(date_key).or_insert(0.0) += message_cost(message, usage);
+            }
         }
 
         // Calculate peak usage hours
@@ -319,6 +545,8 @@ impl AnalyticsEngine {
         TemporalAnalysis {
             conversations_per_day,
             messages_per_day,
+            tokens_per_day,
+            cost_per_day,
             usage_by_hour,
             usage_by_weekday,
             peak_usage_hours,
@@ -344,7 +572,7 @@ impl AnalyticsEngine {
 
                     // Track usage over time
                     if let Some(started_at) = conversation.started_at {
-                        let date_key = started_at.format("%Y-%m-%d").to_string();
+                        let date_key = self.timezone.day_key(started_at);
                         *model_usage_over_time
                             .entry(model.clone())
                             .or_insert_with(BTreeMap::new)
@@ -403,6 +631,9 @@ impl AnalyticsEngine {
     fn compute_tool_analytics(&self) -> ToolAnalytics {
         let mut tool_usage_count = HashMap::new();
         let mut tool_usage_over_time = HashMap::new();
+        let mut outcomes: HashMap<String, ToolOutcomeCounts> = HashMap::new();
+        let mut project_tool_outcomes: HashMap<String, ToolOutcomeCounts> = HashMap::new();
+        let mut tool_outcomes_over_time: BTreeMap<String, ToolOutcomeCounts> = BTreeMap::new();
         let mut _total_conversations_with_tools = 0;
         let mut total_tool_uses = 0;
 
@@ -417,7 +648,7 @@ impl AnalyticsEngine {
 
                     // Track usage over time
                     if let Some(started_at) = conversation.started_at {
-                        let date_key = started_at.format("%Y-%m-%d").to_string();
+                        let date_key = self.timezone.day_key(started_at);
                         *tool_usage_over_time
                             .entry(tool_use.name.clone())
                             .or_insert_with(BTreeMap::new)
@@ -430,6 +661,27 @@ impl AnalyticsEngine {
             if conversation_has_tools {
                 _total_conversations_with_tools += 1;
             }
+
+            let mut conversation_outcomes = ToolOutcomeCounts::default();
+            for (tool_use, outcome) in tool_outcomes(conversation) {
+                outcomes
+                    .entry(tool_use.name.clone())
+                    .or_default()
+                    .record(outcome);
+                conversation_outcomes.record(outcome);
+            }
+            if conversation_outcomes.total() > 0 {
+                project_tool_outcomes
+                    .entry(conversation.project_path.clone())
+                    .or_default()
+                    .add(&conversation_outcomes);
+                if let Some(started_at) = conversation.started_at {
+                    tool_outcomes_over_time
+                        .entry(self.timezone.day_key(started_at))
+                        .or_default()
+                        .add(&conversation_outcomes);
+                }
+            }
         }
 
         let average_tools_per_conversation = if self.conversations.len() > 0 {
@@ -438,10 +690,9 @@ impl AnalyticsEngine {
             0.0
         };
 
-        // For now, assume 100% success rate (we don't have failure data)
-        let tool_success_rates: HashMap<String, f64> = tool_usage_count
-            .keys()
-            .map(|tool| (tool.clone(), 100.0))
+        let tool_success_rates: HashMap<String, f64> = outcomes
+            .iter()
+            .filter_map(|(tool, counts)| Some((tool.clone(), counts.success_rate()?)))
             .collect();
 
         // Calculate top tools
@@ -455,7 +706,7 @@ impl AnalyticsEngine {
                 } else {
                     0.0
                 },
-                success_rate: 100.0, // Default success rate
+                success_rate: tool_success_rates.get(tool).copied(),
             })
             .collect();
         top_tools.sort_by(|a, b| b.usage_count.cmp(&a.usage_count));
@@ -463,12 +714,121 @@ impl AnalyticsEngine {
         ToolAnalytics {
             tool_usage_count,
             tool_success_rates,
+            tool_outcomes: outcomes,
+            project_tool_outcomes,
+            tool_outcomes_over_time,
             average_tools_per_conversation,
             tool_usage_over_time,
             top_tools,
         }
     }
 
+    /// Compute prompt cache efficiency analytics
+    fn compute_cache_analytics(&self) -> CacheAnalytics {
+        let mut analytics = CacheAnalytics::default();
+
+        for conversation in &self.conversations {
+            let mut conversation_cache = CacheUsage::default();
+            for (message, usage) in billed_usage(&conversation.messages) {
+                let model = message.model.as_deref();
+                conversation_cache.record(model, usage);
+                if let Some(model) = model {
+                    analytics
+                        .per_model
+                        .entry(model.to_string())
+                        .or_default()
+                        .record(Some(model), usage);
+                }
+                analytics
+                    .per_day
+                    .entry(self.timezone.day_key(message.timestamp))
+                    .or_default()
+                    .record(model, usage);
+            }
+            if conversation_cache.prompt_tokens() == 0 {
+                continue;
+            }
+
+            analytics.overall.add(&conversation_cache);
+            analytics
+                .per_project
+                .entry(conversation.project_path.clone())
+                .or_default()
+                .add(&conversation_cache);
+            analytics
+                .per_conversation
+                .insert(conversation.session_id.clone(), conversation_cache);
+
+            let poor_reuse = conversation_cache.prompt_tokens()
+                >= POOR_CACHE_REUSE_MIN_PROMPT_TOKENS
+                && conversation_cache
+                    .hit_ratio()
+                    .is_some_and(|ratio| ratio < POOR_CACHE_HIT_RATIO);
+            if poor_reuse {
+                analytics.poor_reuse.push(PoorCacheReuse {
+                    session_id: conversation.session_id.clone(),
+                    project_path: conversation.project_path.clone(),
+                    cache_usage: conversation_cache,
+                    estimated_cost: conversation_usage(conversation).1,
+                });
+            }
+        }
+        analytics
+            .poor_reuse
+            .sort_by_key(|session| std::cmp::Reverse(session.cache_usage.prompt_tokens()));
+
+        analytics
+    }
+
+    /// Compute file edit hotspots and churn
+    fn compute_file_analytics(&self) -> FileAnalytics {
+        let mut files: HashMap<String, FileHotspot> = HashMap::new();
+        let mut project_files: HashMap<String, HashMap<String, FileHotspot>> = HashMap::new();
+
+        for file in FileIndex::build(&self.conversations).modified_files() {
+            for session in file.sessions {
+                project_files
+                    .entry(session.project_path)
+                    .or_default()
+                    .entry(file.path.clone())
+                    .or_insert_with(|| FileHotspot {
+                        path: file.path.clone(),
+                        ..FileHotspot::default()
+                    })
+                    .record_session(session.touches);
+                files
+                    .entry(file.path.clone())
+                    .or_insert_with(|| FileHotspot {
+                        path: file.path.clone(),
+                        ..FileHotspot::default()
+                    })
+                    .record_session(session.touches);
+            }
+        }
+
+        fn most_edited(files: HashMap<String, FileHotspot>) -> Vec<FileHotspot> {
+            let mut files: Vec<FileHotspot> = files.into_values().collect();
+            files.sort_by(|a, b| {
+                b.edits
+                    .cmp(&a.edits)
+                    .then(b.re_edits.cmp(&a.re_edits))
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+            files.truncate(FILE_HOTSPOT_LIMIT);
+            files
+        }
+
+        FileAnalytics {
+            files_edited: files.len(),
+            total_edits: files.values().map(|file| file.edits).sum(),
+            top_files: most_edited(files),
+            top_files_per_project: project_files
+                .into_iter()
+                .map(|(project, files)| (project, most_edited(files)))
+                .collect(),
+        }
+    }
+
     /// Compute project-based analytics
     fn compute_project_analytics(&self) -> ProjectAnalytics {
         let mut conversations_per_project = HashMap::new();
@@ -485,7 +845,7 @@ impl AnalyticsEngine {
 
             // Track activity over time
             if let Some(started_at) = conversation.started_at {
-                let date_key = started_at.format("%Y-%m-%d").to_string();
+                let date_key = self.timezone.day_key(started_at);
                 *project_activity_over_time
                     .entry(project.clone())
                     .or_insert_with(BTreeMap::new)
@@ -584,10 +944,65 @@ impl AnalyticsEngine {
             average_turns_per_conversation,
             message_length_distribution,
             completion_rate,
-            response_patterns: ResponsePatterns {
-                average_response_time_minutes: None, // Would need timestamp analysis
-                response_time_distribution: None,
-            },
+            response_patterns: self.compute_response_patterns(),
+        }
+    }
+
+    /// Compute response latency from prompt and reply timestamps, leaving out
+    /// turns that waited on a permission prompt
+    fn compute_response_patterns(&self) -> ResponsePatterns {
+        let mut turns: Vec<(&Conversation, ResponseTurn)> = Vec::new();
+        let mut permission_wait_turns = 0;
+        for conversation in &self.conversations {
+            for turn in response_turns(conversation) {
+                if turn.waited_on_permission {
+                    permission_wait_turns += 1;
+                } else {
+                    turns.push((conversation, turn));
+                }
+            }
+        }
+
+        let mut by_model: HashMap<String, Vec<&ResponseTurn>> = HashMap::new();
+        let mut by_project: HashMap<String, Vec<&ResponseTurn>> = HashMap::new();
+        let mut by_hour: BTreeMap<u8, Vec<&ResponseTurn>> = BTreeMap::new();
+        for (conversation, turn) in &turns {
+            if let Some(model) = &turn.model {
+                by_model.entry(model.clone()).or_default().push(turn);
+            }
+            by_project
+                .entry(conversation.project_path.clone())
+                .or_default()
+                .push(turn);
+            by_hour
+                .entry(self.timezone.hour(turn.prompted_at) as u8)
+                .or_default()
+                .push(turn);
+        }
+        fn group_stats<'a, K, M: FromIterator<(K, LatencyStats)>>(
+            groups: impl IntoIterator<Item = (K, Vec<&'a ResponseTurn>)>,
+        ) -> M {
+            groups
+                .into_iter()
+                .filter_map(|(key, turns)| Some((key, LatencyStats::from_turns(turns)?)))
+                .collect()
+        }
+
+        let first_reply_seconds: Vec<usize> = turns
+            .iter()
+            .map(|(_, turn)| turn.first_reply.num_seconds().max(0) as usize)
+            .collect();
+        let latency = LatencyStats::from_turns(turns.iter().map(|(_, turn)| turn));
+
+        ResponsePatterns {
+            average_response_time_minutes: latency.map(|stats| stats.first_reply.mean / 60.0),
+            response_time_distribution: (!first_reply_seconds.is_empty())
+                .then(|| Self::calculate_length_distribution(&first_reply_seconds)),
+            latency,
+            latency_per_model: group_stats(by_model),
+            latency_per_project: group_stats(by_project),
+            latency_per_hour: group_stats(by_hour),
+            permission_wait_turns,
         }
     }
 
@@ -696,7 +1111,12 @@ impl AnalyticsEngine {
         let mut turns = 0;
         let mut last_role: Option<MessageRole> = None;
 
-        for message in &conversation.messages {
+        // Tool results continue the assistant's turn
+        for message in conversation
+            .messages
+            .iter()
+            .filter(|m| m.role != MessageRole::Tool)
+        {
             if last_role != Some(message.role.clone()) {
                 turns += 1;
                 last_role = Some(message.role.clone());
@@ -706,3 +1126,146 @@ impl AnalyticsEngine {
         turns
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::conversation::ConversationMessage;
+    use crate::claude::test_support::usage;
+    use chrono::TimeZone;
+
+    fn conversation(session_id: &str, project: &str, usages: &[(u32, u32, u32)]) -> Conversation {
+        let timestamp = Utc.with_ymd_and_hms(2024, 6, 1, 10, 0, 0).unwrap();
+        let messages = usages
+            .iter()
+            .map(|&(input, cache_write, cache_read)| {
+                ConversationMessage::assistant(timestamp)
+                    .with_content("Done")
+                    .with_model("claude-sonnet-4")
+                    .with_usage(Usage {
+                        cache_creation_input_tokens: Some(cache_write),
+                        cache_read_input_tokens: Some(cache_read),
+                        ..usage(input, 100)
+                    })
+            })
+            .collect();
+        Conversation::new_for_test(session_id, project, messages)
+    }
+
+    #[test]
+    fn test_cache_analytics() {
+        let conversations = vec![
+            // Written once, then read back on every turn
+            conversation(
+                "reused",
+                "-api",
+                &[(10, 50_000, 0), (10, 1_000, 50_000), (10, 1_000, 51_000)],
+            ),
+            // Re-sends its whole prompt every turn
+            conversation("wasteful", "-api", &[(60_000, 0, 0), (60_000, 0, 0)]),
+        ];
+        let mut engine = AnalyticsEngine::new(conversations).with_timezone(Timezone::Utc);
+        let cache = engine.generate_analytics().unwrap().cache_analytics.clone();
+
+        let reused = cache.per_conversation["reused"];
+        assert_eq!(reused.cache_read_tokens, 101_000);
+        assert_eq!(reused.cache_write_tokens, 52_000);
+        assert!(reused.hit_ratio().unwrap() > 65.0);
+        assert!(reused.estimated_savings > 0.0);
+
+        assert_eq!(cache.per_project["-api"].prompt_tokens(), 273_030);
+        assert_eq!(cache.per_model["claude-sonnet-4"].prompt_tokens(), 273_030);
+        assert_eq!(cache.per_day["2024-06-01"].prompt_tokens(), 273_030);
+
+        assert_eq!(cache.poor_reuse.len(), 1);
+        assert_eq!(cache.poor_reuse[0].session_id, "wasteful");
+        assert_eq!(cache.poor_reuse[0].cache_usage.hit_ratio(), Some(0.0));
+    }
+
+    #[test]
+    fn test_tokens_per_day_by_response_date() {
+        let evening = Utc.with_ymd_and_hms(2024, 6, 1, 23, 50, 0).unwrap();
+        let response = |minutes: i64, id: &str| {
+            ConversationMessage::assistant(evening + chrono::Duration::minutes(minutes))
+                .with_message_id(id)
+                .with_model("claude-sonnet-4")
+                .with_usage(usage(1_000, 0))
+        };
+        // The second response is split over two entries and lands after midnight
+        let conversations = vec![Conversation::new_for_test(
+            "late",
+            "-api",
+            vec![
+                response(0, "msg_1"),
+                response(20, "msg_2"),
+                response(20, "msg_2"),
+            ],
+        )];
+        let mut engine = AnalyticsEngine::new(conversations).with_timezone(Timezone::Utc);
+        let temporal = engine
+            .generate_analytics()
+            .unwrap()
+            .temporal_analysis
+            .clone();
+
+        assert_eq!(temporal.tokens_per_day["2024-06-01"], 1_000);
+        assert_eq!(temporal.tokens_per_day["2024-06-02"], 1_000);
+        assert_eq!(temporal.conversations_per_day["2024-06-01"], 1);
+    }
+
+    fn editing_conversation(
+        session_id: &str,
+        project: &str,
+        edits: &[(&str, &str)],
+    ) -> Conversation {
+        let timestamp = Utc.with_ymd_and_hms(2024, 6, 1, 10, 0, 0).unwrap();
+        let message = edits.iter().enumerate().fold(
+            ConversationMessage::assistant(timestamp),
+            |message, (i, (tool, path))| {
+                message.with_tool_use(
+                    &format!("{}-{}", session_id, i),
+                    tool,
+                    serde_json::json!({ "file_path": path }),
+                )
+            },
+        );
+        Conversation::new_for_test(session_id, project, vec![message]).with_cwd("/repo")
+    }
+
+    #[test]
+    fn test_file_hotspots() {
+        let conversations = vec![
+            editing_conversation(
+                "s1",
+                "-repo",
+                &[
+                    ("Read", "src/parser.rs"),
+                    ("Edit", "src/parser.rs"),
+                    ("MultiEdit", "/repo/src/parser.rs"),
+                    ("Edit", "src/parser.rs"),
+                    ("Write", "src/lib.rs"),
+                ],
+            ),
+            editing_conversation("s2", "-repo", &[("Edit", "src/parser.rs")]),
+            editing_conversation("s3", "-other", &[("Edit", "README.md")]),
+        ];
+        let mut engine = AnalyticsEngine::new(conversations);
+        let files = engine.generate_analytics().unwrap().file_analytics.clone();
+
+        assert_eq!(files.files_edited, 3);
+        assert_eq!(files.total_edits, 6);
+
+        let parser = &files.top_files[0];
+        assert_eq!(parser.path, "/repo/src/parser.rs");
+        assert_eq!(parser.edits, 4);
+        assert_eq!(parser.sessions, 2);
+        assert_eq!(parser.re_edits, 2);
+        assert_eq!(parser.max_edits_in_session, 3);
+        assert_eq!(parser.churn(), 2.0);
+
+        let repo_files = &files.top_files_per_project["-repo"];
+        assert_eq!(repo_files.len(), 2);
+        assert_eq!(repo_files[1].path, "/repo/src/lib.rs");
+        assert_eq!(files.top_files_per_project["-other"][0].edits, 1);
+    }
+}
@@ -0,0 +1,255 @@
+use chrono::{DateTime, Datelike, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::conversation::Conversation;
+use super::pricing::{billed_usage, format_tokens, message_cost};
+use super::timezone::Timezone;
+
+/// What a budget limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetMetric {
+    /// Input, output and cache tokens
+    Tokens,
+    /// Estimated cost in USD
+    Cost,
+}
+
+impl BudgetMetric {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Tokens => "tokens",
+            Self::Cost => "cost",
+        }
+    }
+
+    /// Format an amount with its unit
+    pub fn format_amount(&self, amount: f64) -> String {
+        match self {
+            Self::Tokens => format_tokens(amount as u64),
+            Self::Cost => format!("${:.2}", amount),
+        }
+    }
+}
+
+/// The period a budget resets over, in the configured time zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    /// Since midnight today
+    Day,
+    /// Since Monday of this ISO week
+    Week,
+    /// Since the first of this month
+    Month,
+    /// All recorded usage, e.g. for a project's total budget
+    Total,
+}
+
+impl BudgetPeriod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Day => "daily",
+            Self::Week => "weekly",
+            Self::Month => "monthly",
+            Self::Total => "total",
+        }
+    }
+
+    /// Start of the period containing `now`, or `None` for all time
+    pub fn start(&self, now: DateTime<Utc>, timezone: &Timezone) -> Option<DateTime<Utc>> {
+        let today = timezone.date(now);
+        let first_day = match self {
+            Self::Day => today,
+            Self::Week => today - Duration::days(today.weekday().num_days_from_monday() as i64),
+            Self::Month => today.with_day(1)?,
+            Self::Total => return None,
+        };
+        Some(timezone.start_of_day(first_day))
+    }
+}
+
+/// A limit on tokens or estimated cost over a period, optionally for one project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Budget {
+    /// Name shown in reports; described from the other fields when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub metric: BudgetMetric,
+    pub period: BudgetPeriod,
+    /// Only count projects whose path contains this text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Tokens, or USD for cost budgets
+    pub limit: f64,
+}
+
+impl Budget {
+    /// The configured name, or e.g. `daily cost` / `weekly tokens (api)`
+    pub fn label(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        let label = format!("{} {}", self.period.label(), self.metric.label());
+        match &self.project {
+            Some(project) => format!("{} ({})", label, project),
+            None => label,
+        }
+    }
+
+    /// Tokens or estimated cost used since `since` in matching projects
+    pub fn spent<'a>(
+        &self,
+        conversations: impl IntoIterator<Item = &'a Conversation>,
+        since: Option<DateTime<Utc>>,
+    ) -> f64 {
+        let messages = conversations
+            .into_iter()
+            .filter(|conv| {
+                self.project
+                    .as_deref()
+                    .is_none_or(|project| conv.project_path.contains(project))
+            })
+            .flat_map(|conv| &conv.messages)
+            .filter(|message| since.is_none_or(|since| message.timestamp >= since));
+        billed_usage(messages)
+            .map(|(message, usage)| match self.metric {
+                BudgetMetric::Tokens => usage.total_tokens() as f64,
+                BudgetMetric::Cost => message_cost(message, usage),
+            })
+            .sum()
+    }
+}
+
+/// Spend against one budget in its current period
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetStatus {
+    pub budget: Budget,
+    /// Start of the current period, `None` for total budgets
+    pub period_start: Option<DateTime<Utc>>,
+    pub spent: f64,
+}
+
+impl BudgetStatus {
+    /// Spend in the period containing `now` for each budget
+    pub fn evaluate(
+        budgets: &[Budget],
+        conversations: &[Conversation],
+        now: DateTime<Utc>,
+        timezone: &Timezone,
+    ) -> Vec<Self> {
+        budgets
+            .iter()
+            .map(|budget| {
+                let period_start = budget.period.start(now, timezone);
+                Self {
+                    spent: budget.spent(conversations, period_start),
+                    budget: budget.clone(),
+                    period_start,
+                }
+            })
+            .collect()
+    }
+
+    /// Spend as a percentage of the limit
+    pub fn percent_used(&self) -> f64 {
+        if self.budget.limit > 0.0 {
+            self.spent / self.budget.limit * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    pub fn is_exceeded(&self) -> bool {
+        self.spent > self.budget.limit
+    }
+
+    /// Spend against the limit, e.g. `$4.20 of $5.00 (84%)`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} of {} ({:.0}%)",
+            self.budget.metric.format_amount(self.spent),
+            self.budget.metric.format_amount(self.budget.limit),
+            self.percent_used()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::conversation::ConversationMessage;
+    use crate::claude::test_support::usage;
+    use chrono::TimeZone;
+
+    fn conversation(project: &str, timestamps: &[DateTime<Utc>]) -> Conversation {
+        let messages = timestamps
+            .iter()
+            .map(|&timestamp| {
+                ConversationMessage::assistant(timestamp)
+                    .with_model("claude-sonnet-4")
+                    .with_usage(usage(100_000, 0))
+            })
+            .collect();
+        Conversation::new_for_test(&format!("{}-session", project), project, messages)
+    }
+
+    #[test]
+    fn test_period_start() {
+        // Wednesday
+        let now = Utc.with_ymd_and_hms(2024, 6, 12, 15, 30, 0).unwrap();
+        let start = |period: BudgetPeriod| period.start(now, &Timezone::Utc);
+        assert_eq!(
+            start(BudgetPeriod::Day),
+            Some(Utc.with_ymd_and_hms(2024, 6, 12, 0, 0, 0).unwrap())
+        );
+        assert_eq!(
+            start(BudgetPeriod::Week),
+            Some(Utc.with_ymd_and_hms(2024, 6, 10, 0, 0, 0).unwrap())
+        );
+        assert_eq!(
+            start(BudgetPeriod::Month),
+            Some(Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(start(BudgetPeriod::Total), None);
+    }
+
+    #[test]
+    fn test_evaluate_budgets() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 12, 15, 0, 0).unwrap();
+        let conversations = vec![
+            conversation("-api", &[now - Duration::hours(2), now - Duration::days(1)]),
+            conversation("-web", &[now - Duration::hours(1)]),
+        ];
+        let budgets = vec![
+            Budget {
+                name: None,
+                metric: BudgetMetric::Tokens,
+                period: BudgetPeriod::Day,
+                project: None,
+                limit: 150_000.0,
+            },
+            Budget {
+                name: Some("api spend".to_string()),
+                metric: BudgetMetric::Cost,
+                period: BudgetPeriod::Week,
+                project: Some("api".to_string()),
+                limit: 1.0,
+            },
+        ];
+
+        let statuses = BudgetStatus::evaluate(&budgets, &conversations, now, &Timezone::Utc);
+        assert_eq!(statuses[0].budget.label(), "daily tokens");
+        assert_eq!(statuses[0].spent, 200_000.0);
+        assert!(statuses[0].is_exceeded());
+        assert_eq!(
+            statuses[0].summary(),
+            "200.0k tokens of 150.0k tokens (133%)"
+        );
+
+        // Two sonnet messages this week at $0.30 each
+        assert_eq!(statuses[1].budget.label(), "api spend");
+        assert!((statuses[1].spent - 0.6).abs() < 1e-9);
+        assert!(!statuses[1].is_exceeded());
+    }
+}
@@ -45,7 +45,7 @@ pub struct TimelineCache {
 
 impl TimelineCache {
     /// Current cache format version
-    const CACHE_VERSION: u32 = 1;
+    const CACHE_VERSION: u32 = 2;
 
     /// Cache directory name within Claude directory
     const CACHE_DIR_NAME: &'static str = "timeline_cache";
@@ -87,12 +87,37 @@ impl TimelineCache {
                 start.timestamp().hash(&mut hasher);
                 end.timestamp().hash(&mut hasher);
             }
+            // Relative calendar periods resolve to different weeks/months over
+            // time, so key them by the period they currently start at
+            TimePeriod::ThisWeek
+            | TimePeriod::PreviousWeek
+            | TimePeriod::ThisMonth
+            | TimePeriod::PreviousMonth => {
+                config.period.label(&config.timezone).hash(&mut hasher);
+                config
+                    .period
+                    .start_time(&config.timezone)
+                    .timestamp()
+                    .hash(&mut hasher);
+            }
+            TimePeriod::IsoWeek { year, week } => {
+                "iso_week".hash(&mut hasher);
+                year.hash(&mut hasher);
+                week.hash(&mut hasher);
+            }
+            TimePeriod::Quarter { year, quarter } => {
+                "quarter".hash(&mut hasher);
+                year.hash(&mut hasher);
+                quarter.hash(&mut hasher);
+            }
         }
 
         // Hash other config parameters
         config.summary_depth.hash(&mut hasher);
         config.max_conversations_per_project.hash(&mut hasher);
         config.include_empty_projects.hash(&mut hasher);
+        // Day keys and peak hours depend on the zone they were bucketed in
+        config.timezone.hash(&mut hasher);
 
         hasher.finish()
     }
@@ -235,6 +260,13 @@ impl TimelineCache {
             TimePeriod::LastWeek => vec![TimePeriod::LastMonth],
             TimePeriod::LastMonth => vec![], // Already the longest standard period
             TimePeriod::Custom { .. } => vec![], // Don't try to filter custom periods
+            // Recent calendar periods may fit inside a cached rolling period;
+            // filter_to_period rejects the ones that don't
+            TimePeriod::ThisWeek => vec![TimePeriod::LastWeek, TimePeriod::LastMonth],
+            TimePeriod::PreviousWeek | TimePeriod::ThisMonth | TimePeriod::PreviousMonth => {
+                vec![TimePeriod::LastMonth]
+            }
+            TimePeriod::IsoWeek { .. } | TimePeriod::Quarter { .. } => vec![],
         }
     }
 
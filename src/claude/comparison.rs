@@ -0,0 +1,327 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::timeline::{ActivityTimeline, ProjectActivity, TimePeriod};
+use super::timezone::Timezone;
+
+/// Topics compared per project
+const COMPARED_TOPICS: usize = 5;
+
+/// A count in the previous and current period
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CountDelta {
+    pub previous: u64,
+    pub current: u64,
+}
+
+impl CountDelta {
+    pub fn new(previous: u64, current: u64) -> Self {
+        Self { previous, current }
+    }
+
+    pub fn change(&self) -> i64 {
+        self.current as i64 - self.previous as i64
+    }
+
+    /// Relative change, if there was activity in the previous period
+    pub fn percent_change(&self) -> Option<f64> {
+        (self.previous > 0).then(|| self.change() as f64 / self.previous as f64 * 100.0)
+    }
+
+    /// Change as e.g. `+12 (+30%)`, `-4 (-50%)`, `+3 (new)` or `0`
+    pub fn change_label(&self) -> String {
+        let change = self.change();
+        if change == 0 {
+            return "0".to_string();
+        }
+        match self.percent_change() {
+            Some(percent) => format!("{:+} ({:+.0}%)", change, percent),
+            None => format!("{:+} (new)", change),
+        }
+    }
+}
+
+/// How a project's activity changed between the periods
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectChange {
+    /// Active now, quiet in the previous period
+    Appeared,
+    /// Active in both periods
+    Continued,
+    /// Active in the previous period, quiet now
+    WentQuiet,
+}
+
+impl ProjectChange {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Appeared => "new",
+            Self::Continued => "active",
+            Self::WentQuiet => "quiet",
+        }
+    }
+}
+
+/// One project in both periods
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectComparison {
+    pub project_path: String,
+    pub change: ProjectChange,
+    pub conversations: CountDelta,
+    pub messages: CountDelta,
+    pub tokens: CountDelta,
+    pub tool_calls: CountDelta,
+    /// Top topics now that weren't top topics before
+    pub new_topics: Vec<String>,
+    /// Top topics before that aren't top topics now
+    pub dropped_topics: Vec<String>,
+}
+
+/// Uses of one tool in both periods
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolComparison {
+    pub name: String,
+    pub uses: CountDelta,
+}
+
+/// Differences between a timeline and the one for the period before it
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineComparison {
+    pub current_period: TimePeriod,
+    pub previous_period: TimePeriod,
+    /// Zone both periods are resolved in
+    pub timezone: Timezone,
+    pub active_projects: CountDelta,
+    pub conversations: CountDelta,
+    pub messages: CountDelta,
+    pub tokens: CountDelta,
+    pub tool_calls: CountDelta,
+    /// Projects ordered by the size of their message change, largest first
+    pub projects: Vec<ProjectComparison>,
+    /// Tools ordered by the size of their change, largest first
+    pub tools: Vec<ToolComparison>,
+}
+
+impl TimelineComparison {
+    pub fn between(current: &ActivityTimeline, previous: &ActivityTimeline) -> Self {
+        let project_paths: BTreeSet<&String> = current
+            .projects
+            .keys()
+            .chain(previous.projects.keys())
+            .collect();
+
+        let mut projects: Vec<ProjectComparison> = project_paths
+            .into_iter()
+            .filter_map(|path| {
+                compare_projects(
+                    path,
+                    current.projects.get(path).filter(|p| is_active(p)),
+                    previous.projects.get(path).filter(|p| is_active(p)),
+                )
+            })
+            .collect();
+        projects.sort_by_key(|project| std::cmp::Reverse(project.messages.change().abs()));
+
+        let current_tools = tool_usage(current);
+        let previous_tools = tool_usage(previous);
+        let mut tools: Vec<ToolComparison> = current_tools
+            .keys()
+            .chain(previous_tools.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|name| ToolComparison {
+                name: name.to_string(),
+                uses: CountDelta::new(
+                    previous_tools.get(name).copied().unwrap_or(0),
+                    current_tools.get(name).copied().unwrap_or(0),
+                ),
+            })
+            .collect();
+        tools.sort_by_key(|tool| std::cmp::Reverse(tool.uses.change().abs()));
+
+        let total = |select: fn(&ProjectComparison) -> CountDelta| {
+            projects
+                .iter()
+                .map(select)
+                .fold(CountDelta::default(), |sum, delta| {
+                    CountDelta::new(sum.previous + delta.previous, sum.current + delta.current)
+                })
+        };
+        let active_projects = CountDelta::new(
+            projects
+                .iter()
+                .filter(|p| p.change != ProjectChange::Appeared)
+                .count() as u64,
+            projects
+                .iter()
+                .filter(|p| p.change != ProjectChange::WentQuiet)
+                .count() as u64,
+        );
+
+        Self {
+            current_period: current.config.period,
+            previous_period: previous.config.period,
+            timezone: current.config.timezone,
+            active_projects,
+            conversations: total(|p| p.conversations),
+            messages: total(|p| p.messages),
+            tokens: total(|p| p.tokens),
+            tool_calls: total(|p| p.tool_calls),
+            projects,
+            tools,
+        }
+    }
+}
+
+fn is_active(project: &ProjectActivity) -> bool {
+    !project.conversations.is_empty()
+}
+
+fn compare_projects(
+    path: &str,
+    current: Option<&ProjectActivity>,
+    previous: Option<&ProjectActivity>,
+) -> Option<ProjectComparison> {
+    let change = match (current, previous) {
+        (Some(_), Some(_)) => ProjectChange::Continued,
+        (Some(_), None) => ProjectChange::Appeared,
+        (None, Some(_)) => ProjectChange::WentQuiet,
+        (None, None) => return None,
+    };
+
+    let delta = |count: fn(&ProjectActivity) -> u64| {
+        CountDelta::new(previous.map_or(0, count), current.map_or(0, count))
+    };
+    let topics = |project: Option<&ProjectActivity>| -> Vec<String> {
+        project
+            .map(|p| {
+                p.topical_summary
+                    .main_topics
+                    .iter()
+                    .take(COMPARED_TOPICS)
+                    .map(|topic| topic.name.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let current_topics = topics(current);
+    let previous_topics = topics(previous);
+
+    Some(ProjectComparison {
+        project_path: path.to_string(),
+        change,
+        conversations: delta(|p| p.conversations.len() as u64),
+        messages: delta(|p| p.stats.total_messages as u64),
+        tokens: delta(|p| p.stats.total_tokens),
+        tool_calls: delta(|p| {
+            p.conversations
+                .iter()
+                .map(|conv| conv.tool_usage_count as u64)
+                .sum()
+        }),
+        new_topics: current_topics
+            .iter()
+            .filter(|topic| !previous_topics.contains(topic))
+            .cloned()
+            .collect(),
+        dropped_topics: previous_topics
+            .iter()
+            .filter(|topic| !current_topics.contains(topic))
+            .cloned()
+            .collect(),
+    })
+}
+
+/// Uses of each tool across all projects
+fn tool_usage(timeline: &ActivityTimeline) -> BTreeMap<&str, u64> {
+    let mut usage = BTreeMap::new();
+    for project in timeline.projects.values() {
+        for (tool, count) in &project.stats.tool_usage {
+            *usage.entry(tool.as_str()).or_insert(0) += *count as u64;
+        }
+    }
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::conversation::{Conversation, ConversationMessage};
+    use crate::claude::timeline::{SummaryDepth, TimelineConfig};
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn conversation(session_id: &str, project: &str, day: u32, tools: &[&str]) -> Conversation {
+        let timestamp = Utc.with_ymd_and_hms(2024, 6, day, 10, 0, 0).unwrap();
+        let assistant = tools.iter().fold(
+            ConversationMessage::assistant(timestamp + Duration::minutes(1)).with_content("Done"),
+            |message, name| {
+                message.with_tool_use(
+                    &format!("{}-{}", session_id, name),
+                    name,
+                    serde_json::Value::Null,
+                )
+            },
+        );
+        let messages = vec![
+            ConversationMessage::user(timestamp).with_content("Refactor the parser"),
+            assistant,
+        ];
+        Conversation::new_for_test(session_id, project, messages)
+    }
+
+    fn timeline(conversations: Vec<Conversation>, week: u32) -> ActivityTimeline {
+        ActivityTimeline::create_filtered_timeline(
+            conversations,
+            TimelineConfig {
+                period: TimePeriod::IsoWeek { year: 2024, week },
+                summary_depth: SummaryDepth::Brief,
+                max_conversations_per_project: None,
+                include_empty_projects: false,
+                timezone: Timezone::Utc,
+            },
+        )
+    }
+
+    #[test]
+    fn test_count_delta_labels() {
+        assert_eq!(CountDelta::new(10, 13).change_label(), "+3 (+30%)");
+        assert_eq!(CountDelta::new(4, 2).change_label(), "-2 (-50%)");
+        assert_eq!(CountDelta::new(0, 3).change_label(), "+3 (new)");
+        assert_eq!(CountDelta::new(5, 5).change_label(), "0");
+    }
+
+    #[test]
+    fn test_compare_consecutive_weeks() {
+        // Week 22 is May 27 - June 2, week 23 is June 3 - 9
+        let conversations = vec![
+            conversation("old-1", "-api", 1, &["Read"]),
+            conversation("old-2", "-docs", 2, &["Edit"]),
+            conversation("new-1", "-api", 4, &["Read", "Bash"]),
+            conversation("new-2", "-api", 5, &["Bash"]),
+            conversation("new-3", "-cli", 6, &[]),
+        ];
+        let previous = timeline(conversations.clone(), 22);
+        let current = timeline(conversations, 23);
+
+        let comparison = TimelineComparison::between(&current, &previous);
+        assert_eq!(comparison.active_projects, CountDelta::new(2, 2));
+        assert_eq!(comparison.conversations, CountDelta::new(2, 3));
+        assert_eq!(comparison.tool_calls, CountDelta::new(2, 3));
+
+        let change = |path: &str| {
+            comparison
+                .projects
+                .iter()
+                .find(|p| p.project_path == path)
+                .map(|p| p.change)
+        };
+        assert_eq!(change("-api"), Some(ProjectChange::Continued));
+        assert_eq!(change("-cli"), Some(ProjectChange::Appeared));
+        assert_eq!(change("-docs"), Some(ProjectChange::WentQuiet));
+
+        let bash = comparison.tools.iter().find(|t| t.name == "Bash").unwrap();
+        assert_eq!(bash.uses, CountDelta::new(0, 2));
+        assert_eq!(comparison.tools[0].name, "Bash");
+    }
+}
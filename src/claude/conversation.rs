@@ -8,6 +8,9 @@ pub struct Conversation {
     pub session_id: String,
     /// Project path where the conversation took place
     pub project_path: String,
+    /// Working directory the session ran in (if recorded)
+    #[serde(default)]
+    pub cwd: Option<String>,
     /// Summary of the conversation (if available)
     pub summary: Option<String>,
     /// All messages in the conversation
@@ -92,8 +95,18 @@ pub enum MessageContent {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentBlock {
-    Text { text: String },
-    Image { source: ImageSource },
+    Text {
+        text: String,
+    },
+    Image {
+        source: ImageSource,
+    },
+    ToolResult {
+        tool_use_id: String,
+        #[serde(default)]
+        content: serde_json::Value,
+        is_error: Option<bool>,
+    },
 }
 
 /// Assistant content blocks
@@ -120,7 +133,7 @@ pub struct ImageSource {
 }
 
 /// Token usage information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Usage {
     pub input_tokens: u32,
     pub cache_creation_input_tokens: Option<u32>,
@@ -139,6 +152,15 @@ pub struct ConversationMessage {
     pub timestamp: DateTime<Utc>,
     pub model: Option<String>,
     pub tool_uses: Vec<ToolUse>,
+    #[serde(default)]
+    pub tool_results: Vec<ToolResult>,
+    /// Token usage reported for assistant messages
+    #[serde(default)]
+    pub usage: Option<Usage>,
+    /// API response id of assistant messages; a response split over several
+    /// entries repeats its id and usage in each
+    #[serde(default)]
+    pub message_id: Option<String>,
 }
 
 /// Role of the message sender
@@ -147,6 +169,9 @@ pub enum MessageRole {
     User,
     Assistant,
     System,
+    /// Tool results sent back to the assistant; recorded as user entries
+    /// but not written by the user
+    Tool,
 }
 
 /// Tool use information
@@ -157,6 +182,82 @@ pub struct ToolUse {
     pub input: serde_json::Value,
 }
 
+/// Tool result information (returned to the assistant in a user message)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolResult {
+    pub tool_use_id: String,
+    pub content: String,
+    pub is_error: bool,
+}
+
+/// Tool input fields that carry searchable text, in display order
+pub const SEARCHABLE_TOOL_INPUT_FIELDS: [&str; 9] = [
+    "command",
+    "file_path",
+    "notebook_path",
+    "path",
+    "pattern",
+    "old_string",
+    "new_string",
+    "url",
+    "query",
+];
+
+impl ToolUse {
+    /// Searchable text for this tool call: the tool name followed by one
+    /// `field: value` line per searchable input field
+    pub fn searchable_text(&self) -> String {
+        let mut text = self.name.clone();
+        for field in SEARCHABLE_TOOL_INPUT_FIELDS {
+            if let Some(value) = self.input.get(field).and_then(|v| v.as_str()) {
+                text.push('\n');
+                text.push_str(field);
+                text.push_str(": ");
+                text.push_str(value);
+            }
+        }
+        text
+    }
+}
+
+impl ToolResult {
+    /// Extract the text of a tool result, which is either a string or a list of content blocks
+    fn text_from_value(content: &serde_json::Value) -> String {
+        match content {
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Array(blocks) => blocks
+                .iter()
+                .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+
+    /// First non-empty line of the result, marked when the tool failed
+    pub fn summary(&self) -> String {
+        let first_line = self
+            .content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("(no output)");
+        if self.is_error {
+            format!("{} (error)", first_line)
+        } else {
+            first_line.to_string()
+        }
+    }
+}
+
+impl ConversationMessage {
+    /// Whether this is a prompt typed by the user, rather than a user message
+    /// carrying tool results
+    pub fn is_user_prompt(&self) -> bool {
+        self.role == MessageRole::User && self.tool_results.is_empty()
+    }
+}
+
 impl Conversation {
     /// Create a new conversation from a vector of entries
     pub fn from_entries(
@@ -168,6 +269,7 @@ impl Conversation {
         let mut summary = None;
         let mut started_at = None;
         let mut last_updated = None;
+        let mut session_cwd = None;
 
         for entry in entries {
             match entry {
@@ -179,33 +281,59 @@ impl Conversation {
                     parent_uuid,
                     message,
                     timestamp,
+                    cwd,
                     ..
                 } => {
+                    if session_cwd.is_none() {
+                        session_cwd = cwd;
+                    }
                     if started_at.is_none() {
                         started_at = Some(timestamp);
                     }
                     last_updated = Some(timestamp);
 
+                    let mut tool_results = Vec::new();
                     let content = match message.content {
                         MessageContent::Text(text) => text,
                         MessageContent::Blocks(blocks) => blocks
                             .into_iter()
                             .filter_map(|block| match block {
                                 ContentBlock::Text { text } => Some(text),
+                                ContentBlock::ToolResult {
+                                    tool_use_id,
+                                    content,
+                                    is_error,
+                                } => {
+                                    tool_results.push(ToolResult {
+                                        tool_use_id,
+                                        content: ToolResult::text_from_value(&content),
+                                        is_error: is_error.unwrap_or(false),
+                                    });
+                                    None
+                                }
                                 _ => None,
                             })
                             .collect::<Vec<_>>()
                             .join("\n"),
                     };
 
+                    let role = if content.trim().is_empty() && !tool_results.is_empty() {
+                        MessageRole::Tool
+                    } else {
+                        MessageRole::User
+                    };
+
                     messages.push(ConversationMessage {
                         uuid,
                         parent_uuid,
-                        role: MessageRole::User,
+                        role,
                         content,
                         timestamp,
                         model: None,
                         tool_uses: vec![],
+                        tool_results,
+                        usage: None,
+                        message_id: None,
                     });
                 }
                 ConversationEntry::Assistant {
@@ -213,8 +341,12 @@ impl Conversation {
                     parent_uuid,
                     message,
                     timestamp,
+                    cwd,
                     ..
                 } => {
+                    if session_cwd.is_none() {
+                        session_cwd = cwd;
+                    }
                     last_updated = Some(timestamp);
 
                     let mut content = String::new();
@@ -242,6 +374,9 @@ impl Conversation {
                         timestamp,
                         model: message.model,
                         tool_uses,
+                        tool_results: vec![],
+                        usage: message.usage,
+                        message_id: Some(message.id),
                     });
                 }
             }
@@ -250,6 +385,7 @@ impl Conversation {
         Conversation {
             session_id,
             project_path,
+            cwd: session_cwd,
             summary,
             messages,
             started_at,
@@ -257,7 +393,7 @@ impl Conversation {
         }
     }
 
-    /// Get the duration of the conversation
+    /// Get the duration of the conversation (wall-clock, including idle gaps)
     pub fn duration(&self) -> Option<chrono::Duration> {
         match (self.started_at, self.last_updated) {
             (Some(start), Some(end)) => Some(end - start),
@@ -265,6 +401,15 @@ impl Conversation {
         }
     }
 
+    /// Time actively spent in the conversation, leaving out any gap between
+    /// messages longer than `idle_threshold`
+    pub fn active_duration(&self, idle_threshold: chrono::Duration) -> chrono::Duration {
+        super::work_sessions::detect_work_sessions([self], idle_threshold)
+            .iter()
+            .map(|session| session.duration())
+            .sum()
+    }
+
     /// Get the number of user messages
     pub fn user_message_count(&self) -> usize {
         self.messages
@@ -281,3 +426,31 @@ impl Conversation {
             .count()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_results_are_not_user_messages() {
+        let entries: Vec<ConversationEntry> = [
+            r#"{"type":"user","sessionId":"s1","uuid":"u1","parentUuid":null,"timestamp":"2024-06-01T10:00:00Z","message":{"role":"user","content":"Run the tests"}}"#,
+            r#"{"type":"assistant","sessionId":"s1","uuid":"a1","parentUuid":"u1","timestamp":"2024-06-01T10:00:05Z","message":{"id":"msg_1","type":"message","role":"assistant","model":"claude-sonnet-4","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo test"}}],"stop_reason":null,"stop_sequence":null}}"#,
+            r#"{"type":"user","sessionId":"s1","uuid":"u2","parentUuid":"a1","timestamp":"2024-06-01T10:00:30Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"test result: ok","is_error":false}]}}"#,
+            r#"{"type":"assistant","sessionId":"s1","uuid":"a2","parentUuid":"u2","timestamp":"2024-06-01T10:00:35Z","message":{"id":"msg_2","type":"message","role":"assistant","model":"claude-sonnet-4","content":[{"type":"text","text":"All green"}],"stop_reason":null,"stop_sequence":null}}"#,
+        ]
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+        let conversation =
+            Conversation::from_entries("s1".to_string(), "-repo".to_string(), entries);
+        assert_eq!(conversation.messages.len(), 4);
+        assert_eq!(conversation.user_message_count(), 1);
+        assert_eq!(conversation.assistant_message_count(), 2);
+
+        let tool_message = &conversation.messages[2];
+        assert_eq!(tool_message.role, MessageRole::Tool);
+        assert_eq!(tool_message.tool_results[0].summary(), "test result: ok");
+    }
+}
@@ -0,0 +1,424 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+use super::conversation::{Conversation, ConversationMessage};
+use super::file_index::FileIndex;
+use super::timeline::{ActivityTimeline, TimePeriod};
+use super::timezone::Timezone;
+
+/// Longest command or error excerpt shown in a digest
+const MAX_EXCERPT_CHARS: usize = 120;
+
+/// A tool call that returned an error
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedToolCall {
+    pub session_id: String,
+    pub tool: String,
+    /// The command or file the call was about, if any
+    pub target: Option<String>,
+    /// First line of the error output
+    pub error: String,
+}
+
+/// A todo item not yet completed in a conversation's latest todo list
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenTodo {
+    pub session_id: String,
+    pub content: String,
+    pub status: String,
+}
+
+/// What happened in one project during the digest period
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectDigest {
+    pub project: String,
+    /// Topical summary text from the timeline
+    pub summary: String,
+    pub topics: Vec<String>,
+    /// (session ID, title or content summary) per conversation
+    pub conversations: Vec<(String, String)>,
+    /// (path, modified) for every file touched
+    pub files_touched: Vec<(String, bool)>,
+    pub commands: Vec<String>,
+    pub failed_tool_calls: Vec<FailedToolCall>,
+    pub open_todos: Vec<OpenTodo>,
+}
+
+/// A standup digest: per-project activity for a period, rendered as Markdown
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    pub period: String,
+    pub generated_at: DateTime<Utc>,
+    /// Zone the period and generation time are shown in
+    pub timezone: Timezone,
+    /// Projects, most active first
+    pub projects: Vec<ProjectDigest>,
+}
+
+impl Digest {
+    /// Build a digest from a timeline and the conversations it was generated from.
+    /// Only messages within the timeline period count towards commands, files,
+    /// failures and todos.
+    pub fn build(timeline: &ActivityTimeline, conversations: &[Conversation]) -> Self {
+        let period = &timeline.config.period;
+        let tz = &timeline.config.timezone;
+
+        let projects = timeline
+            .projects_by_activity()
+            .into_iter()
+            .filter(|project| !project.conversations.is_empty())
+            .map(|project| {
+                let project_conversations: Vec<&Conversation> = project
+                    .conversations
+                    .iter()
+                    .filter_map(|summary| {
+                        conversations
+                            .iter()
+                            .find(|conv| conv.session_id == summary.session_id)
+                    })
+                    .collect();
+
+                let name = project_conversations
+                    .iter()
+                    .find_map(|conv| conv.cwd.clone())
+                    .unwrap_or_else(|| project.project_path.clone());
+
+                ProjectDigest {
+                    project: name,
+                    summary: project.topical_summary.summary_text.clone(),
+                    topics: project
+                        .topical_summary
+                        .main_topics
+                        .iter()
+                        .take(5)
+                        .map(|topic| topic.name.clone())
+                        .collect(),
+                    conversations: project
+                        .conversations
+                        .iter()
+                        .map(|summary| {
+                            let title = summary
+                                .title
+                                .clone()
+                                .unwrap_or_else(|| summary.content_summary.clone());
+                            (summary.session_id.clone(), title)
+                        })
+                        .collect(),
+                    files_touched: files_touched(&project_conversations, period, tz),
+                    commands: commands_run(&project_conversations, period, tz),
+                    failed_tool_calls: failed_tool_calls(&project_conversations, period, tz),
+                    open_todos: open_todos(&project_conversations, period, tz),
+                }
+            })
+            .collect();
+
+        Self {
+            period: period.label(tz),
+            timezone: *tz,
+            generated_at: timeline.generated_at,
+            projects,
+        }
+    }
+
+    /// Render the digest as Markdown
+    pub fn to_markdown(&self) -> String {
+        let timezone = self.timezone;
+        let mut md = format!("# Standup Digest: {}\n\n", self.period);
+        let conversation_count: usize = self.projects.iter().map(|p| p.conversations.len()).sum();
+        md.push_str(&format!(
+            "_Generated {} {}: {} conversation(s) across {} project(s)_\n\n",
+            timezone.format(self.generated_at, "%Y-%m-%d %H:%M"),
+            timezone,
+            conversation_count,
+            self.projects.len()
+        ));
+
+        if self.projects.is_empty() {
+            md.push_str("No activity in this period.\n");
+            return md;
+        }
+
+        for project in &self.projects {
+            md.push_str(&format!("## {}\n\n", project.project));
+            md.push_str(&format!("{}\n\n", project.summary));
+            if !project.topics.is_empty() {
+                md.push_str(&format!("**Topics:** {}\n\n", project.topics.join(", ")));
+            }
+
+            md.push_str("### Conversations\n\n");
+            for (session_id, title) in &project.conversations {
+                md.push_str(&format!("- {} (`{}`)\n", title, session_id));
+            }
+            md.push('\n');
+
+            if !project.files_touched.is_empty() {
+                md.push_str("### Files Touched\n\n");
+                for (path, modified) in &project.files_touched {
+                    let note = if *modified { " (modified)" } else { "" };
+                    md.push_str(&format!("- `{}`{}\n", path, note));
+                }
+                md.push('\n');
+            }
+
+            if !project.commands.is_empty() {
+                md.push_str("### Commands Run\n\n");
+                for command in &project.commands {
+                    md.push_str(&format!("- `{}`\n", command));
+                }
+                md.push('\n');
+            }
+
+            if !project.failed_tool_calls.is_empty() {
+                md.push_str("### Failed Tool Calls\n\n");
+                for failure in &project.failed_tool_calls {
+                    match &failure.target {
+                        Some(target) => md.push_str(&format!(
+                            "- **{}** `{}`: {}\n",
+                            failure.tool, target, failure.error
+                        )),
+                        None => {
+                            md.push_str(&format!("- **{}**: {}\n", failure.tool, failure.error))
+                        }
+                    }
+                }
+                md.push('\n');
+            }
+
+            if !project.open_todos.is_empty() {
+                md.push_str("### Open Todos\n\n");
+                for todo in &project.open_todos {
+                    let status = if todo.status == "in_progress" {
+                        " _(in progress)_"
+                    } else {
+                        ""
+                    };
+                    md.push_str(&format!("- [ ] {}{}\n", todo.content, status));
+                }
+                md.push('\n');
+            }
+        }
+
+        md
+    }
+}
+
+/// Messages of the conversations sent within the period
+fn period_messages<'a>(
+    conversations: &'a [&'a Conversation],
+    period: &'a TimePeriod,
+    tz: &Timezone,
+) -> impl Iterator<Item = (&'a Conversation, &'a ConversationMessage)> + 'a {
+    let (start, end) = (period.start_time(tz), period.end_time(tz));
+    conversations.iter().flat_map(move |conv| {
+        conv.messages
+            .iter()
+            .filter(move |m| m.timestamp >= start && m.timestamp <= end)
+            .map(move |m| (*conv, m))
+    })
+}
+
+fn files_touched(
+    conversations: &[&Conversation],
+    period: &TimePeriod,
+    tz: &Timezone,
+) -> Vec<(String, bool)> {
+    let index = FileIndex::build(conversations.iter().copied());
+
+    let mut files: Vec<(String, bool)> = index
+        .find("**", false)
+        .into_iter()
+        .filter_map(|file| {
+            let sessions: Vec<_> = file
+                .sessions
+                .iter()
+                .filter(|s| period.overlaps(s.first_touched, s.last_touched, tz))
+                .collect();
+            if sessions.is_empty() {
+                return None;
+            }
+            let modified = sessions
+                .iter()
+                .any(|s| s.operations.iter().any(|op| op.is_modification()));
+            Some((file.path, modified))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn commands_run(
+    conversations: &[&Conversation],
+    period: &TimePeriod,
+    tz: &Timezone,
+) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    period_messages(conversations, period, tz)
+        .flat_map(|(_, m)| &m.tool_uses)
+        .filter(|tool| tool.name == "Bash")
+        .filter_map(|tool| tool.input.get("command").and_then(|c| c.as_str()))
+        .map(excerpt)
+        .filter(|command| seen.insert(command.clone()))
+        .collect()
+}
+
+fn failed_tool_calls(
+    conversations: &[&Conversation],
+    period: &TimePeriod,
+    tz: &Timezone,
+) -> Vec<FailedToolCall> {
+    period_messages(conversations, period, tz)
+        .flat_map(|(conv, m)| m.tool_results.iter().map(move |result| (conv, result)))
+        .filter(|(_, result)| result.is_error)
+        .map(|(conv, result)| {
+            let tool_use = conv
+                .messages
+                .iter()
+                .flat_map(|m| &m.tool_uses)
+                .find(|tool| tool.id == result.tool_use_id);
+            FailedToolCall {
+                session_id: conv.session_id.clone(),
+                tool: tool_use
+                    .map(|tool| tool.name.clone())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                target: tool_use.and_then(|tool| {
+                    ["command", "file_path", "notebook_path", "url", "pattern"]
+                        .iter()
+                        .find_map(|field| tool.input.get(field).and_then(|v| v.as_str()))
+                        .map(excerpt)
+                }),
+                error: excerpt(&result.content),
+            }
+        })
+        .collect()
+}
+
+/// Unfinished items from the last todo list written in each conversation
+fn open_todos(
+    conversations: &[&Conversation],
+    period: &TimePeriod,
+    tz: &Timezone,
+) -> Vec<OpenTodo> {
+    conversations
+        .iter()
+        .filter_map(|conv| {
+            let todos = period_messages(std::slice::from_ref(conv), period, tz)
+                .flat_map(|(_, m)| &m.tool_uses)
+                .filter(|tool| tool.name == "TodoWrite")
+                .filter_map(|tool| tool.input.get("todos").and_then(|t| t.as_array()))
+                .last()?;
+            Some(
+                todos
+                    .iter()
+                    .filter_map(|todo| {
+                        let content = todo.get("content")?.as_str()?;
+                        let status = todo.get("status")?.as_str()?;
+                        (status != "completed").then(|| OpenTodo {
+                            session_id: conv.session_id.clone(),
+                            content: content.to_string(),
+                            status: status.to_string(),
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .flatten()
+        .collect()
+}
+
+/// First non-empty line, shortened for display
+fn excerpt(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("");
+    if line.chars().count() > MAX_EXCERPT_CHARS {
+        let cut: String = line.chars().take(MAX_EXCERPT_CHARS).collect();
+        format!("{}…", cut)
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::conversation::MessageRole;
+    use crate::claude::timeline::{SummaryDepth, TimelineConfig};
+    use chrono::{Duration, TimeZone};
+    use serde_json::json;
+
+    fn message(minute: u32, role: MessageRole) -> ConversationMessage {
+        ConversationMessage::new_for_test(
+            role,
+            Utc.with_ymd_and_hms(2024, 6, 3, 9, minute, 0).unwrap(),
+        )
+        .with_content("Refactor the parser module")
+    }
+
+    #[test]
+    fn test_digest_markdown() {
+        let messages = vec![
+            message(0, MessageRole::Assistant)
+                .with_tool_use("t1", "Bash", json!({"command": "cargo test\n--quiet"}))
+                .with_tool_use("t2", "Edit", json!({"file_path": "src/parser.rs"})),
+            message(1, MessageRole::User).with_tool_result(
+                "t1",
+                "\nerror[E0308]: mismatched types\nmore",
+                true,
+            ),
+            message(2, MessageRole::Assistant).with_tool_use(
+                "t3",
+                "TodoWrite",
+                json!({"todos": [
+                    {"content": "Fix types", "status": "in_progress"},
+                    {"content": "Write tests", "status": "pending"},
+                    {"content": "Read code", "status": "completed"}
+                ]}),
+            ),
+            message(3, MessageRole::Assistant).with_tool_use(
+                "t4",
+                "Bash",
+                json!({"command": "cargo test"}),
+            ),
+        ];
+        let conversations = vec![Conversation::new_for_test("sess-1", "-repo", messages)
+            .with_cwd("/repo")
+            .with_summary("Parser refactor")];
+
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 0, 0, 0).unwrap();
+        let config = TimelineConfig {
+            period: TimePeriod::Custom {
+                start,
+                end: start + Duration::days(1),
+            },
+            summary_depth: SummaryDepth::Brief,
+            ..TimelineConfig::default()
+        };
+        let timeline = ActivityTimeline::create_filtered_timeline(conversations.clone(), config);
+        let digest = Digest::build(&timeline, &conversations);
+
+        assert_eq!(digest.projects.len(), 1);
+        let project = &digest.projects[0];
+        assert_eq!(project.project, "/repo");
+        assert_eq!(project.commands, vec!["cargo test"]);
+        assert_eq!(
+            project.files_touched,
+            vec![("/repo/src/parser.rs".to_string(), true)]
+        );
+        assert_eq!(
+            project.failed_tool_calls[0].error,
+            "error[E0308]: mismatched types"
+        );
+        assert_eq!(project.open_todos.len(), 2);
+
+        let md = digest.to_markdown();
+        assert!(md.contains("## /repo"));
+        assert!(md.contains("- Parser refactor (`sess-1`)"));
+        assert!(md.contains("- `/repo/src/parser.rs` (modified)"));
+        assert!(md.contains("- **Bash** `cargo test`: error[E0308]: mismatched types"));
+        assert!(md.contains("- [ ] Fix types _(in progress)_"));
+        assert!(!md.contains("Read code"));
+    }
+}
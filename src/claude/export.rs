@@ -1,4 +1,5 @@
 use super::conversation::{Conversation, ConversationMessage, MessageRole};
+use super::timezone::Timezone;
 use crate::errors::ClaudeToolsError;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -14,6 +15,18 @@ pub enum ExportFormat {
     Json,
 }
 
+impl ExportFormat {
+    /// File extension for files written in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
 /// Export configuration options
 #[derive(Debug, Clone)]
 pub struct ExportConfig {
@@ -31,6 +44,8 @@ pub struct ExportConfig {
     pub template_path: Option<PathBuf>,
     /// Title for the export
     pub title: Option<String>,
+    /// Time zone timestamps are shown in
+    pub timezone: Timezone,
 }
 
 /// Main export engine
@@ -144,14 +159,14 @@ impl ConversationExporter {
             if let Some(started) = conversation.started_at {
                 content.push_str(&format!(
                     "**Started:** {}\n",
-                    started.format("%Y-%m-%d %H:%M:%S UTC")
+                    self.config.timezone.format_with_zone(started)
                 ));
             }
 
             if let Some(updated) = conversation.last_updated {
                 content.push_str(&format!(
                     "**Last Updated:** {}\n",
-                    updated.format("%Y-%m-%d %H:%M:%S UTC")
+                    self.config.timezone.format_with_zone(updated)
                 ));
             }
 
@@ -170,12 +185,14 @@ impl ConversationExporter {
                 MessageRole::User => "👤",
                 MessageRole::Assistant => "🤖",
                 MessageRole::System => "⚙️",
+                MessageRole::Tool => "🔧",
             };
 
             let role_name = match message.role {
                 MessageRole::User => "User",
                 MessageRole::Assistant => "Assistant",
                 MessageRole::System => "System",
+                MessageRole::Tool => "Tool",
             };
 
             content.push_str(&format!(
@@ -188,7 +205,7 @@ impl ConversationExporter {
             if self.config.include_timestamps {
                 content.push_str(&format!(
                     "**Timestamp:** {}\n",
-                    message.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
+                    self.config.timezone.format_with_zone(message.timestamp)
                 ));
             }
 
@@ -209,13 +226,25 @@ impl ConversationExporter {
                 content.push_str("\n");
             }
 
+            if self.config.include_tool_usage && !message.tool_results.is_empty() {
+                content.push_str("**Tool Results:**\n\n");
+                for result in &message.tool_results {
+                    content.push_str(&format!(
+                        "- `{}`: {}\n",
+                        result.tool_use_id,
+                        result.summary()
+                    ));
+                }
+                content.push('\n');
+            }
+
             content.push_str("---\n\n");
         }
 
         // Footer
         content.push_str(&format!(
-            "\n*Exported on {} UTC*\n",
-            Utc::now().format("%Y-%m-%d %H:%M:%S")
+            "\n*Exported on {}*\n",
+            self.config.timezone.format_with_zone(Utc::now())
         ));
 
         Ok(content)
@@ -269,6 +298,9 @@ impl ConversationExporter {
         .message.system {{
             border-left: 4px solid #ffc107;
         }}
+        .message.tool {{
+            border-left: 4px solid #6c757d;
+        }}
         .message-header {{
             display: flex;
             align-items: center;
@@ -383,7 +415,7 @@ impl ConversationExporter {
                     r#"
                 <dt>Started:</dt>
                 <dd>{}</dd>"#,
-                    started.format("%Y-%m-%d %H:%M:%S UTC")
+                    self.config.timezone.format_with_zone(started)
                 ));
             }
 
@@ -405,6 +437,7 @@ impl ConversationExporter {
                 MessageRole::User => ("user", "👤", "User"),
                 MessageRole::Assistant => ("assistant", "🤖", "Assistant"),
                 MessageRole::System => ("system", "⚙️", "System"),
+                MessageRole::Tool => ("tool", "🔧", "Tool"),
             };
 
             content.push_str(&format!(
@@ -428,7 +461,7 @@ impl ConversationExporter {
                 content.push_str(&format!(
                     r#"
             <span class="timestamp">{}</span>"#,
-                    message.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
+                    self.config.timezone.format_with_zone(message.timestamp)
                 ));
             }
 
@@ -459,6 +492,23 @@ impl ConversationExporter {
                 content.push_str("\n        </div>");
             }
 
+            if self.config.include_tool_usage && !message.tool_results.is_empty() {
+                content.push_str(
+                    r#"
+        <div class="tools">
+            <strong>Tool Results:</strong><br>"#,
+                );
+                for result in &message.tool_results {
+                    content.push_str(&format!(
+                        r#"
+            <div class="tool">• {}: {}</div>"#,
+                        html_escape(&result.tool_use_id),
+                        html_escape(&result.summary())
+                    ));
+                }
+                content.push_str("\n        </div>");
+            }
+
             content.push_str("\n    </div>");
         }
 
@@ -466,11 +516,11 @@ impl ConversationExporter {
         content.push_str(&format!(
             r#"
     <div class="footer">
-        <p>Exported on {} UTC</p>
+        <p>Exported on {}</p>
     </div>
 </body>
 </html>"#,
-            Utc::now().format("%Y-%m-%d %H:%M:%S")
+            self.config.timezone.format_with_zone(Utc::now())
         ));
 
         Ok(content)
@@ -513,6 +563,7 @@ impl Default for ExportConfig {
             include_timestamps: true,
             template_path: None,
             title: None,
+            timezone: Timezone::default(),
         }
     }
 }
@@ -532,33 +583,14 @@ mod tests {
     use chrono::Utc;
 
     fn create_test_conversation() -> Conversation {
-        Conversation {
-            session_id: "test-123".to_string(),
-            project_path: "/test/project".to_string(),
-            summary: Some("Test conversation".to_string()),
-            messages: vec![
-                ConversationMessage {
-                    uuid: "msg-1".to_string(),
-                    parent_uuid: None,
-                    role: MessageRole::User,
-                    content: "Hello, how are you?".to_string(),
-                    timestamp: Utc::now(),
-                    model: None,
-                    tool_uses: vec![],
-                },
-                ConversationMessage {
-                    uuid: "msg-2".to_string(),
-                    parent_uuid: Some("msg-1".to_string()),
-                    role: MessageRole::Assistant,
-                    content: "I'm doing well, thank you!".to_string(),
-                    timestamp: Utc::now(),
-                    model: Some("claude-3".to_string()),
-                    tool_uses: vec![],
-                },
-            ],
-            started_at: Some(Utc::now()),
-            last_updated: Some(Utc::now()),
-        }
+        let messages = vec![
+            ConversationMessage::user(Utc::now()).with_content("Hello, how are you?"),
+            ConversationMessage::assistant(Utc::now())
+                .with_content("I'm doing well, thank you!")
+                .with_model("claude-3"),
+        ];
+        Conversation::new_for_test("test-123", "/test/project", messages)
+            .with_summary("Test conversation")
     }
 
     #[test]
@@ -576,6 +608,23 @@ mod tests {
         assert!(content.contains("I'm doing well, thank you!"));
     }
 
+    #[test]
+    fn test_timestamps_in_configured_zone() {
+        use chrono::TimeZone;
+
+        let mut conversation = create_test_conversation();
+        conversation.started_at = Some(Utc.with_ymd_and_hms(2024, 6, 1, 22, 30, 0).unwrap());
+        let config = ExportConfig {
+            timezone: Timezone::parse("Europe/Berlin").unwrap(),
+            ..ExportConfig::default()
+        };
+
+        let content = ConversationExporter::new(config)
+            .generate_markdown(&conversation)
+            .unwrap();
+        assert!(content.contains("**Started:** 2024-06-02 00:30:00 Europe/Berlin"));
+    }
+
     #[test]
     fn test_html_generation() {
         let conversation = create_test_conversation();
@@ -0,0 +1,460 @@
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use super::conversation::{Conversation, ToolUse};
+use super::shell_commands::split_commands;
+
+/// How a tool call touched a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum FileOperation {
+    Read,
+    Edit,
+    MultiEdit,
+    Write,
+    NotebookEdit,
+    /// Path mentioned in a Bash command
+    Bash,
+}
+
+impl FileOperation {
+    /// Operation for a tool name, if the tool touches files
+    pub fn from_tool_name(name: &str) -> Option<Self> {
+        match name {
+            "Read" => Some(Self::Read),
+            "Edit" => Some(Self::Edit),
+            "MultiEdit" => Some(Self::MultiEdit),
+            "Write" => Some(Self::Write),
+            "NotebookEdit" => Some(Self::NotebookEdit),
+            "Bash" => Some(Self::Bash),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Read => "Read",
+            Self::Edit => "Edit",
+            Self::MultiEdit => "MultiEdit",
+            Self::Write => "Write",
+            Self::NotebookEdit => "NotebookEdit",
+            Self::Bash => "Bash",
+        }
+    }
+
+    /// Whether the operation changes the file
+    pub fn is_modification(&self) -> bool {
+        matches!(
+            self,
+            Self::Edit | Self::MultiEdit | Self::Write | Self::NotebookEdit
+        )
+    }
+}
+
+/// A single tool call touching a file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileTouch {
+    pub session_id: String,
+    pub project_path: String,
+    pub timestamp: DateTime<Utc>,
+    pub operation: FileOperation,
+}
+
+/// Everything one session did to one file
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionFileActivity {
+    pub session_id: String,
+    pub project_path: String,
+    pub first_touched: DateTime<Utc>,
+    pub last_touched: DateTime<Utc>,
+    /// Distinct operations, in a stable order
+    pub operations: Vec<FileOperation>,
+    pub touches: usize,
+}
+
+/// A file matching a lookup, with the sessions that touched it (most recent first)
+#[derive(Debug, Clone, Serialize)]
+pub struct FileMatch {
+    pub path: String,
+    pub sessions: Vec<SessionFileActivity>,
+}
+
+impl FileMatch {
+    /// Most recent time any session touched the file
+    pub fn last_touched(&self) -> Option<DateTime<Utc>> {
+        self.sessions.iter().map(|s| s.last_touched).max()
+    }
+}
+
+/// Index from absolute file path to the tool calls that touched it
+#[derive(Debug, Clone, Default)]
+pub struct FileIndex {
+    touches: BTreeMap<String, Vec<FileTouch>>,
+}
+
+impl FileIndex {
+    /// Build the index from the tool calls in every conversation
+    pub fn build<'a>(conversations: impl IntoIterator<Item = &'a Conversation>) -> Self {
+        let mut index = Self::default();
+
+        for conversation in conversations {
+            let cwd = conversation.cwd.as_deref();
+            for message in &conversation.messages {
+                for tool_use in &message.tool_uses {
+                    let Some(operation) = FileOperation::from_tool_name(&tool_use.name) else {
+                        continue;
+                    };
+
+                    for path in tool_file_paths(tool_use) {
+                        index
+                            .touches
+                            .entry(resolve_path(&path, cwd))
+                            .or_default()
+                            .push(FileTouch {
+                                session_id: conversation.session_id.clone(),
+                                project_path: conversation.project_path.clone(),
+                                timestamp: message.timestamp,
+                                operation,
+                            });
+                    }
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Number of distinct files in the index
+    pub fn len(&self) -> usize {
+        self.touches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.touches.is_empty()
+    }
+
+    /// Files touched by a session, sorted by path
+    pub fn files_for_session(&self, session_id: &str) -> Vec<&str> {
+        self.touches
+            .iter()
+            .filter(|(_, touches)| touches.iter().any(|t| t.session_id == session_id))
+            .map(|(path, _)| path.as_str())
+            .collect()
+    }
+
+    /// Files matching a path or glob, most recently touched first.
+    ///
+    /// Relative patterns match any path ending with them, so `src/ui/app.rs`
+    /// finds the file in whichever project it lives. `*` and `?` match within a
+    /// path component and `**` matches across components.
+    pub fn find(&self, pattern: &str, modified_only: bool) -> Vec<FileMatch> {
+        let matcher = pattern_regex(pattern);
+        self.matching(|path| matcher.is_match(path), modified_only)
+    }
+
+    /// Every file a tool call changed, most recently touched first
+    pub fn modified_files(&self) -> Vec<FileMatch> {
+        self.matching(|_| true, true)
+    }
+
+    /// Tool calls that touched a file, in conversation order
+    pub fn touches(&self, path: &str) -> &[FileTouch] {
+        self.touches.get(path).map_or(&[], Vec::as_slice)
+    }
+
+    fn matching(&self, filter: impl Fn(&str) -> bool, modified_only: bool) -> Vec<FileMatch> {
+        let mut matches: Vec<FileMatch> = self
+            .touches
+            .iter()
+            .filter(|(path, _)| filter(path))
+            .filter_map(|(path, touches)| {
+                let touches: Vec<&FileTouch> = touches
+                    .iter()
+                    .filter(|t| !modified_only || t.operation.is_modification())
+                    .collect();
+                if touches.is_empty() {
+                    return None;
+                }
+                Some(FileMatch {
+                    path: path.clone(),
+                    sessions: session_activity(&touches),
+                })
+            })
+            .collect();
+
+        matches.sort_by_key(|m| std::cmp::Reverse(m.last_touched()));
+        matches
+    }
+}
+
+/// Group touches of one file by session, most recent session first
+fn session_activity(touches: &[&FileTouch]) -> Vec<SessionFileActivity> {
+    let mut by_session: HashMap<&str, SessionFileActivity> = HashMap::new();
+
+    for touch in touches {
+        let activity = by_session
+            .entry(touch.session_id.as_str())
+            .or_insert_with(|| SessionFileActivity {
+                session_id: touch.session_id.clone(),
+                project_path: touch.project_path.clone(),
+                first_touched: touch.timestamp,
+                last_touched: touch.timestamp,
+                operations: Vec::new(),
+                touches: 0,
+            });
+        activity.first_touched = activity.first_touched.min(touch.timestamp);
+        activity.last_touched = activity.last_touched.max(touch.timestamp);
+        activity.touches += 1;
+        if !activity.operations.contains(&touch.operation) {
+            activity.operations.push(touch.operation);
+        }
+    }
+
+    let mut sessions: Vec<SessionFileActivity> = by_session.into_values().collect();
+    for session in &mut sessions {
+        session.operations.sort();
+    }
+    sessions.sort_by(|a, b| {
+        b.last_touched
+            .cmp(&a.last_touched)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+    sessions
+}
+
+/// File paths a tool call refers to, as written in its input
+pub fn tool_file_paths(tool_use: &ToolUse) -> Vec<String> {
+    match FileOperation::from_tool_name(&tool_use.name) {
+        Some(FileOperation::Bash) => tool_use
+            .input
+            .get("command")
+            .and_then(|v| v.as_str())
+            .map(bash_command_paths)
+            .unwrap_or_default(),
+        Some(_) => ["file_path", "notebook_path"]
+            .iter()
+            .filter_map(|field| tool_use.input.get(*field).and_then(|v| v.as_str()))
+            .filter(|path| !path.is_empty())
+            .map(str::to_string)
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Tokens in a shell command that look like file paths, leaving out heredoc
+/// bodies
+fn bash_command_paths(command_line: &str) -> Vec<String> {
+    let separated: String = split_commands(command_line, true)
+        .into_iter()
+        .flat_map(|command| command.chars().chain([' ']))
+        .map(|c| match c {
+            '&' | '<' | '>' | '(' | ')' => ' ',
+            _ => c,
+        })
+        .collect();
+
+    let mut paths = Vec::new();
+    for token in separated.split_whitespace() {
+        let token = token.trim_matches(|c| c == '"' || c == '\'' || c == ',');
+        if looks_like_path(token) && !paths.iter().any(|p| p == token) {
+            paths.push(token.to_string());
+        }
+    }
+    paths
+}
+
+fn looks_like_path(token: &str) -> bool {
+    if token.is_empty()
+        || token.starts_with('-')
+        || token.starts_with("/dev/")
+        || token.contains("://")
+        || token.contains(['=', '$', '`', '*', '?', '{', '}', '[', ']', '~', ':'])
+    {
+        return false;
+    }
+
+    if token.contains('/') {
+        // Bare "/" or "." style tokens aren't useful
+        return token.chars().any(|c| c.is_alphanumeric());
+    }
+
+    // A bare file name needs an extension starting with a letter ("main.rs", not "1.5")
+    match token.rsplit_once('.') {
+        Some((stem, extension)) => {
+            !stem.is_empty()
+                && extension.len() <= 8
+                && extension.starts_with(|c: char| c.is_ascii_alphabetic())
+                && extension.chars().all(|c| c.is_ascii_alphanumeric())
+        }
+        None => false,
+    }
+}
+
+/// Resolve a path against the session working directory and normalize `.`/`..`
+pub fn resolve_path(path: &str, cwd: Option<&str>) -> String {
+    let joined = match cwd {
+        Some(cwd) if !path.starts_with('/') => format!("{}/{}", cwd.trim_end_matches('/'), path),
+        _ => path.to_string(),
+    };
+
+    let mut components: Vec<&str> = Vec::new();
+    for component in joined.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            _ => components.push(component),
+        }
+    }
+
+    let normalized = components.join("/");
+    if joined.starts_with('/') {
+        format!("/{}", normalized)
+    } else {
+        normalized
+    }
+}
+
+/// Regex for a path or glob; relative patterns match at any component boundary
+fn pattern_regex(pattern: &str) -> Regex {
+    let pattern = pattern.trim_start_matches("./");
+    let mut regex = String::from(if pattern.starts_with('/') {
+        "^"
+    } else {
+        "(?:^|/)"
+    });
+
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+
+    Regex::new(&regex).expect("escaped glob is a valid regex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::conversation::ConversationMessage;
+    use chrono::TimeZone;
+
+    fn tool_message(hour: u32, name: &str, input: serde_json::Value) -> ConversationMessage {
+        ConversationMessage::assistant(Utc.with_ymd_and_hms(2024, 6, 1, hour, 0, 0).unwrap())
+            .with_tool_use(&format!("tool-{}", hour), name, input)
+    }
+
+    fn conversation(
+        session_id: &str,
+        cwd: &str,
+        messages: Vec<ConversationMessage>,
+    ) -> Conversation {
+        Conversation::new_for_test(session_id, &cwd.replace('/', "-"), messages).with_cwd(cwd)
+    }
+
+    #[test]
+    fn test_resolve_path() {
+        assert_eq!(
+            resolve_path("src/main.rs", Some("/repo")),
+            "/repo/src/main.rs"
+        );
+        assert_eq!(resolve_path("./a/../b.rs", Some("/repo/")), "/repo/b.rs");
+        assert_eq!(resolve_path("/etc/hosts", Some("/repo")), "/etc/hosts");
+        assert_eq!(resolve_path("src/lib.rs", None), "src/lib.rs");
+    }
+
+    #[test]
+    fn test_bash_command_paths() {
+        assert_eq!(
+            bash_command_paths("cat src/main.rs | grep -n foo > /dev/null && rm -f out.txt"),
+            vec!["src/main.rs", "out.txt"]
+        );
+        assert!(bash_command_paths("cargo test --release 1.5").is_empty());
+        assert!(bash_command_paths("curl https://example.com/a.json").is_empty());
+        assert_eq!(
+            bash_command_paths("cat > notes.md <<'EOF'\nsee docs/setup.md\nEOF\ngit add notes.md"),
+            vec!["notes.md"]
+        );
+    }
+
+    #[test]
+    fn test_file_index_find() {
+        let conversations = vec![
+            conversation(
+                "s1",
+                "/home/me/proj",
+                vec![
+                    tool_message(
+                        1,
+                        "Read",
+                        serde_json::json!({"file_path": "/home/me/proj/src/ui/app.rs"}),
+                    ),
+                    tool_message(
+                        2,
+                        "Edit",
+                        serde_json::json!({"file_path": "/home/me/proj/src/ui/app.rs"}),
+                    ),
+                    tool_message(
+                        3,
+                        "Bash",
+                        serde_json::json!({"command": "cargo fmt -- src/lib.rs"}),
+                    ),
+                ],
+            ),
+            conversation(
+                "s2",
+                "/home/me/proj",
+                vec![
+                    tool_message(5, "Read", serde_json::json!({"file_path": "src/ui/app.rs"})),
+                    tool_message(6, "Grep", serde_json::json!({"path": "src"})),
+                ],
+            ),
+        ];
+
+        let index = FileIndex::build(&conversations);
+        assert_eq!(index.len(), 2);
+
+        let matches = index.find("src/ui/app.rs", false);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].path, "/home/me/proj/src/ui/app.rs");
+        let sessions: Vec<&str> = matches[0]
+            .sessions
+            .iter()
+            .map(|s| s.session_id.as_str())
+            .collect();
+        assert_eq!(sessions, vec!["s2", "s1"]);
+        assert_eq!(
+            matches[0].sessions[1].operations,
+            vec![FileOperation::Read, FileOperation::Edit]
+        );
+
+        let modified = index.find("app.rs", true);
+        assert_eq!(modified[0].sessions.len(), 1);
+        assert_eq!(modified[0].sessions[0].session_id, "s1");
+
+        assert_eq!(index.find("src/**/*.rs", false).len(), 2);
+        assert_eq!(index.find("/home/me/proj/src/*.rs", false).len(), 1);
+        assert!(index.find("ui/app", false).is_empty());
+        assert!(index.find("p.rs", false).is_empty());
+
+        assert_eq!(
+            index.files_for_session("s1"),
+            vec!["/home/me/proj/src/lib.rs", "/home/me/proj/src/ui/app.rs"]
+        );
+    }
+}
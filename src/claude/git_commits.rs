@@ -0,0 +1,331 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::conversation::Conversation;
+use super::file_index::FileIndex;
+use crate::errors::{ClaudeToolsError, Result};
+
+/// Default window before a commit in which active conversations are linked to it
+pub const DEFAULT_COMMIT_WINDOW_MINUTES: i64 = 60;
+
+/// A commit read from a project's git log
+#[derive(Debug, Clone, Serialize)]
+pub struct GitCommit {
+    pub hash: String,
+    pub author: String,
+    /// Commit (not author) date, so rebased commits sort by when they landed
+    pub timestamp: DateTime<Utc>,
+    pub subject: String,
+    /// Changed files, relative to the repository root
+    pub files: Vec<String>,
+}
+
+impl GitCommit {
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(8)]
+    }
+}
+
+/// Why a conversation was linked to a commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitLinkReason {
+    /// The conversation had messages in the window before the commit
+    ActiveBefore,
+    /// The conversation edited files the commit changed
+    TouchedFiles,
+}
+
+impl CommitLinkReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::ActiveBefore => "active before",
+            Self::TouchedFiles => "touched files",
+        }
+    }
+}
+
+/// A conversation linked to a commit
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionLink {
+    pub session_id: String,
+    pub project_path: String,
+    pub reasons: Vec<CommitLinkReason>,
+    /// Committed files the conversation edited since the file was last committed
+    pub files: Vec<String>,
+}
+
+/// A commit with the conversations that led to it
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkedCommit {
+    /// Absolute path of the repository root
+    pub repository: String,
+    pub commit: GitCommit,
+    pub sessions: Vec<SessionLink>,
+}
+
+impl LinkedCommit {
+    pub fn has_session(&self, session_id: &str) -> bool {
+        self.sessions
+            .iter()
+            .any(|link| link.session_id == session_id)
+    }
+}
+
+/// Root of the git repository containing `dir`, if any
+pub fn repository_root(dir: &Path) -> Option<PathBuf> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!root.is_empty()).then(|| PathBuf::from(root))
+}
+
+/// Read non-merge commits from a repository, oldest first
+pub fn read_git_log(repository: &Path, since: Option<DateTime<Utc>>) -> Result<Vec<GitCommit>> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(repository).args([
+        "log",
+        "--no-merges",
+        "--name-only",
+        "--format=%x1e%H%x1f%an%x1f%cI%x1f%s",
+    ]);
+    if let Some(since) = since {
+        command.arg(format!("--since={}", since.to_rfc3339()));
+    }
+
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(ClaudeToolsError::General(anyhow::anyhow!(
+            "git log failed in {}: {}",
+            repository.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let mut commits = parse_git_log(&String::from_utf8_lossy(&output.stdout));
+    commits.reverse();
+    Ok(commits)
+}
+
+/// Parse `git log` output in the format used by `read_git_log`
+fn parse_git_log(log: &str) -> Vec<GitCommit> {
+    log.split('\x1e')
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let header: Vec<&str> = lines.next()?.split('\x1f').collect();
+            let [hash, author, date, subject] = header[..] else {
+                return None;
+            };
+            let timestamp = DateTime::parse_from_rfc3339(date).ok()?.with_timezone(&Utc);
+            Some(GitCommit {
+                hash: hash.to_string(),
+                author: author.to_string(),
+                timestamp,
+                subject: subject.to_string(),
+                files: lines
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Link commits (oldest first) in `repository` to conversations. A
+/// conversation is linked when it had messages within `window` before the
+/// commit, or when it edited a committed file after that file's previous commit.
+pub fn link_commits(
+    repository: &str,
+    commits: Vec<GitCommit>,
+    conversations: &[&Conversation],
+    window: Duration,
+) -> Vec<LinkedCommit> {
+    let index = FileIndex::build(conversations.iter().copied());
+    let mut last_committed: HashMap<&str, DateTime<Utc>> = HashMap::new();
+    let mut linked = Vec::new();
+    for commit in &commits {
+        let mut links: BTreeMap<&str, (&str, BTreeSet<CommitLinkReason>, BTreeSet<String>)> =
+            BTreeMap::new();
+
+        let window_start = commit.timestamp - window;
+        for conv in conversations {
+            if conv
+                .messages
+                .iter()
+                .any(|m| m.timestamp >= window_start && m.timestamp <= commit.timestamp)
+            {
+                links
+                    .entry(conv.session_id.as_str())
+                    .or_insert_with(|| {
+                        (conv.project_path.as_str(), BTreeSet::new(), BTreeSet::new())
+                    })
+                    .1
+                    .insert(CommitLinkReason::ActiveBefore);
+            }
+        }
+
+        for file in &commit.files {
+            let absolute = format!("{}/{}", repository.trim_end_matches('/'), file);
+            let since = last_committed.insert(file.as_str(), commit.timestamp);
+            for touch in index.touches(&absolute) {
+                if !touch.operation.is_modification()
+                    || touch.timestamp > commit.timestamp
+                    || since.is_some_and(|since| touch.timestamp <= since)
+                {
+                    continue;
+                }
+                let link = links.entry(touch.session_id.as_str()).or_insert_with(|| {
+                    (
+                        touch.project_path.as_str(),
+                        BTreeSet::new(),
+                        BTreeSet::new(),
+                    )
+                });
+                link.1.insert(CommitLinkReason::TouchedFiles);
+                link.2.insert(file.clone());
+            }
+        }
+
+        if links.is_empty() {
+            continue;
+        }
+        linked.push(LinkedCommit {
+            repository: repository.to_string(),
+            commit: commit.clone(),
+            sessions: links
+                .into_iter()
+                .map(|(session_id, (project_path, reasons, files))| SessionLink {
+                    session_id: session_id.to_string(),
+                    project_path: project_path.to_string(),
+                    reasons: reasons.into_iter().collect(),
+                    files: files.into_iter().collect(),
+                })
+                .collect(),
+        });
+    }
+    linked
+}
+
+/// Link conversations to commits in the git repositories they ran in, newest
+/// commit first. Conversations without a working directory, or whose directory
+/// is no longer a git repository, are skipped.
+pub fn correlate_commits(conversations: &[Conversation], window: Duration) -> Vec<LinkedCommit> {
+    let mut roots: HashMap<&str, Option<PathBuf>> = HashMap::new();
+    let mut by_repository: BTreeMap<PathBuf, Vec<&Conversation>> = BTreeMap::new();
+    for conv in conversations {
+        let Some(cwd) = conv.cwd.as_deref() else {
+            continue;
+        };
+        let root = roots
+            .entry(cwd)
+            .or_insert_with(|| repository_root(Path::new(cwd)));
+        if let Some(root) = root {
+            by_repository.entry(root.clone()).or_default().push(conv);
+        }
+    }
+
+    let mut linked = Vec::new();
+    for (repository, repository_conversations) in by_repository {
+        // Edits only count towards the next commit, so a day of slack covers
+        // conversations whose work was committed later
+        let since = repository_conversations
+            .iter()
+            .filter_map(|conv| conv.started_at)
+            .min()
+            .map(|start| start - Duration::days(1));
+        let Ok(commits) = read_git_log(&repository, since) else {
+            continue;
+        };
+        linked.extend(link_commits(
+            &repository.to_string_lossy(),
+            commits,
+            &repository_conversations,
+            window,
+        ));
+    }
+
+    linked.sort_by_key(|linked| std::cmp::Reverse(linked.commit.timestamp));
+    linked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::conversation::ConversationMessage;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 3, hour, minute, 0).unwrap()
+    }
+
+    fn edit(hour: u32, minute: u32, path: &str) -> ConversationMessage {
+        ConversationMessage::assistant(at(hour, minute)).with_tool_use(
+            &format!("tool-{}-{}", hour, minute),
+            "Edit",
+            serde_json::json!({ "file_path": path }),
+        )
+    }
+
+    fn conversation(session_id: &str, messages: Vec<ConversationMessage>) -> Conversation {
+        Conversation::new_for_test(session_id, "-repo", messages).with_cwd("/repo")
+    }
+
+    fn commit(hash: &str, timestamp: DateTime<Utc>, files: &[&str]) -> GitCommit {
+        GitCommit {
+            hash: hash.to_string(),
+            author: "Dev".to_string(),
+            timestamp,
+            subject: format!("Commit {}", hash),
+            files: files.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_git_log() {
+        let log = "\x1eabc123\x1fDev\x1f2024-06-03T11:00:00+02:00\x1fFix login\n\nsrc/login.rs\nREADME.md\n\x1edef456\x1fDev\x1f2024-06-03T08:00:00Z\x1fInitial\n\nsrc/main.rs\n";
+        let commits = parse_git_log(log);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].timestamp, at(9, 0));
+        assert_eq!(commits[0].subject, "Fix login");
+        assert_eq!(commits[0].files, vec!["src/login.rs", "README.md"]);
+        assert_eq!(commits[1].short_hash(), "def456");
+    }
+
+    #[test]
+    fn test_link_commits_by_window_and_files() {
+        // "a" edits the login code in the morning; "b" is chatting just before the
+        // afternoon commit
+        let a = conversation("a", vec![edit(9, 0, "/repo/src/login.rs")]);
+        let b = conversation("b", vec![edit(14, 50, "/repo/docs/notes.md")]);
+        let commits = vec![
+            commit("early", at(8, 0), &["src/login.rs"]),
+            commit("late", at(15, 0), &["src/login.rs", "README.md"]),
+        ];
+
+        let linked = link_commits("/repo", commits, &[&a, &b], Duration::minutes(30));
+        assert_eq!(linked.len(), 1);
+        assert_eq!(linked[0].commit.hash, "late");
+        assert_eq!(linked[0].sessions.len(), 2);
+
+        let from_files = &linked[0].sessions[0];
+        assert_eq!(from_files.session_id, "a");
+        assert_eq!(from_files.reasons, vec![CommitLinkReason::TouchedFiles]);
+        assert_eq!(from_files.files, vec!["src/login.rs"]);
+
+        let from_window = &linked[0].sessions[1];
+        assert_eq!(from_window.reasons, vec![CommitLinkReason::ActiveBefore]);
+        assert!(from_window.files.is_empty());
+        assert!(linked[0].has_session("b"));
+    }
+}
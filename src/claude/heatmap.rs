@@ -0,0 +1,424 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::analytics::TemporalAnalysis;
+use super::pricing::format_tokens;
+
+/// What a heatmap cell is shaded by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HeatmapMetric {
+    Conversations,
+    #[default]
+    Messages,
+    Tokens,
+    Cost,
+}
+
+impl HeatmapMetric {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HeatmapMetric::Conversations => "conversations",
+            HeatmapMetric::Messages => "messages",
+            HeatmapMetric::Tokens => "tokens",
+            HeatmapMetric::Cost => "cost",
+        }
+    }
+
+    /// The next metric, for cycling through them in the TUI
+    pub fn next(&self) -> Self {
+        match self {
+            HeatmapMetric::Conversations => HeatmapMetric::Messages,
+            HeatmapMetric::Messages => HeatmapMetric::Tokens,
+            HeatmapMetric::Tokens => HeatmapMetric::Cost,
+            HeatmapMetric::Cost => HeatmapMetric::Conversations,
+        }
+    }
+
+    /// Format a cell value with its unit
+    pub fn format_value(&self, value: f64) -> String {
+        match self {
+            HeatmapMetric::Conversations | HeatmapMetric::Messages => {
+                format!("{} {}", value as u64, self.label())
+            }
+            HeatmapMetric::Tokens => format_tokens(value as u64),
+            HeatmapMetric::Cost => format!("${:.2}", value),
+        }
+    }
+}
+
+/// A GitHub-style calendar heatmap: one column per ISO week (Monday first),
+/// one cell per day, shaded into `LEVELS` intensity levels
+#[derive(Debug, Clone)]
+pub struct CalendarHeatmap {
+    pub metric: HeatmapMetric,
+    /// First day shown
+    pub first: NaiveDate,
+    /// Last day shown
+    pub last: NaiveDate,
+    values: BTreeMap<NaiveDate, f64>,
+    max: f64,
+}
+
+impl CalendarHeatmap {
+    /// Number of shading levels, including the empty level 0
+    pub const LEVELS: usize = 5;
+
+    /// Unicode shades for each level, used by text output
+    pub const SHADES: [char; Self::LEVELS] = ['·', '░', '▒', '▓', '█'];
+
+    /// Build a heatmap over `first..=last` from daily analytics
+    pub fn from_temporal(
+        temporal: &TemporalAnalysis,
+        metric: HeatmapMetric,
+        first: NaiveDate,
+        last: NaiveDate,
+    ) -> Self {
+        let daily: Vec<(&String, f64)> = match metric {
+            HeatmapMetric::Conversations => temporal
+                .conversations_per_day
+                .iter()
+                .map(|(day, &count)| (day, count as f64))
+                .collect(),
+            HeatmapMetric::Messages => temporal
+                .messages_per_day
+                .iter()
+                .map(|(day, &count)| (day, count as f64))
+                .collect(),
+            HeatmapMetric::Tokens => temporal
+                .tokens_per_day
+                .iter()
+                .map(|(day, &tokens)| (day, tokens as f64))
+                .collect(),
+            HeatmapMetric::Cost => temporal
+                .cost_per_day
+                .iter()
+                .map(|(day, &cost)| (day, cost))
+                .collect(),
+        };
+
+        let values: BTreeMap<NaiveDate, f64> = daily
+            .into_iter()
+            .filter_map(|(day, value)| {
+                let date = NaiveDate::parse_from_str(day, "%Y-%m-%d").ok()?;
+                (date >= first && date <= last && value > 0.0).then_some((date, value))
+            })
+            .collect();
+        let max = values.values().copied().fold(0.0, f64::max);
+
+        Self {
+            metric,
+            first,
+            last,
+            values,
+            max,
+        }
+    }
+
+    /// The 52 weeks up to and including `today`
+    pub fn last_year(temporal: &TemporalAnalysis, metric: HeatmapMetric, today: NaiveDate) -> Self {
+        Self::from_temporal(temporal, metric, today - Duration::days(363), today)
+    }
+
+    /// A calendar year, January 1st to December 31st
+    pub fn for_year(temporal: &TemporalAnalysis, metric: HeatmapMetric, year: i32) -> Self {
+        let first = NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or_default();
+        let last = NaiveDate::from_ymd_opt(year, 12, 31).unwrap_or_default();
+        Self::from_temporal(temporal, metric, first, last)
+    }
+
+    /// Monday of the first column
+    fn grid_start(&self) -> NaiveDate {
+        self.first - Duration::days(self.first.weekday().num_days_from_monday() as i64)
+    }
+
+    /// Number of week columns
+    pub fn week_count(&self) -> usize {
+        ((self.last - self.grid_start()).num_days() / 7 + 1) as usize
+    }
+
+    /// Date in a week column and weekday row (0 = Monday), if it is within the heatmap
+    pub fn date_at(&self, week: usize, weekday: usize) -> Option<NaiveDate> {
+        let date = self.grid_start() + Duration::days((week * 7 + weekday) as i64);
+        self.contains(date).then_some(date)
+    }
+
+    /// Week column of a date
+    pub fn week_of(&self, date: NaiveDate) -> usize {
+        ((date - self.grid_start()).num_days() / 7) as usize
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        date >= self.first && date <= self.last
+    }
+
+    /// Metric value for a day (0 when there was no activity)
+    pub fn value(&self, date: NaiveDate) -> f64 {
+        self.values.get(&date).copied().unwrap_or(0.0)
+    }
+
+    /// Shading level for a day, 0 (no activity) to `LEVELS - 1` (busiest)
+    pub fn level(&self, date: NaiveDate) -> usize {
+        let value = self.value(date);
+        if value <= 0.0 || self.max <= 0.0 {
+            return 0;
+        }
+        let steps = (Self::LEVELS - 1) as f64;
+        ((value / self.max * steps).ceil() as usize).clamp(1, Self::LEVELS - 1)
+    }
+
+    /// Sum of the metric over the heatmap
+    pub fn total(&self) -> f64 {
+        self.values.values().sum()
+    }
+
+    /// Number of days with any activity
+    pub fn active_days(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The day with the highest value
+    pub fn busiest_day(&self) -> Option<(NaiveDate, f64)> {
+        self.values
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(&date, &value)| (date, value))
+    }
+
+    /// One character per week column, with a month abbreviation starting at
+    /// the first column of each month (when there is room for it)
+    pub fn month_labels(&self) -> String {
+        let mut labels = vec![' '; self.week_count() + 3];
+        let mut next_free = 0;
+
+        for week in 0..self.week_count() {
+            let Some(month_start) = (0..7)
+                .filter_map(|day| self.date_at(week, day))
+                .find(|date| date.day() == 1 || (*date == self.first && self.first_month_fits()))
+            else {
+                continue;
+            };
+            if week < next_free {
+                continue;
+            }
+            let name = month_start.format("%b").to_string();
+            for (offset, c) in name.chars().enumerate() {
+                labels[week + offset] = c;
+            }
+            next_free = week + name.len() + 1;
+        }
+
+        labels
+            .into_iter()
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    /// Whether a partial first month spans enough columns to be labelled
+    fn first_month_fits(&self) -> bool {
+        let next_month = self
+            .first
+            .with_day(1)
+            .and_then(|date| date.checked_add_months(Months::new(1)));
+        next_month.is_none_or(|date| self.week_of(date) >= 4)
+    }
+
+    /// Row label for a weekday row (Mon, Wed and Fri, like GitHub)
+    pub fn weekday_label(weekday: usize) -> &'static str {
+        match weekday {
+            0 => "Mon",
+            2 => "Wed",
+            4 => "Fri",
+            _ => "",
+        }
+    }
+
+    /// Render as text using Unicode shade blocks
+    pub fn render_unicode(&self) -> Vec<String> {
+        let mut lines = vec![format!("    {}", self.month_labels())];
+
+        for weekday in 0..7 {
+            let cells: String = (0..self.week_count())
+                .map(|week| match self.date_at(week, weekday) {
+                    Some(date) => Self::SHADES[self.level(date)],
+                    None => ' ',
+                })
+                .collect();
+            lines.push(format!("{:<4}{}", Self::weekday_label(weekday), cells));
+        }
+
+        let legend: String = Self::SHADES.iter().map(|c| format!("{} ", c)).collect();
+        lines.push(format!("    Less {}More", legend));
+        lines
+    }
+
+    /// Render as a standalone SVG document
+    pub fn to_svg(&self) -> String {
+        const CELL: usize = 11;
+        const STEP: usize = 13;
+        const LEFT: usize = 32;
+        const TOP: usize = 20;
+        const COLORS: [&str; CalendarHeatmap::LEVELS] =
+            ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+        let width = LEFT + self.week_count() * STEP + 10;
+        let height = TOP + 7 * STEP + 30;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+             font-family=\"sans-serif\" font-size=\"10\">\n",
+            width, height
+        );
+        svg.push_str(&format!(
+            "  <title>Activity by {} from {} to {}</title>\n",
+            self.metric.label(),
+            self.first,
+            self.last
+        ));
+
+        for (week, c) in self.month_labels().chars().enumerate() {
+            if c.is_ascii_uppercase() {
+                let name: String = self.month_labels().chars().skip(week).take(3).collect();
+                svg.push_str(&format!(
+                    "  <text x=\"{}\" y=\"{}\" fill=\"#767676\">{}</text>\n",
+                    LEFT + week * STEP,
+                    TOP - 6,
+                    name
+                ));
+            }
+        }
+
+        for weekday in 0..7 {
+            let label = Self::weekday_label(weekday);
+            if !label.is_empty() {
+                svg.push_str(&format!(
+                    "  <text x=\"0\" y=\"{}\" fill=\"#767676\">{}</text>\n",
+                    TOP + weekday * STEP + CELL - 1,
+                    label
+                ));
+            }
+        }
+
+        for week in 0..self.week_count() {
+            for weekday in 0..7 {
+                let Some(date) = self.date_at(week, weekday) else {
+                    continue;
+                };
+                svg.push_str(&format!(
+                    "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"2\" fill=\"{}\">\
+                     <title>{}: {}</title></rect>\n",
+                    LEFT + week * STEP,
+                    TOP + weekday * STEP,
+                    CELL,
+                    CELL,
+                    COLORS[self.level(date)],
+                    date,
+                    self.metric.format_value(self.value(date))
+                ));
+            }
+        }
+
+        let legend_y = TOP + 7 * STEP + 10;
+        svg.push_str(&format!(
+            "  <text x=\"{}\" y=\"{}\" fill=\"#767676\">Less</text>\n",
+            LEFT,
+            legend_y + CELL - 1
+        ));
+        for (level, color) in COLORS.iter().enumerate() {
+            svg.push_str(&format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"2\" fill=\"{}\"/>\n",
+                LEFT + 30 + level * STEP,
+                legend_y,
+                CELL,
+                CELL,
+                color
+            ));
+        }
+        svg.push_str(&format!(
+            "  <text x=\"{}\" y=\"{}\" fill=\"#767676\">More</text>\n",
+            LEFT + 34 + Self::LEVELS * STEP,
+            legend_y + CELL - 1
+        ));
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::analytics::ActivityTrends;
+
+    fn temporal(messages: &[(&str, usize)]) -> TemporalAnalysis {
+        TemporalAnalysis {
+            conversations_per_day: messages.iter().map(|(d, _)| (d.to_string(), 1)).collect(),
+            messages_per_day: messages.iter().map(|(d, n)| (d.to_string(), *n)).collect(),
+            tokens_per_day: BTreeMap::new(),
+            cost_per_day: BTreeMap::new(),
+            usage_by_hour: Default::default(),
+            usage_by_weekday: Default::default(),
+            peak_usage_hours: Vec::new(),
+            activity_trends: ActivityTrends {
+                weekly_growth_rate: 0.0,
+                monthly_growth_rate: 0.0,
+                most_active_month: String::new(),
+                most_active_day: String::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_heatmap_grid_and_levels() {
+        let data = temporal(&[("2024-01-01", 2), ("2024-01-10", 8), ("2023-12-31", 50)]);
+        let heatmap = CalendarHeatmap::for_year(&data, HeatmapMetric::Messages, 2024);
+
+        // 2024-01-01 is a Monday, 2024-12-31 a Tuesday
+        assert_eq!(heatmap.week_count(), 53);
+        assert_eq!(heatmap.date_at(0, 0), NaiveDate::from_ymd_opt(2024, 1, 1));
+        assert_eq!(heatmap.date_at(52, 2), None);
+
+        let jan10 = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        assert_eq!(heatmap.week_of(jan10), 1);
+        assert_eq!(heatmap.level(jan10), 4);
+        assert_eq!(
+            heatmap.level(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
+            1
+        );
+        assert_eq!(
+            heatmap.level(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
+            0
+        );
+        assert_eq!(heatmap.total(), 10.0);
+        assert_eq!(heatmap.active_days(), 2);
+        assert_eq!(heatmap.busiest_day(), Some((jan10, 8.0)));
+        assert!(heatmap.month_labels().starts_with("Jan Feb Mar"));
+    }
+
+    #[test]
+    fn test_heatmap_rendering() {
+        let data = temporal(&[("2024-03-05", 4)]);
+        let heatmap = CalendarHeatmap::for_year(&data, HeatmapMetric::Messages, 2024);
+
+        let lines = heatmap.render_unicode();
+        assert_eq!(lines.len(), 9);
+        assert!(lines[2].starts_with("    "));
+        assert!(lines[2].contains('█'));
+        assert!(lines[1].starts_with("Mon "));
+
+        let svg = heatmap.to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect").count(), 366 + CalendarHeatmap::LEVELS);
+        assert!(svg.contains("<title>2024-03-05: 4 messages</title>"));
+    }
+
+    #[test]
+    fn test_metric_formatting() {
+        assert_eq!(
+            HeatmapMetric::Tokens.format_value(1_250_000.0),
+            "1.2M tokens"
+        );
+        assert_eq!(HeatmapMetric::Cost.format_value(3.456), "$3.46");
+        assert_eq!(HeatmapMetric::Cost.next(), HeatmapMetric::Conversations);
+    }
+}
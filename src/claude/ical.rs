@@ -0,0 +1,259 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeSet;
+
+use super::conversation::Conversation;
+use super::timeline::ConversationSummary;
+use super::work_sessions::{project_work_sessions, ProjectWorkSession};
+
+/// One work session on one project, as a calendar event
+#[derive(Debug, Clone)]
+pub struct WorkSessionEvent {
+    pub project: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Start of the whole work session, which `start` may be clipped from;
+    /// keeps the event UID the same whatever range is exported
+    pub unclipped_start: DateTime<Utc>,
+    pub session_ids: Vec<String>,
+    /// Title or content summary of each conversation in the work session
+    pub summaries: Vec<String>,
+    /// Tools used during the work session
+    pub tools: Vec<String>,
+}
+
+impl WorkSessionEvent {
+    /// Detect work sessions per project and describe them as events, oldest
+    /// first, optionally clipped to `range`
+    pub fn from_conversations(
+        conversations: &[Conversation],
+        idle_threshold: Duration,
+        range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    ) -> Vec<Self> {
+        let mut events: Vec<Self> = project_work_sessions(conversations, idle_threshold, range)
+            .into_iter()
+            .map(|project_session| {
+                let ProjectWorkSession {
+                    project_path,
+                    conversations,
+                    unclipped_start,
+                    session,
+                } = project_session;
+                let project = conversations
+                    .iter()
+                    .find_map(|conv| conv.cwd.clone())
+                    .unwrap_or_else(|| project_path.to_string());
+                let summaries = conversations
+                    .iter()
+                    .map(|conv| {
+                        let summary = ConversationSummary::from_conversation(conv);
+                        summary.title.unwrap_or(summary.content_summary)
+                    })
+                    .filter(|summary| !summary.is_empty())
+                    .collect();
+                let tools: BTreeSet<String> = conversations
+                    .iter()
+                    .flat_map(|conv| &conv.messages)
+                    .filter(|m| m.timestamp >= session.start && m.timestamp <= session.end)
+                    .flat_map(|m| m.tool_uses.iter().map(|tool| tool.name.clone()))
+                    .collect();
+
+                WorkSessionEvent {
+                    project,
+                    start: session.start,
+                    end: session.end,
+                    unclipped_start,
+                    session_ids: session.session_ids,
+                    summaries,
+                    tools: tools.into_iter().collect(),
+                }
+            })
+            .collect();
+
+        events.sort_by(|a, b| a.start.cmp(&b.start).then(a.project.cmp(&b.project)));
+        events
+    }
+
+    /// Last path component of the project, for event titles
+    fn project_name(&self) -> &str {
+        self.project
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(&self.project)
+    }
+
+    fn description(&self) -> String {
+        let mut lines = vec![format!("Project: {}", self.project)];
+        if !self.summaries.is_empty() {
+            lines.push(String::new());
+            lines.extend(
+                self.summaries
+                    .iter()
+                    .map(|summary| format!("- {}", summary)),
+            );
+        }
+        if !self.tools.is_empty() {
+            lines.push(String::new());
+            lines.push(format!("Tools: {}", self.tools.join(", ")));
+        }
+        lines.push(String::new());
+        for session_id in &self.session_ids {
+            lines.push(format!(
+                "Session: {} (claude-tools show {})",
+                session_id, session_id
+            ));
+        }
+        lines.join("\n")
+    }
+
+    fn write_vevent(&self, out: &mut Vec<String>, stamp: &str) {
+        // Calendars drop zero-length events, so single-message sessions last a minute
+        let end = self.end.max(self.start + Duration::minutes(1));
+        let first_session = self.session_ids.first().map(String::as_str).unwrap_or("");
+
+        out.push("BEGIN:VEVENT".to_string());
+        out.push(format!(
+            "UID:{}-{}@claude-tools",
+            first_session,
+            self.unclipped_start.timestamp()
+        ));
+        out.push(format!("DTSTAMP:{}", stamp));
+        out.push(format!("DTSTART:{}", format_utc(self.start)));
+        out.push(format!("DTEND:{}", format_utc(end)));
+        out.push(format!(
+            "SUMMARY:{}",
+            escape_text(&format!("Claude: {}", self.project_name()))
+        ));
+        out.push(format!("DESCRIPTION:{}", escape_text(&self.description())));
+        out.push(format!("CATEGORIES:{}", escape_text(self.project_name())));
+        for session_id in &self.session_ids {
+            out.push(format!("X-CLAUDE-SESSION-ID:{}", escape_text(session_id)));
+        }
+        out.push("END:VEVENT".to_string());
+    }
+}
+
+/// Render events as an iCalendar (RFC 5545) document
+pub fn to_ics(events: &[WorkSessionEvent], generated_at: DateTime<Utc>) -> String {
+    let stamp = format_utc(generated_at);
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//claude-tools//Work Sessions//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Claude work sessions".to_string(),
+    ];
+    for event in events {
+        event.write_vevent(&mut lines, &stamp);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+fn format_utc(timestamp: DateTime<Utc>) -> String {
+    timestamp.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT property value
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line to at most 75 octets, without splitting characters
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::conversation::{ConversationMessage, MessageRole};
+    use chrono::TimeZone;
+
+    fn message(minute: u32, role: MessageRole, tool: Option<&str>) -> ConversationMessage {
+        let message = ConversationMessage::new_for_test(
+            role,
+            Utc.with_ymd_and_hms(2024, 6, 3, 9, minute, 0).unwrap(),
+        )
+        .with_content("Fix the login bug");
+        match tool {
+            Some(name) => {
+                message.with_tool_use(&format!("tool-{}", minute), name, serde_json::Value::Null)
+            }
+            None => message,
+        }
+    }
+
+    #[test]
+    fn test_work_session_events_to_ics() {
+        let messages = vec![
+            message(0, MessageRole::User, None),
+            message(5, MessageRole::Assistant, Some("Edit")),
+            message(20, MessageRole::Assistant, Some("Bash")),
+        ];
+        let conversations = vec![
+            Conversation::new_for_test("sess-1", "-home-me-app", messages)
+                .with_cwd("/home/me/app")
+                .with_summary("Login fix, with tests"),
+        ];
+
+        let events =
+            WorkSessionEvent::from_conversations(&conversations, Duration::minutes(30), None);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].tools, vec!["Bash", "Edit"]);
+
+        let generated_at = Utc.with_ymd_and_hms(2024, 6, 4, 0, 0, 0).unwrap();
+        let ics = to_ics(&events, generated_at);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20240603T090000Z\r\n"));
+        assert!(ics.contains("DTEND:20240603T092000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Claude: app\r\n"));
+        assert!(ics.contains("Login fix\\, with tests"));
+        assert!(ics.contains("X-CLAUDE-SESSION-ID:sess-1\r\n"));
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+
+        // Clipping the work session to a range keeps its UID
+        let from = Utc.with_ymd_and_hms(2024, 6, 3, 9, 10, 0).unwrap();
+        let range = Some((from, from + Duration::hours(1)));
+        let clipped =
+            WorkSessionEvent::from_conversations(&conversations, Duration::minutes(30), range);
+        assert_eq!(clipped[0].start, from);
+        let uid = |events: &[WorkSessionEvent]| {
+            to_ics(events, generated_at)
+                .split("\r\n")
+                .find(|line| line.starts_with("UID:"))
+                .map(str::to_string)
+        };
+        assert_eq!(uid(&clipped), uid(&events));
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let line = format!("DESCRIPTION:{}", "é".repeat(60));
+        let folded = fold_line(&line);
+        assert!(folded.split("\r\n").all(|part| part.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -0,0 +1,240 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::conversation::{Conversation, ConversationMessage, MessageRole};
+use super::tool_outcomes::ToolOutcome;
+
+/// Tools that can legitimately take a while to return a result; a slow result
+/// from any other tool means it sat behind a permission prompt
+const LONG_RUNNING_TOOLS: [&str; 5] = ["Bash", "Task", "WebFetch", "WebSearch", "BashOutput"];
+
+/// Gap between a quick tool call and its result beyond which the call is
+/// taken to have waited on a permission prompt
+const PERMISSION_WAIT_SECONDS: i64 = 15;
+
+/// One prompt and the assistant's work answering it
+#[derive(Debug, Clone)]
+pub struct ResponseTurn {
+    pub prompted_at: DateTime<Utc>,
+    /// Model of the first reply
+    pub model: Option<String>,
+    /// Time from the prompt to the first assistant message
+    pub first_reply: Duration,
+    /// Time from the prompt to the last assistant message before the next prompt
+    pub completed: Duration,
+    /// Whether a tool call in the turn waited on a permission prompt
+    pub waited_on_permission: bool,
+}
+
+/// Split a conversation into turns, one per user prompt that got a reply.
+/// Tool results are sent as user messages but don't start a turn
+pub fn response_turns(conversation: &Conversation) -> Vec<ResponseTurn> {
+    let is_prompt = |m: &ConversationMessage| m.is_user_prompt() && !m.content.trim().is_empty();
+
+    let mut turns = Vec::new();
+    let mut messages = conversation.messages.iter().peekable();
+    while let Some(prompt) = messages.next() {
+        if !is_prompt(prompt) {
+            continue;
+        }
+        let mut turn_messages = Vec::new();
+        while let Some(message) = messages.next_if(|m| !is_prompt(m)) {
+            turn_messages.push(message);
+        }
+
+        let mut replies = turn_messages
+            .iter()
+            .filter(|m| m.role == MessageRole::Assistant);
+        let Some(first_reply) = replies.next() else {
+            continue;
+        };
+        let last_reply = replies.next_back().unwrap_or(first_reply);
+
+        turns.push(ResponseTurn {
+            prompted_at: prompt.timestamp,
+            model: first_reply.model.clone(),
+            first_reply: first_reply.timestamp - prompt.timestamp,
+            completed: last_reply.timestamp - prompt.timestamp,
+            waited_on_permission: waited_on_permission(&turn_messages),
+        });
+    }
+    turns
+}
+
+/// Whether any tool call in a turn was declined, or was a quick tool whose
+/// result came back too late to be anything but a permission prompt
+fn waited_on_permission(turn_messages: &[&ConversationMessage]) -> bool {
+    let tool_calls: HashMap<&str, (&str, DateTime<Utc>)> = turn_messages
+        .iter()
+        .flat_map(|m| m.tool_uses.iter().map(move |tool| (tool, m.timestamp)))
+        .map(|(tool, timestamp)| (tool.id.as_str(), (tool.name.as_str(), timestamp)))
+        .collect();
+
+    turn_messages
+        .iter()
+        .flat_map(|m| {
+            m.tool_results
+                .iter()
+                .map(move |result| (result, m.timestamp))
+        })
+        .any(|(result, returned_at)| {
+            let Some(&(name, called_at)) = tool_calls.get(result.tool_use_id.as_str()) else {
+                return false;
+            };
+            ToolOutcome::from_result(name, result) == ToolOutcome::Rejected
+                || (!LONG_RUNNING_TOOLS.contains(&name)
+                    && returned_at - called_at > Duration::seconds(PERMISSION_WAIT_SECONDS))
+        })
+}
+
+/// Percentiles of a set of latencies, in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LatencyDistribution {
+    pub count: usize,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+}
+
+impl LatencyDistribution {
+    pub fn from_seconds(seconds: &[f64]) -> Option<Self> {
+        if seconds.is_empty() {
+            return None;
+        }
+        let mut sorted = seconds.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        // Nearest-rank percentile
+        let percentile = |p: f64| {
+            let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+        Some(Self {
+            count: sorted.len(),
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50: percentile(50.0),
+            p90: percentile(90.0),
+            p99: percentile(99.0),
+        })
+    }
+
+    /// Percentiles as e.g. `p50 4.2s, p90 12.0s, p99 1m 02s`
+    pub fn percentiles_label(&self) -> String {
+        format!(
+            "p50 {}, p90 {}, p99 {}",
+            format_latency(self.p50),
+            format_latency(self.p90),
+            format_latency(self.p99)
+        )
+    }
+}
+
+/// Latency distributions for a group of turns
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    /// Prompt to first assistant reply
+    pub first_reply: LatencyDistribution,
+    /// Prompt to the end of the turn
+    pub turn: LatencyDistribution,
+}
+
+impl LatencyStats {
+    /// Distributions for `turns`, or `None` when there are none
+    pub fn from_turns<'a>(turns: impl IntoIterator<Item = &'a ResponseTurn>) -> Option<Self> {
+        let (first_reply, turn): (Vec<f64>, Vec<f64>) = turns
+            .into_iter()
+            .map(|turn| (seconds(turn.first_reply), seconds(turn.completed)))
+            .unzip();
+        Some(Self {
+            first_reply: LatencyDistribution::from_seconds(&first_reply)?,
+            turn: LatencyDistribution::from_seconds(&turn)?,
+        })
+    }
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.num_milliseconds().max(0) as f64 / 1000.0
+}
+
+/// A latency as e.g. `850ms`, `12.4s` or `3m 05s`
+pub fn format_latency(seconds: f64) -> String {
+    if seconds < 1.0 {
+        format!("{:.0}ms", seconds * 1000.0)
+    } else if seconds < 60.0 {
+        format!("{:.1}s", seconds)
+    } else {
+        let total = seconds.round() as u64;
+        format!("{}m {:02}s", total / 60, total % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(second: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 1, 10, 0, 0).unwrap() + Duration::seconds(second)
+    }
+
+    fn user(second: i64, content: &str) -> ConversationMessage {
+        ConversationMessage::user(at(second)).with_content(content)
+    }
+
+    fn assistant(second: i64, content: &str) -> ConversationMessage {
+        ConversationMessage::assistant(at(second))
+            .with_content(content)
+            .with_model("claude-sonnet-4")
+    }
+
+    #[test]
+    fn test_response_turns() {
+        let null = serde_json::Value::Null;
+        let conversation = Conversation::new_for_test(
+            "s1",
+            "-repo",
+            vec![
+                user(0, "Run the tests"),
+                assistant(4, "").with_tool_use("t1", "Bash", null.clone()),
+                // A slow test run is not a permission prompt
+                user(64, "").with_tool_result("t1", "ok", false),
+                assistant(70, "All green"),
+                user(100, "Now fix the typo"),
+                assistant(102, "").with_tool_use("t2", "Edit", null),
+                // An edit that took a minute waited for approval
+                user(162, "").with_tool_result("t2", "updated", false),
+                assistant(165, "Fixed"),
+                user(200, "Thanks"),
+            ],
+        );
+
+        let turns = response_turns(&conversation);
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].first_reply, Duration::seconds(4));
+        assert_eq!(turns[0].completed, Duration::seconds(70));
+        assert!(!turns[0].waited_on_permission);
+        assert_eq!(turns[1].completed, Duration::seconds(65));
+        assert!(turns[1].waited_on_permission);
+    }
+
+    #[test]
+    fn test_latency_distribution() {
+        let seconds: Vec<f64> = (1..=100).map(f64::from).collect();
+        let distribution = LatencyDistribution::from_seconds(&seconds).unwrap();
+        assert_eq!(distribution.count, 100);
+        assert_eq!(distribution.p50, 50.0);
+        assert_eq!(distribution.p90, 90.0);
+        assert_eq!(distribution.p99, 99.0);
+        assert_eq!(distribution.mean, 50.5);
+        assert!(LatencyDistribution::from_seconds(&[]).is_none());
+
+        assert_eq!(format_latency(0.25), "250ms");
+        assert_eq!(format_latency(12.44), "12.4s");
+        assert_eq!(format_latency(185.0), "3m 05s");
+        assert_eq!(
+            distribution.percentiles_label(),
+            "p50 50.0s, p90 1m 30s, p99 1m 39s"
+        );
+    }
+}
@@ -1,26 +1,73 @@
 pub mod analytics;
+pub mod budgets;
 pub mod cache;
+pub mod comparison;
 pub mod conversation;
+pub mod digest;
 pub mod directory;
 pub mod export;
+pub mod file_index;
+pub mod git_commits;
+pub mod heatmap;
+pub mod ical;
+pub mod latency;
 pub mod parser;
+pub mod pricing;
+pub mod prompt_patterns;
+pub mod saved_searches;
 pub mod search;
+pub mod shell_commands;
 pub mod streaming;
+#[cfg(test)]
+pub mod test_support;
 pub mod timeline;
+pub mod timezone;
+pub mod tokenizer;
+pub mod tool_outcomes;
+pub mod usage_blocks;
+pub mod work_sessions;
 
 pub use analytics::{
-    AnalyticsEngine, BasicStats, ConversationAnalytics, ModelAnalytics, ProjectAnalytics,
-    QualityMetrics, TemporalAnalysis, ToolAnalytics,
+    AnalyticsEngine, BasicStats, CacheAnalytics, CacheUsage, ConversationAnalytics, FileAnalytics,
+    FileHotspot, ModelAnalytics, PoorCacheReuse, ProjectAnalytics, QualityMetrics,
+    TemporalAnalysis, ToolAnalytics,
 };
+pub use budgets::{Budget, BudgetMetric, BudgetPeriod, BudgetStatus};
 pub use cache::{CacheMetadata, CacheStats, CachedTimeline, TimelineCache};
-pub use conversation::{Conversation, ConversationEntry, ConversationMessage, MessageRole};
+pub use comparison::{
+    CountDelta, ProjectChange, ProjectComparison, TimelineComparison, ToolComparison,
+};
+pub use conversation::{
+    Conversation, ConversationEntry, ConversationMessage, MessageRole, ToolResult, ToolUse,
+};
+pub use digest::{Digest, FailedToolCall, OpenTodo, ProjectDigest};
 pub use directory::ClaudeDirectory;
 pub use export::{ConversationExporter, ExportConfig, ExportFormat, ExportResult};
+pub use file_index::{FileIndex, FileMatch, FileOperation, FileTouch, SessionFileActivity};
+pub use git_commits::{CommitLinkReason, GitCommit, LinkedCommit, SessionLink};
+pub use heatmap::{CalendarHeatmap, HeatmapMetric};
+pub use ical::WorkSessionEvent;
+pub use latency::{LatencyDistribution, LatencyStats, ResponseTurn};
 pub use parser::{ConversationParser, ConversationStats};
-pub use search::{DateRange, HighlightType, MatchHighlight, SearchEngine, SearchMode, SearchQuery, SearchResult};
+pub use pricing::ModelPricing;
+pub use prompt_patterns::{
+    PromptPatternAnalytics, RepeatedPrompt, SlashCommandKind, SlashCommandUsage,
+};
+pub use saved_searches::{Collection, CollectionSource, HistoryEntry, SavedSearch, SearchStore};
+pub use search::{
+    DateRange, FacetCount, FacetKind, HighlightType, MatchHighlight, MatchSource, RankingWeights,
+    ScoreExplanation, SearchEngine, SearchFacets, SearchField, SearchMode, SearchQuery,
+    SearchResult, SimilarConversation,
+};
+pub use shell_commands::CommandStats;
 pub use streaming::{ConversationMetadata, StreamingConversationParser};
 pub use timeline::{
     ActivityIndicators, ActivityIntensity, ActivityTimeline, ActivityTrend, BarSegment,
     ConversationSummary, ProjectActivity, RankingIndicator, SegmentType, SummaryDepth, TimePeriod,
     TimelineConfig, TopicalSummary,
 };
+pub use timezone::Timezone;
+pub use tokenizer::{Token, Tokenizer};
+pub use tool_outcomes::{ToolOutcome, ToolOutcomeCounts};
+pub use usage_blocks::{BlockProjection, UsageBlock};
+pub use work_sessions::{Timesheet, TimesheetEntry, TimesheetGrouping, WorkSession};
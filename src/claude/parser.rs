@@ -117,6 +117,13 @@ impl ConversationParser {
         ))
     }
 
+    /// Path of the JSONL file a parsed conversation was read from
+    pub fn conversation_file_path(&self, conversation: &Conversation) -> std::path::PathBuf {
+        self.projects_dir()
+            .join(&conversation.project_path)
+            .join(format!("{}.jsonl", conversation.session_id))
+    }
+
     /// Get conversations for a specific project
     pub fn get_project_conversations(
         &self,
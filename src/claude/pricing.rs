@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+
+use super::conversation::{Conversation, ConversationMessage, Usage};
+
+/// Per-million-token prices in USD for a model family
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+impl ModelPricing {
+    /// Approximate list prices, matched on the model family in the model name
+    pub fn for_model(model: &str) -> Option<Self> {
+        let model = model.to_lowercase();
+        if model.contains("opus") {
+            Some(Self {
+                input: 15.0,
+                output: 75.0,
+                cache_write: 18.75,
+                cache_read: 1.50,
+            })
+        } else if model.contains("sonnet") {
+            Some(Self {
+                input: 3.0,
+                output: 15.0,
+                cache_write: 3.75,
+                cache_read: 0.30,
+            })
+        } else if model.contains("haiku") {
+            Some(Self {
+                input: 0.80,
+                output: 4.0,
+                cache_write: 1.0,
+                cache_read: 0.08,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Cost in USD of one message's usage
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        let per_token = |tokens: u32, price: f64| tokens as f64 * price / 1_000_000.0;
+        per_token(usage.input_tokens, self.input)
+            + per_token(usage.output_tokens, self.output)
+            + per_token(
+                usage.cache_creation_input_tokens.unwrap_or(0),
+                self.cache_write,
+            )
+            + per_token(usage.cache_read_input_tokens.unwrap_or(0), self.cache_read)
+    }
+
+    /// Saving in USD from prompt caching on one message, compared with
+    /// billing cache reads and writes as plain input; negative when cache
+    /// writes cost more than reads saved
+    pub fn cache_savings(&self, usage: &Usage) -> f64 {
+        let per_token = |tokens: u32, price: f64| tokens as f64 * price / 1_000_000.0;
+        per_token(
+            usage.cache_read_input_tokens.unwrap_or(0),
+            self.input - self.cache_read,
+        ) - per_token(
+            usage.cache_creation_input_tokens.unwrap_or(0),
+            self.cache_write - self.input,
+        )
+    }
+}
+
+impl Usage {
+    /// All tokens billed for the message: input, output and cache reads/writes
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens as u64
+            + self.output_tokens as u64
+            + self.cache_creation_input_tokens.unwrap_or(0) as u64
+            + self.cache_read_input_tokens.unwrap_or(0) as u64
+    }
+}
+
+/// Messages with usage, counting each API response once: a response split
+/// over several entries shares one response id, and resumed sessions copy
+/// earlier entries with their uuids
+pub fn billed_usage<'a>(
+    messages: impl IntoIterator<Item = &'a ConversationMessage>,
+) -> impl Iterator<Item = (&'a ConversationMessage, &'a Usage)> {
+    let mut seen = HashSet::new();
+    messages.into_iter().filter_map(move |message| {
+        let usage = message.usage.as_ref()?;
+        let key = message.message_id.as_deref().unwrap_or(&message.uuid);
+        seen.insert(key).then_some((message, usage))
+    })
+}
+
+/// Estimated cost (USD) of a message's usage, 0 for models without known pricing
+pub fn message_cost(message: &ConversationMessage, usage: &Usage) -> f64 {
+    message
+        .model
+        .as_deref()
+        .and_then(ModelPricing::for_model)
+        .map_or(0.0, |pricing| pricing.cost(usage))
+}
+
+/// Total tokens and estimated cost (USD) of a conversation; models without
+/// known pricing count towards tokens but not cost
+pub fn conversation_usage(conversation: &Conversation) -> (u64, f64) {
+    billed_usage(&conversation.messages).fold((0, 0.0), |(tokens, cost), (message, usage)| {
+        (
+            tokens + usage.total_tokens(),
+            cost + message_cost(message, usage),
+        )
+    })
+}
+
+/// A token count as e.g. `850 tokens`, `12.4k tokens` or `1.2M tokens`
+pub fn format_tokens(tokens: u64) -> String {
+    let tokens = tokens as f64;
+    if tokens >= 1_000_000.0 {
+        format!("{:.1}M tokens", tokens / 1_000_000.0)
+    } else if tokens >= 1_000.0 {
+        format!("{:.1}k tokens", tokens / 1_000.0)
+    } else {
+        format!("{} tokens", tokens as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_pricing() {
+        let usage = Usage {
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_creation_input_tokens: Some(0),
+            cache_read_input_tokens: Some(1_000_000),
+            service_tier: None,
+        };
+        assert_eq!(usage.total_tokens(), 2_100_000);
+
+        let sonnet = ModelPricing::for_model("claude-sonnet-4-20250514").unwrap();
+        assert!((sonnet.cost(&usage) - 4.8).abs() < 1e-9);
+        assert!(ModelPricing::for_model("gpt-4").is_none());
+        // 1M cache reads at $0.30 instead of $3.00
+        assert!((sonnet.cache_savings(&usage) - 2.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_format_tokens() {
+        assert_eq!(format_tokens(850), "850 tokens");
+        assert_eq!(format_tokens(27_000), "27.0k tokens");
+        assert_eq!(format_tokens(2_500_000), "2.5M tokens");
+    }
+
+    #[test]
+    fn test_usage_counted_once_per_response() {
+        use crate::claude::test_support::usage;
+        use chrono::Utc;
+
+        // One response split over a text and a tool use entry, then a second
+        // response and a resumed copy of it
+        let response = |id: &str| {
+            ConversationMessage::assistant(Utc::now())
+                .with_message_id(id)
+                .with_model("claude-sonnet-4")
+                .with_usage(usage(1_000_000, 0))
+        };
+        let second = response("msg_2");
+        let conversation = Conversation::new_for_test(
+            "s1",
+            "-repo",
+            vec![response("msg_1"), response("msg_1"), second.clone(), second],
+        );
+
+        let (tokens, cost) = conversation_usage(&conversation);
+        assert_eq!(tokens, 2_000_000);
+        assert!((cost - 6.0).abs() < 1e-9);
+    }
+}
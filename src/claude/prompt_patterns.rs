@@ -0,0 +1,339 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::conversation::Conversation;
+use super::timezone::Timezone;
+
+/// Commands that ship with Claude Code
+const BUILT_IN_COMMANDS: [&str; 22] = [
+    "add-dir",
+    "agents",
+    "bug",
+    "clear",
+    "compact",
+    "config",
+    "cost",
+    "doctor",
+    "help",
+    "init",
+    "login",
+    "logout",
+    "mcp",
+    "memory",
+    "model",
+    "permissions",
+    "pr_comments",
+    "resume",
+    "review",
+    "status",
+    "terminal-setup",
+    "vim",
+];
+
+/// Repeated prompts kept, most repeated first
+pub const REPEATED_PROMPT_LIMIT: usize = 20;
+
+/// Prompts longer than this are too specific to count as repeats
+const MAX_REPEATED_PROMPT_CHARS: usize = 200;
+
+/// Where a slash command comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlashCommandKind {
+    BuiltIn,
+    /// `/project:` commands from the repository's `.claude/commands`
+    Project,
+    /// `/user:` commands from the user's global configuration
+    User,
+    /// `/mcp__<server>__<prompt>` prompts from MCP servers
+    Mcp,
+    /// Custom commands without a namespace prefix
+    Custom,
+}
+
+impl SlashCommandKind {
+    pub fn of(command: &str) -> Self {
+        let name = command.trim_start_matches('/');
+        if name.starts_with("project:") {
+            Self::Project
+        } else if name.starts_with("user:") {
+            Self::User
+        } else if name.starts_with("mcp__") {
+            Self::Mcp
+        } else if BUILT_IN_COMMANDS.contains(&name) {
+            Self::BuiltIn
+        } else {
+            Self::Custom
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::BuiltIn => "built-in",
+            Self::Project => "project",
+            Self::User => "user",
+            Self::Mcp => "mcp",
+            Self::Custom => "custom",
+        }
+    }
+}
+
+/// The slash command a user message invokes, from its `<command-name>` marker
+/// or a leading `/name`
+pub fn slash_command(content: &str) -> Option<String> {
+    if let Some((_, rest)) = content.split_once("<command-name>") {
+        if let Some((name, _)) = rest.split_once("</command-name>") {
+            let name = name.trim();
+            if !name.is_empty() {
+                return Some(format!("/{}", name.trim_start_matches('/')));
+            }
+        }
+    }
+
+    let rest = content.trim_start().strip_prefix('/')?;
+    let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+    let name = &rest[..end];
+    let is_command_name = !name.is_empty()
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'));
+    is_command_name.then(|| format!("/{}", name))
+}
+
+/// A typed prompt reduced to a form that matches its repeats: lowercase with
+/// collapsed whitespace. Slash commands, command output and long prompts
+/// don't count
+fn normalized_prompt(content: &str) -> Option<String> {
+    let content = content.trim();
+    if content.is_empty()
+        || content.starts_with('<')
+        || content.starts_with('/')
+        || content.chars().count() > MAX_REPEATED_PROMPT_CHARS
+    {
+        return None;
+    }
+    Some(
+        content
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase(),
+    )
+}
+
+/// Uses of one slash command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashCommandUsage {
+    /// Command including its leading slash, e.g. `/project:deploy`
+    pub name: String,
+    pub kind: SlashCommandKind,
+    pub uses: usize,
+    /// Conversations that used the command
+    pub sessions: usize,
+    /// Uses per project
+    pub projects: BTreeMap<String, usize>,
+    pub last_used: DateTime<Utc>,
+}
+
+/// A prompt the user typed more than once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedPrompt {
+    /// The prompt, lowercased with whitespace collapsed
+    pub prompt: String,
+    pub count: usize,
+    /// Conversations the prompt was typed in
+    pub sessions: usize,
+    pub projects: Vec<String>,
+    pub last_used: DateTime<Utc>,
+}
+
+/// Slash commands and repeated prompts across conversations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptPatternAnalytics {
+    /// Slash commands, most used first
+    pub slash_commands: Vec<SlashCommandUsage>,
+    /// Slash command uses per day, by command
+    pub slash_commands_per_day: BTreeMap<String, BTreeMap<String, usize>>,
+    /// Prompts typed at least twice, most repeated first
+    pub repeated_prompts: Vec<RepeatedPrompt>,
+}
+
+impl PromptPatternAnalytics {
+    pub fn from_conversations<'a>(
+        conversations: impl IntoIterator<Item = &'a Conversation>,
+        timezone: &Timezone,
+    ) -> Self {
+        let mut commands: HashMap<String, (SlashCommandUsage, BTreeSet<&str>)> = HashMap::new();
+        let mut prompts: HashMap<String, (RepeatedPrompt, BTreeSet<&str>, BTreeSet<&str>)> =
+            HashMap::new();
+        let mut slash_commands_per_day: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+
+        for conversation in conversations {
+            let session = conversation.session_id.as_str();
+            let project = conversation.project_path.as_str();
+
+            for message in conversation.messages.iter().filter(|m| m.is_user_prompt()) {
+                if let Some(name) = slash_command(&message.content) {
+                    *slash_commands_per_day
+                        .entry(timezone.day_key(message.timestamp))
+                        .or_default()
+                        .entry(name.clone())
+                        .or_insert(0) += 1;
+
+                    let (usage, sessions) = commands.entry(name.clone()).or_insert_with(|| {
+                        (
+                            SlashCommandUsage {
+                                kind: SlashCommandKind::of(&name),
+                                name,
+                                uses: 0,
+                                sessions: 0,
+                                projects: BTreeMap::new(),
+                                last_used: message.timestamp,
+                            },
+                            BTreeSet::new(),
+                        )
+                    });
+                    usage.uses += 1;
+                    *usage.projects.entry(project.to_string()).or_insert(0) += 1;
+                    usage.last_used = usage.last_used.max(message.timestamp);
+                    sessions.insert(session);
+                } else if let Some(prompt) = normalized_prompt(&message.content) {
+                    let (repeated, sessions, projects) =
+                        prompts.entry(prompt.clone()).or_insert_with(|| {
+                            (
+                                RepeatedPrompt {
+                                    prompt,
+                                    count: 0,
+                                    sessions: 0,
+                                    projects: Vec::new(),
+                                    last_used: message.timestamp,
+                                },
+                                BTreeSet::new(),
+                                BTreeSet::new(),
+                            )
+                        });
+                    repeated.count += 1;
+                    repeated.last_used = repeated.last_used.max(message.timestamp);
+                    sessions.insert(session);
+                    projects.insert(project);
+                }
+            }
+        }
+
+        let mut slash_commands: Vec<SlashCommandUsage> = commands
+            .into_values()
+            .map(|(mut usage, sessions)| {
+                usage.sessions = sessions.len();
+                usage
+            })
+            .collect();
+        slash_commands.sort_by(|a, b| b.uses.cmp(&a.uses).then_with(|| a.name.cmp(&b.name)));
+
+        let mut repeated_prompts: Vec<RepeatedPrompt> = prompts
+            .into_values()
+            .filter(|(repeated, _, _)| repeated.count > 1)
+            .map(|(mut repeated, sessions, projects)| {
+                repeated.sessions = sessions.len();
+                repeated.projects = projects.into_iter().map(str::to_string).collect();
+                repeated
+            })
+            .collect();
+        repeated_prompts
+            .sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.prompt.cmp(&b.prompt)));
+        repeated_prompts.truncate(REPEATED_PROMPT_LIMIT);
+
+        Self {
+            slash_commands,
+            slash_commands_per_day,
+            repeated_prompts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::conversation::ConversationMessage;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_slash_command_detection() {
+        assert_eq!(slash_command("/review"), Some("/review".to_string()));
+        assert_eq!(
+            slash_command("/project:deploy staging"),
+            Some("/project:deploy".to_string())
+        );
+        assert_eq!(
+            slash_command(
+                "<command-message>fix-issue is running…</command-message>\n<command-name>/fix-issue</command-name>\n<command-args>123</command-args>"
+            ),
+            Some("/fix-issue".to_string())
+        );
+        assert_eq!(slash_command("/usr/bin/env is missing"), None);
+        assert_eq!(slash_command("please /review this"), None);
+
+        assert_eq!(SlashCommandKind::of("/compact"), SlashCommandKind::BuiltIn);
+        assert_eq!(
+            SlashCommandKind::of("/project:deploy"),
+            SlashCommandKind::Project
+        );
+        assert_eq!(
+            SlashCommandKind::of("/user:standup"),
+            SlashCommandKind::User
+        );
+        assert_eq!(
+            SlashCommandKind::of("/mcp__github__pr"),
+            SlashCommandKind::Mcp
+        );
+        assert_eq!(SlashCommandKind::of("/fix-issue"), SlashCommandKind::Custom);
+    }
+
+    #[test]
+    fn test_prompt_patterns() {
+        let start = Utc.with_ymd_and_hms(2024, 6, 1, 10, 0, 0).unwrap();
+        let conversation = |session_id: &str, project: &str, prompts: &[&str]| {
+            let messages = prompts
+                .iter()
+                .enumerate()
+                .map(|(i, prompt)| {
+                    ConversationMessage::user(start + Duration::days(i as i64))
+                        .with_content(*prompt)
+                })
+                .collect();
+            Conversation::new_for_test(session_id, project, messages)
+        };
+        let conversations = [
+            conversation(
+                "s1",
+                "-api",
+                &[
+                    "/review",
+                    "Run the tests",
+                    "<command-name>/compact</command-name>",
+                ],
+            ),
+            conversation("s2", "-api", &["/review src/lib.rs", "run  the TESTS"]),
+            conversation("s3", "-web", &["/project:deploy", "Fix the build"]),
+        ];
+
+        let patterns = PromptPatternAnalytics::from_conversations(&conversations, &Timezone::Utc);
+        let review = &patterns.slash_commands[0];
+        assert_eq!(review.name, "/review");
+        assert_eq!(review.kind, SlashCommandKind::BuiltIn);
+        assert_eq!(review.uses, 2);
+        assert_eq!(review.sessions, 2);
+        assert_eq!(review.projects["-api"], 2);
+        assert_eq!(patterns.slash_commands.len(), 3);
+        assert_eq!(patterns.slash_commands_per_day["2024-06-01"]["/review"], 2);
+        assert_eq!(patterns.slash_commands_per_day["2024-06-03"]["/compact"], 1);
+
+        assert_eq!(patterns.repeated_prompts.len(), 1);
+        let repeated = &patterns.repeated_prompts[0];
+        assert_eq!(repeated.prompt, "run the tests");
+        assert_eq!(repeated.count, 2);
+        assert_eq!(repeated.sessions, 2);
+        assert_eq!(repeated.projects, ["-api"]);
+    }
+}
@@ -0,0 +1,346 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::conversation::Conversation;
+use super::search::{SearchEngine, SearchQuery};
+use crate::config::AppConfig;
+use crate::errors::ClaudeToolsError;
+
+/// A named search: the query as typed plus every `SearchQuery` filter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    /// Query text as the user typed it
+    pub query_text: String,
+    pub query: SearchQuery,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A previously executed search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub query_text: String,
+    pub query: SearchQuery,
+    pub searched_at: DateTime<Utc>,
+    pub result_count: usize,
+}
+
+/// What a collection contains
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum CollectionSource {
+    /// Conversations matching a query, re-evaluated each time
+    Query {
+        query_text: String,
+        query: Box<SearchQuery>,
+    },
+    /// A hand-picked set of sessions
+    Sessions { session_ids: Vec<String> },
+}
+
+/// A named set of conversations that list, export and stats can target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    pub source: CollectionSource,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Saved searches, search history and collections, persisted as JSON
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchStore {
+    #[serde(default)]
+    pub saved: BTreeMap<String, SavedSearch>,
+    #[serde(default)]
+    pub history: Vec<HistoryEntry>,
+    #[serde(default)]
+    pub collections: BTreeMap<String, Collection>,
+    /// File the store was loaded from and saves to
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl SearchStore {
+    /// Store file name within the app config directory
+    const FILE_NAME: &'static str = "claude-tools-searches.json";
+
+    /// Maximum number of history entries kept
+    pub const MAX_HISTORY: usize = 100;
+
+    /// Default store location, next to the app configuration file
+    pub fn default_path() -> Result<PathBuf, ClaudeToolsError> {
+        let config_path = AppConfig::default_config_path()?;
+        let config_dir = config_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        Ok(config_dir.join(Self::FILE_NAME))
+    }
+
+    /// Load the store from its default location
+    pub fn load_default() -> Result<Self, ClaudeToolsError> {
+        Self::load(Self::default_path()?)
+    }
+
+    /// Load the store from a file, starting empty if it doesn't exist yet
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ClaudeToolsError> {
+        let path = path.as_ref();
+        let mut store = if path.exists() {
+            let content = fs::read_to_string(path)?;
+            serde_json::from_str::<SearchStore>(&content)?
+        } else {
+            SearchStore::default()
+        };
+        store.path = path.to_path_buf();
+        Ok(store)
+    }
+
+    /// Write the store back to the file it was loaded from
+    pub fn save(&self) -> Result<(), ClaudeToolsError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write to a temporary file first, then rename (atomic operation)
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// File backing this store
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Save (or overwrite) a named search
+    pub fn save_search(&mut self, name: &str, query_text: &str, query: SearchQuery) {
+        self.saved.insert(
+            name.to_string(),
+            SavedSearch {
+                query_text: query_text.to_string(),
+                query,
+                created_at: Utc::now(),
+            },
+        );
+    }
+
+    /// Look up a named search
+    pub fn saved_search(&self, name: &str) -> Result<&SavedSearch, ClaudeToolsError> {
+        self.saved
+            .get(name)
+            .ok_or_else(|| ClaudeToolsError::Config(format!("No saved search named '{}'", name)))
+    }
+
+    /// Record an executed search, newest last; repeating the latest query replaces it
+    pub fn record_history(&mut self, query_text: &str, query: SearchQuery, result_count: usize) {
+        if self
+            .history
+            .last()
+            .is_some_and(|last| last.query_text == query_text)
+        {
+            self.history.pop();
+        }
+
+        self.history.push(HistoryEntry {
+            query_text: query_text.to_string(),
+            query,
+            searched_at: Utc::now(),
+            result_count,
+        });
+
+        if self.history.len() > Self::MAX_HISTORY {
+            let excess = self.history.len() - Self::MAX_HISTORY;
+            self.history.drain(..excess);
+        }
+    }
+
+    /// History entries, most recent first, without repeated query text
+    pub fn recent_history(&self) -> Vec<&HistoryEntry> {
+        let mut seen = HashSet::new();
+        self.history
+            .iter()
+            .rev()
+            .filter(|entry| seen.insert(entry.query_text.as_str()))
+            .collect()
+    }
+
+    /// Create (or replace) a collection
+    pub fn create_collection(&mut self, name: &str, source: CollectionSource) {
+        self.collections.insert(
+            name.to_string(),
+            Collection {
+                source,
+                created_at: Utc::now(),
+            },
+        );
+    }
+
+    /// Add sessions to a hand-picked collection, creating it if needed
+    pub fn add_to_collection(
+        &mut self,
+        name: &str,
+        session_ids: &[String],
+    ) -> Result<(), ClaudeToolsError> {
+        let collection = self
+            .collections
+            .entry(name.to_string())
+            .or_insert_with(|| Collection {
+                source: CollectionSource::Sessions {
+                    session_ids: Vec::new(),
+                },
+                created_at: Utc::now(),
+            });
+
+        match collection.source {
+            CollectionSource::Sessions {
+                session_ids: ref mut existing,
+            } => {
+                for id in session_ids {
+                    if !existing.contains(id) {
+                        existing.push(id.clone());
+                    }
+                }
+                Ok(())
+            }
+            CollectionSource::Query { .. } => Err(ClaudeToolsError::Config(format!(
+                "Collection '{}' is defined by a query; sessions can't be added to it",
+                name
+            ))),
+        }
+    }
+
+    /// Look up a collection
+    pub fn collection(&self, name: &str) -> Result<&Collection, ClaudeToolsError> {
+        self.collections
+            .get(name)
+            .ok_or_else(|| ClaudeToolsError::Config(format!("No collection named '{}'", name)))
+    }
+
+    /// Conversations belonging to a collection, in the collection's order
+    pub fn collection_conversations(
+        &self,
+        name: &str,
+        conversations: Vec<Conversation>,
+    ) -> Result<Vec<Conversation>, ClaudeToolsError> {
+        match &self.collection(name)?.source {
+            CollectionSource::Sessions { session_ids } => {
+                let mut by_id: std::collections::HashMap<String, Conversation> = conversations
+                    .into_iter()
+                    .map(|conv| (conv.session_id.clone(), conv))
+                    .collect();
+                Ok(session_ids
+                    .iter()
+                    .filter_map(|id| by_id.remove(id))
+                    .collect())
+            }
+            CollectionSource::Query { query, .. } => {
+                let mut engine = SearchEngine::new();
+                engine.build_index(conversations)?;
+                let mut query = query.as_ref().clone();
+                query.max_results = None;
+                Ok(engine
+                    .search(&query)?
+                    .into_iter()
+                    .map(|result| result.conversation)
+                    .collect())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn conversation(session_id: &str, summary: &str) -> Conversation {
+        Conversation::new_for_test(session_id, "project", Vec::new()).with_summary(summary)
+    }
+
+    #[test]
+    fn test_save_and_load_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("searches.json");
+
+        let mut store = SearchStore::load(&path).unwrap();
+        assert!(store.saved.is_empty());
+
+        let query = SearchQuery::text("deploy")
+            .with_project("api")
+            .with_max_results(5);
+        store.save_search("deploys", "deploy", query);
+        store.save().unwrap();
+
+        let loaded = SearchStore::load(&path).unwrap();
+        let saved = loaded.saved_search("deploys").unwrap();
+        assert_eq!(saved.query_text, "deploy");
+        assert_eq!(saved.query.project_filter.as_deref(), Some("api"));
+        assert_eq!(saved.query.max_results, Some(5));
+        assert!(loaded.saved_search("missing").is_err());
+    }
+
+    #[test]
+    fn test_history_dedup_and_cap() {
+        let mut store = SearchStore::default();
+        store.record_history("rust", SearchQuery::text("rust"), 3);
+        store.record_history("rust", SearchQuery::text("rust"), 4);
+        assert_eq!(store.history.len(), 1);
+        assert_eq!(store.history[0].result_count, 4);
+
+        store.record_history("python", SearchQuery::text("python"), 1);
+        store.record_history("rust", SearchQuery::text("rust"), 2);
+        let recent: Vec<&str> = store
+            .recent_history()
+            .iter()
+            .map(|entry| entry.query_text.as_str())
+            .collect();
+        assert_eq!(recent, vec!["rust", "python"]);
+
+        for i in 0..(SearchStore::MAX_HISTORY + 10) {
+            store.record_history(&format!("q{}", i), SearchQuery::default(), 0);
+        }
+        assert_eq!(store.history.len(), SearchStore::MAX_HISTORY);
+    }
+
+    #[test]
+    fn test_collections() {
+        let mut store = SearchStore::default();
+        let conversations = || {
+            vec![
+                conversation("a", "Rust error handling"),
+                conversation("b", "Python packaging"),
+                conversation("c", "More Rust lifetimes"),
+            ]
+        };
+
+        store
+            .add_to_collection("picked", &["c".to_string(), "a".to_string()])
+            .unwrap();
+        store
+            .add_to_collection("picked", &["a".to_string()])
+            .unwrap();
+        let picked = store
+            .collection_conversations("picked", conversations())
+            .unwrap();
+        let ids: Vec<&str> = picked.iter().map(|c| c.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "a"]);
+
+        store.create_collection(
+            "rusty",
+            CollectionSource::Query {
+                query_text: "rust".to_string(),
+                query: Box::new(SearchQuery::text("rust")),
+            },
+        );
+        let rusty = store
+            .collection_conversations("rusty", conversations())
+            .unwrap();
+        assert_eq!(rusty.len(), 2);
+        assert!(store
+            .add_to_collection("rusty", &["b".to_string()])
+            .is_err());
+        assert!(store.collection("missing").is_err());
+    }
+}
@@ -1,10 +1,13 @@
-use super::conversation::{Conversation, ConversationMessage};
+use super::conversation::{Conversation, ConversationMessage, MessageRole as ConversationRole};
+use super::tokenizer::Tokenizer;
 use crate::errors::ClaudeToolsError;
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
 use lru::LruCache;
 use rayon::prelude::*;
 use regex::Regex;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroUsize;
 
 /// Advanced search engine for Claude Code conversations
@@ -19,8 +22,78 @@ pub struct SearchEngine {
     result_cache: LruCache<u64, Vec<SearchResult>>,
     /// All conversations for reference
     conversations: Vec<Conversation>,
+    /// Code-aware tokenizer used for indexing, scoring and highlighting
+    tokenizer: Tokenizer,
+    /// Lazily built TF-IDF vectors per conversation for similarity queries
+    document_vectors: Option<HashMap<String, TermVector>>,
+    /// Weights blending TF-IDF, recency and length into a relevance score
+    weights: RankingWeights,
 }
 
+/// Tunable weights used by relevance scoring
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankingWeights {
+    /// Multiplier applied to the summed per-term TF-IDF contributions
+    pub term_weight: f64,
+    /// Score added when a boolean query term appears in the conversation summary
+    pub summary_weight: f64,
+    /// Extra boost for a conversation updated today, decaying linearly to none
+    pub recency_boost: f64,
+    /// Days over which the recency boost decays
+    pub recency_window_days: f64,
+    /// Multiplier for conversations within the preferred length range
+    pub length_boost: f64,
+    /// Preferred conversation length range, in messages
+    pub length_min_messages: usize,
+    pub length_max_messages: usize,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            term_weight: 1.0,
+            summary_weight: 0.5,
+            recency_boost: 0.5,
+            recency_window_days: 30.0,
+            length_boost: 1.1,
+            length_min_messages: 5,
+            length_max_messages: 50,
+        }
+    }
+}
+
+/// Contribution of a single query term to a relevance score
+#[derive(Debug, Clone, Serialize)]
+pub struct TermContribution {
+    pub term: String,
+    /// Index entries for the term in this conversation (message blocks containing it)
+    pub occurrences: usize,
+    pub tf: f64,
+    pub idf: f64,
+    /// Summary bonus (boolean queries only)
+    pub summary_bonus: f64,
+    pub contribution: f64,
+}
+
+/// Breakdown of how a result's relevance score was computed
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreExplanation {
+    /// Scoring path: text, boolean, regex or filter
+    pub mode: String,
+    pub terms: Vec<TermContribution>,
+    pub term_weight: f64,
+    /// Factor from `RankingWeights::recency_boost` (1.0 when not applied)
+    pub recency_factor: f64,
+    /// Factor from `RankingWeights::length_boost` (1.0 when not applied)
+    pub length_factor: f64,
+    /// Human-readable filters that restricted the candidate set
+    pub filters: Vec<String>,
+    pub score: f64,
+}
+
+/// Sparse TF-IDF term vector
+type TermVector = HashMap<String, f64>;
+
 /// Inverted index for efficient text search
 #[derive(Debug, Clone)]
 pub struct InvertedIndex {
@@ -39,6 +112,7 @@ pub struct IndexEntry {
     pub message_index: usize,
     pub position: usize,
     pub term_frequency: usize,
+    pub field: SearchField,
 }
 
 /// Date-based index for temporal filtering
@@ -56,7 +130,8 @@ pub struct ConversationDateEntry {
 }
 
 /// Search query with various filtering options
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SearchQuery {
     pub text: Option<String>,
     pub regex_pattern: Option<String>,
@@ -72,10 +147,12 @@ pub struct SearchQuery {
     pub max_duration_minutes: Option<u32>,
     pub search_mode: SearchMode,
     pub max_results: Option<usize>,
+    /// Message fields to search (message text, tool inputs, tool results)
+    pub fields: Vec<SearchField>,
 }
 
 /// Boolean query representation for complex search logic
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BooleanQuery {
     /// Single search term
     Term(String),
@@ -90,7 +167,7 @@ pub enum BooleanQuery {
 }
 
 /// Message role for filtering
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MessageRole {
     User,
     Assistant,
@@ -99,7 +176,7 @@ pub enum MessageRole {
 }
 
 /// Search modes supported by the engine
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SearchMode {
     /// Simple case-insensitive text matching
     Text,
@@ -112,7 +189,7 @@ pub enum SearchMode {
 }
 
 /// Date range filter
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DateRange {
     pub start: Option<DateTime<Utc>>,
     pub end: Option<DateTime<Utc>>,
@@ -121,6 +198,20 @@ pub struct DateRange {
 /// Boolean query parser for complex search expressions
 pub struct BooleanQueryParser;
 
+impl BooleanQuery {
+    /// Terms that contribute to the score (those not under a NOT)
+    pub fn positive_terms(&self) -> Vec<String> {
+        match self {
+            BooleanQuery::Term(term) => vec![term.clone()],
+            BooleanQuery::And(left, right) | BooleanQuery::Or(left, right) => {
+                [left.positive_terms(), right.positive_terms()].concat()
+            }
+            BooleanQuery::Not(_) => Vec::new(),
+            BooleanQuery::Group(inner) => inner.positive_terms(),
+        }
+    }
+}
+
 impl BooleanQueryParser {
     /// Parse a boolean query string into a BooleanQuery AST
     pub fn parse(input: &str) -> Result<BooleanQuery, ClaudeToolsError> {
@@ -282,6 +373,210 @@ pub struct SearchResult {
     pub matched_messages: Vec<usize>, // Indices of messages that matched
 }
 
+/// Dimension that search results can be broken down by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FacetKind {
+    Project,
+    Model,
+    Tool,
+    Role,
+    Month,
+    Source,
+}
+
+impl FacetKind {
+    /// Short label used in CLI and TUI output
+    pub fn label(&self) -> &'static str {
+        match self {
+            FacetKind::Project => "project",
+            FacetKind::Model => "model",
+            FacetKind::Tool => "tool",
+            FacetKind::Role => "role",
+            FacetKind::Month => "month",
+            FacetKind::Source => "source",
+        }
+    }
+}
+
+/// Number of matched conversations sharing a facet value
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// Facet counts over a set of search results
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchFacets {
+    pub projects: Vec<FacetCount>,
+    pub models: Vec<FacetCount>,
+    pub tools: Vec<FacetCount>,
+    pub roles: Vec<FacetCount>,
+    pub months: Vec<FacetCount>,
+    pub sources: Vec<FacetCount>,
+}
+
+impl SearchFacets {
+    /// Count, per facet value, how many of the results contain it
+    pub fn from_results(results: &[SearchResult]) -> Self {
+        let mut counts: HashMap<FacetKind, HashMap<String, usize>> = HashMap::new();
+
+        for result in results {
+            let conv = &result.conversation;
+            let mut values: HashSet<(FacetKind, String)> = HashSet::new();
+
+            values.insert((FacetKind::Project, conv.project_path.clone()));
+            if let Some(started) = conv.started_at {
+                values.insert((FacetKind::Month, started.format("%Y-%m").to_string()));
+            }
+            for message in &conv.messages {
+                if let Some(ref model) = message.model {
+                    values.insert((FacetKind::Model, model.clone()));
+                }
+                for tool_use in &message.tool_uses {
+                    values.insert((FacetKind::Tool, tool_use.name.clone()));
+                }
+            }
+
+            // Roles of the matched messages; a filter-only query matches them all
+            let matched: HashSet<usize> = result
+                .match_highlights
+                .iter()
+                .map(|highlight| highlight.message_index)
+                .collect();
+            for (index, message) in conv.messages.iter().enumerate() {
+                if !matched.is_empty() && !matched.contains(&index) {
+                    continue;
+                }
+                let role = match message.role {
+                    ConversationRole::User => "user",
+                    ConversationRole::Assistant => "assistant",
+                    ConversationRole::System => "system",
+                    ConversationRole::Tool => "tool",
+                };
+                values.insert((FacetKind::Role, role.to_string()));
+            }
+            for highlight in &result.match_highlights {
+                let source = match highlight.source.field() {
+                    SearchField::Text => "text",
+                    SearchField::Tools => "tools",
+                    SearchField::Results => "results",
+                };
+                values.insert((FacetKind::Source, source.to_string()));
+            }
+
+            for (kind, value) in values {
+                *counts.entry(kind).or_default().entry(value).or_insert(0) += 1;
+            }
+        }
+
+        let mut take = |kind: FacetKind| {
+            let mut facet: Vec<FacetCount> = counts
+                .remove(&kind)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(value, count)| FacetCount { value, count })
+                .collect();
+            if kind == FacetKind::Month {
+                // Months read best newest first
+                facet.sort_by(|a, b| b.value.cmp(&a.value));
+            } else {
+                facet.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+            }
+            facet
+        };
+
+        Self {
+            projects: take(FacetKind::Project),
+            models: take(FacetKind::Model),
+            tools: take(FacetKind::Tool),
+            roles: take(FacetKind::Role),
+            months: take(FacetKind::Month),
+            sources: take(FacetKind::Source),
+        }
+    }
+
+    /// Facet groups in display order
+    pub fn groups(&self) -> Vec<(FacetKind, &[FacetCount])> {
+        vec![
+            (FacetKind::Project, self.projects.as_slice()),
+            (FacetKind::Model, self.models.as_slice()),
+            (FacetKind::Tool, self.tools.as_slice()),
+            (FacetKind::Role, self.roles.as_slice()),
+            (FacetKind::Month, self.months.as_slice()),
+            (FacetKind::Source, self.sources.as_slice()),
+        ]
+    }
+
+    /// Whether no facet has any value
+    pub fn is_empty(&self) -> bool {
+        self.groups().iter().all(|(_, values)| values.is_empty())
+    }
+}
+
+/// A conversation ranked by cosine similarity to a reference
+#[derive(Debug, Clone)]
+pub struct SimilarConversation {
+    pub conversation: Conversation,
+    pub similarity: f64,
+}
+
+/// A matching message (or tool block within it) with surrounding context lines
+#[derive(Debug, Clone)]
+pub struct MessageHit {
+    pub message_index: usize,
+    pub message_uuid: String,
+    pub role: ConversationRole,
+    pub timestamp: DateTime<Utc>,
+    /// Part of the message the lines come from
+    pub source: MatchSource,
+    /// First and last matching line (1-based, inclusive)
+    pub line_range: (usize, usize),
+    /// Matching lines and their context, in order
+    pub lines: Vec<ContextLine>,
+}
+
+/// Machine-readable form of a search result with stable field names
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResultRecord {
+    pub session_id: String,
+    pub project: String,
+    pub score: f64,
+    pub match_count: usize,
+    pub matched_message_uuids: Vec<String>,
+    pub highlights: Vec<HighlightRecord>,
+    /// Score breakdown, present when explanation was requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<ScoreExplanation>,
+}
+
+/// Machine-readable form of a match highlight
+#[derive(Debug, Clone, Serialize)]
+pub struct HighlightRecord {
+    pub message_uuid: String,
+    /// Field the offsets refer to (text, tools or results)
+    pub field: SearchField,
+    /// Index of the tool use or tool result within the message, for tool fields
+    pub block_index: Option<usize>,
+    /// Byte offsets of the match within the field text
+    pub start: usize,
+    pub end: usize,
+    pub matched_text: String,
+    /// Single-line excerpt around the match
+    pub snippet: String,
+}
+
+/// A line of a message hit
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextLine {
+    /// Line number within the message block (1-based)
+    pub number: usize,
+    pub text: String,
+    /// Whether the line contains a match (otherwise it is context)
+    pub is_match: bool,
+}
+
 /// Result of boolean query evaluation
 #[derive(Debug, Clone)]
 struct BooleanResult {
@@ -301,6 +596,92 @@ pub enum HighlightType {
     InConversationSearch,
 }
 
+/// Searchable fields of a conversation message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchField {
+    /// Message text content
+    Text,
+    /// Tool call inputs (command, file path, pattern, edited strings)
+    Tools,
+    /// Tool result output
+    Results,
+}
+
+impl SearchField {
+    /// All searchable fields
+    pub fn all() -> Vec<SearchField> {
+        vec![SearchField::Text, SearchField::Tools, SearchField::Results]
+    }
+}
+
+/// Part of a message a highlight refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchSource {
+    /// Message text content
+    Text,
+    /// Searchable text of the tool use at this index (see `ToolUse::searchable_text`)
+    ToolInput(usize),
+    /// Content of the tool result at this index
+    ToolResult(usize),
+}
+
+impl MatchSource {
+    /// Field this source belongs to
+    pub fn field(&self) -> SearchField {
+        match self {
+            MatchSource::Text => SearchField::Text,
+            MatchSource::ToolInput(_) => SearchField::Tools,
+            MatchSource::ToolResult(_) => SearchField::Results,
+        }
+    }
+
+    /// Text that highlight offsets from this source refer to
+    pub fn text<'a>(&self, message: &'a ConversationMessage) -> Option<Cow<'a, str>> {
+        match *self {
+            MatchSource::Text => Some(Cow::Borrowed(message.content.as_str())),
+            MatchSource::ToolInput(idx) => message
+                .tool_uses
+                .get(idx)
+                .map(|tool_use| Cow::Owned(tool_use.searchable_text())),
+            MatchSource::ToolResult(idx) => message
+                .tool_results
+                .get(idx)
+                .map(|result| Cow::Borrowed(result.content.as_str())),
+        }
+    }
+}
+
+/// Searchable blocks of a message restricted to the given fields
+fn searchable_blocks<'a>(
+    message: &'a ConversationMessage,
+    fields: &[SearchField],
+) -> Vec<(MatchSource, Cow<'a, str>)> {
+    let mut blocks = Vec::new();
+
+    if fields.contains(&SearchField::Text) {
+        blocks.push((MatchSource::Text, Cow::Borrowed(message.content.as_str())));
+    }
+    if fields.contains(&SearchField::Tools) {
+        for (idx, tool_use) in message.tool_uses.iter().enumerate() {
+            blocks.push((
+                MatchSource::ToolInput(idx),
+                Cow::Owned(tool_use.searchable_text()),
+            ));
+        }
+    }
+    if fields.contains(&SearchField::Results) {
+        for (idx, result) in message.tool_results.iter().enumerate() {
+            blocks.push((
+                MatchSource::ToolResult(idx),
+                Cow::Borrowed(result.content.as_str()),
+            ));
+        }
+    }
+
+    blocks
+}
+
 /// Highlight information for search result display
 #[derive(Debug, Clone)]
 pub struct MatchHighlight {
@@ -309,6 +690,8 @@ pub struct MatchHighlight {
     pub end: usize,
     pub matched_text: String,
     pub highlight_type: HighlightType,
+    /// Part of the message the offsets refer to
+    pub source: MatchSource,
 }
 
 impl SearchEngine {
@@ -320,9 +703,41 @@ impl SearchEngine {
             regex_cache: LruCache::new(NonZeroUsize::new(100).unwrap()),
             result_cache: LruCache::new(NonZeroUsize::new(50).unwrap()),
             conversations: Vec::new(),
+            tokenizer: Tokenizer::new().with_stemming(true),
+            document_vectors: None,
+            weights: RankingWeights::default(),
         }
     }
 
+    /// Enable or disable English stemming; must be set before `build_index`
+    pub fn with_stemming(mut self, stemming: bool) -> Self {
+        self.tokenizer = self.tokenizer.with_stemming(stemming);
+        self
+    }
+
+    /// Use custom ranking weights
+    pub fn with_ranking_weights(mut self, weights: RankingWeights) -> Self {
+        self.set_ranking_weights(weights);
+        self
+    }
+
+    /// Replace the ranking weights, dropping cached (now stale) scores
+    pub fn set_ranking_weights(&mut self, weights: RankingWeights) {
+        self.weights = weights;
+        self.result_cache.clear();
+    }
+
+    /// Current ranking weights
+    pub fn ranking_weights(&self) -> &RankingWeights {
+        &self.weights
+    }
+
+    /// Use a custom tokenizer (must be set before building the index)
+    pub fn with_tokenizer(mut self, tokenizer: Tokenizer) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
     /// Build search index from a collection of conversations
     pub fn build_index(
         &mut self,
@@ -335,10 +750,14 @@ impl SearchEngine {
 
         // Store conversations after indexing
         self.conversations = conversations;
+        self.content_index.total_conversations = self.conversations.len();
 
         // Build date index
         self.build_date_index();
 
+        // Similarity vectors are rebuilt on demand
+        self.document_vectors = None;
+
         Ok(())
     }
 
@@ -351,7 +770,7 @@ impl SearchEngine {
 
         // Index conversation summary if available
         if let Some(ref summary) = conversation.summary {
-            self.index_text(&conversation.session_id, 0, summary);
+            self.index_text(&conversation.session_id, 0, SearchField::Text, summary);
         }
 
         Ok(())
@@ -364,12 +783,20 @@ impl SearchEngine {
         message_index: usize,
         message: &ConversationMessage,
     ) {
-        self.index_text(conversation_id, message_index, &message.content);
+        for (source, text) in searchable_blocks(message, &SearchField::all()) {
+            self.index_text(conversation_id, message_index, source.field(), &text);
+        }
     }
 
     /// Index text content by extracting and storing words
-    fn index_text(&mut self, conversation_id: &str, message_index: usize, text: &str) {
-        let words = Self::extract_words(text);
+    fn index_text(
+        &mut self,
+        conversation_id: &str,
+        message_index: usize,
+        field: SearchField,
+        text: &str,
+    ) {
+        let words = self.extract_words(text);
         let mut word_frequencies = HashMap::new();
 
         // Count word frequencies in this text
@@ -384,23 +811,31 @@ impl SearchEngine {
                 message_index,
                 position: 0, // Simplified position tracking
                 term_frequency: frequency,
+                field,
             };
 
-            self.content_index
+            let entries = self
+                .content_index
                 .word_index
                 .entry(word.clone())
-                .or_insert_with(Vec::new)
-                .push(entry);
-
-            // Update document frequency
-            *self
-                .content_index
-                .document_frequencies
-                .entry(word)
-                .or_insert(0) += 1;
+                .or_insert_with(Vec::new);
+
+            // Conversations are indexed one at a time, so the word is new to this
+            // conversation unless the previous entry belongs to it
+            let first_in_conversation = entries
+                .last()
+                .is_none_or(|last| last.conversation_id != conversation_id);
+            entries.push(entry);
+
+            // Update document frequency (number of conversations containing the word)
+            if first_in_conversation {
+                *self
+                    .content_index
+                    .document_frequencies
+                    .entry(word)
+                    .or_insert(0) += 1;
+            }
         }
-
-        self.content_index.total_conversations = self.conversations.len();
     }
 
     /// Build date index for temporal filtering
@@ -435,11 +870,16 @@ impl SearchEngine {
 
         // Apply text/regex/boolean search
         let results = if let Some(ref boolean_query) = query.boolean_query {
-            self.search_boolean_parallel(&mut candidates, boolean_query)?
+            self.search_boolean_parallel(&mut candidates, boolean_query, &query.fields)?
         } else if let Some(ref text) = query.text {
-            self.search_text_parallel(&mut candidates, text, query.search_mode.clone())?
+            self.search_text_parallel(
+                &mut candidates,
+                text,
+                query.search_mode.clone(),
+                &query.fields,
+            )?
         } else if let Some(ref pattern) = query.regex_pattern {
-            self.search_regex_parallel(&mut candidates, pattern)?
+            self.search_regex_parallel(&mut candidates, pattern, &query.fields)?
         } else {
             // Just return filtered candidates with default scoring
             candidates
@@ -467,19 +907,41 @@ impl SearchEngine {
         Ok(limited_results)
     }
 
+    /// Execute a search query and count facets over the full matched set.
+    ///
+    /// Facets are computed before `max_results` is applied so they describe
+    /// every hit, not just the ones returned.
+    pub fn search_with_facets(
+        &mut self,
+        query: &SearchQuery,
+    ) -> Result<(Vec<SearchResult>, SearchFacets), ClaudeToolsError> {
+        let mut unlimited = query.clone();
+        unlimited.max_results = None;
+
+        let mut results = self.search(&unlimited)?;
+        let facets = SearchFacets::from_results(&results);
+
+        if let Some(max) = query.max_results {
+            results.truncate(max);
+        }
+
+        Ok((results, facets))
+    }
+
     /// Parallel text search across conversations
     fn search_text_parallel(
         &self,
         conversations: &mut [Conversation],
         query: &str,
         mode: SearchMode,
+        fields: &[SearchField],
     ) -> Result<Vec<SearchResult>, ClaudeToolsError> {
-        let query_words = Self::extract_words(query);
+        let query_words = self.extract_words(query);
 
         let results: Vec<SearchResult> = conversations
             .par_iter()
             .filter_map(|conv| {
-                let result = self.score_conversation(conv, &query_words, query, &mode);
+                let result = self.score_conversation(conv, &query_words, query, &mode, fields);
                 if result.relevance_score > 0.0 || result.match_count > 0 {
                     Some(result)
                 } else {
@@ -501,6 +963,7 @@ impl SearchEngine {
         &mut self,
         conversations: &mut [Conversation],
         pattern: &str,
+        fields: &[SearchField],
     ) -> Result<Vec<SearchResult>, ClaudeToolsError> {
         // Get or compile regex
         let regex = if let Some(compiled) = self.regex_cache.get(pattern) {
@@ -516,27 +979,26 @@ impl SearchEngine {
             .par_iter()
             .filter_map(|conv| {
                 let mut highlights = Vec::new();
-                let mut matched_messages = Vec::new();
-                let mut match_count = 0;
 
                 // Search in each message
                 for (msg_idx, message) in conv.messages.iter().enumerate() {
-                    for mat in regex.find_iter(&message.content) {
-                        highlights.push(MatchHighlight {
-                            message_index: msg_idx,
-                            start: mat.start(),
-                            end: mat.end(),
-                            matched_text: mat.as_str().to_string(),
-                            highlight_type: HighlightType::GlobalSearch,
-                        });
-                        match_count += 1;
-                    }
-
-                    if !highlights.is_empty() && !matched_messages.contains(&msg_idx) {
-                        matched_messages.push(msg_idx);
+                    for (source, text) in searchable_blocks(message, fields) {
+                        for mat in regex.find_iter(&text) {
+                            highlights.push(MatchHighlight {
+                                message_index: msg_idx,
+                                start: mat.start(),
+                                end: mat.end(),
+                                matched_text: mat.as_str().to_string(),
+                                highlight_type: HighlightType::GlobalSearch,
+                                source,
+                            });
+                        }
                     }
                 }
 
+                let matched_messages = Self::matched_message_indices(&highlights);
+                let mut match_count = highlights.len();
+
                 // Search in summary
                 if let Some(ref summary) = conv.summary {
                     for _mat in regex.find_iter(summary) {
@@ -570,11 +1032,12 @@ impl SearchEngine {
         &self,
         conversations: &mut [Conversation],
         boolean_query: &BooleanQuery,
+        fields: &[SearchField],
     ) -> Result<Vec<SearchResult>, ClaudeToolsError> {
         let results: Vec<SearchResult> = conversations
             .par_iter()
             .filter_map(|conv| {
-                let result = self.evaluate_boolean_query(conv, boolean_query);
+                let result = self.evaluate_boolean_query(conv, boolean_query, fields);
                 if result.matches {
                     Some(SearchResult {
                         conversation: conv.clone(),
@@ -602,12 +1065,13 @@ impl SearchEngine {
         &self,
         conversation: &Conversation,
         query: &BooleanQuery,
+        fields: &[SearchField],
     ) -> BooleanResult {
         match query {
-            BooleanQuery::Term(term) => self.evaluate_term(conversation, term),
+            BooleanQuery::Term(term) => self.evaluate_term(conversation, term, fields),
             BooleanQuery::And(left, right) => {
-                let left_result = self.evaluate_boolean_query(conversation, left);
-                let right_result = self.evaluate_boolean_query(conversation, right);
+                let left_result = self.evaluate_boolean_query(conversation, left, fields);
+                let right_result = self.evaluate_boolean_query(conversation, right, fields);
 
                 BooleanResult {
                     matches: left_result.matches && right_result.matches,
@@ -627,8 +1091,8 @@ impl SearchEngine {
                 }
             }
             BooleanQuery::Or(left, right) => {
-                let left_result = self.evaluate_boolean_query(conversation, left);
-                let right_result = self.evaluate_boolean_query(conversation, right);
+                let left_result = self.evaluate_boolean_query(conversation, left, fields);
+                let right_result = self.evaluate_boolean_query(conversation, right, fields);
 
                 BooleanResult {
                     matches: left_result.matches || right_result.matches,
@@ -644,7 +1108,7 @@ impl SearchEngine {
                 }
             }
             BooleanQuery::Not(inner) => {
-                let inner_result = self.evaluate_boolean_query(conversation, inner);
+                let inner_result = self.evaluate_boolean_query(conversation, inner, fields);
                 BooleanResult {
                     matches: !inner_result.matches,
                     score: if !inner_result.matches { 1.0 } else { 0.0 },
@@ -653,54 +1117,35 @@ impl SearchEngine {
                     matched_messages: Vec::new(),
                 }
             }
-            BooleanQuery::Group(inner) => self.evaluate_boolean_query(conversation, inner),
+            BooleanQuery::Group(inner) => self.evaluate_boolean_query(conversation, inner, fields),
         }
     }
 
     /// Evaluate a single term against a conversation
-    fn evaluate_term(&self, conversation: &Conversation, term: &str) -> BooleanResult {
-        let term_lower = term.to_lowercase();
-        let mut highlights = Vec::new();
-        let mut matched_messages = Vec::new();
-        let mut match_count = 0;
+    fn evaluate_term(
+        &self,
+        conversation: &Conversation,
+        term: &str,
+        fields: &[SearchField],
+    ) -> BooleanResult {
+        let highlights = self.find_term_highlights(conversation, term, fields);
+        let matched_messages = Self::matched_message_indices(&highlights);
+        let mut match_count = highlights.len();
         let mut score = 0.0;
 
-        // Search in messages
-        for (msg_idx, message) in conversation.messages.iter().enumerate() {
-            let content_lower = message.content.to_lowercase();
-            let mut start = 0;
-
-            while let Some(pos) = content_lower[start..].find(&term_lower) {
-                let actual_pos = start + pos;
-                highlights.push(MatchHighlight {
-                    message_index: msg_idx,
-                    start: actual_pos,
-                    end: actual_pos + term.len(),
-                    matched_text: term.to_string(),
-                    highlight_type: HighlightType::GlobalSearch,
-                });
-                match_count += 1;
-
-                if !matched_messages.contains(&msg_idx) {
-                    matched_messages.push(msg_idx);
-                }
-
-                start = actual_pos + 1;
-            }
-        }
-
         // Search in summary
         if let Some(ref summary) = conversation.summary {
-            if summary.to_lowercase().contains(&term_lower) {
+            if summary.to_lowercase().contains(&term.to_lowercase()) {
                 match_count += 1;
-                score += 0.5; // Summary matches get some weight
+                score += self.weights.summary_weight; // Summary matches get some weight
             }
         }
 
         // Calculate TF-IDF score for the term
         if match_count > 0 {
-            let tf = self.calculate_term_frequency(term, conversation);
-            let idf = self.calculate_inverse_document_frequency(term);
+            let tf = self.calculate_term_frequency(&self.extract_words(term), conversation, fields);
+            let idf = self
+                .calculate_inverse_document_frequency(&self.tokenizer.normalize_identifier(term));
             score += tf * idf;
         }
 
@@ -713,62 +1158,136 @@ impl SearchEngine {
         }
     }
 
-    /// Score a conversation using TF-IDF
-    fn score_conversation(
+    /// Find highlights for a term: literal (case-insensitive) occurrences first, falling
+    /// back to code-aware token matches when the literal text never appears
+    fn find_term_highlights(
         &self,
         conversation: &Conversation,
-        query_words: &[String],
-        query_text: &str,
-        mode: &SearchMode,
-    ) -> SearchResult {
-        let mut total_score = 0.0;
+        term: &str,
+        fields: &[SearchField],
+    ) -> Vec<MatchHighlight> {
+        let term_lower = term.to_lowercase();
         let mut highlights = Vec::new();
-        let mut matched_messages = Vec::new();
-        let mut match_count = 0;
-
-        // Calculate TF-IDF score for each query word
-        for word in query_words {
-            if let Some(entries) = self.content_index.word_index.get(word) {
-                for entry in entries {
-                    if entry.conversation_id == conversation.session_id {
-                        let tf = self.calculate_term_frequency(word, conversation);
-                        let idf = self.calculate_inverse_document_frequency(word);
-                        total_score += tf * idf;
-                    }
-                }
-            }
-        }
-
-        // Find actual matches for highlighting
-        match mode {
-            SearchMode::Text | SearchMode::Advanced => {
-                let query_lower = query_text.to_lowercase();
 
-                for (msg_idx, message) in conversation.messages.iter().enumerate() {
-                    let content_lower = message.content.to_lowercase();
+        if !term_lower.is_empty() {
+            for (msg_idx, message) in conversation.messages.iter().enumerate() {
+                for (source, text) in searchable_blocks(message, fields) {
+                    let content_lower = text.to_lowercase();
 
                     // Find all occurrences
                     let mut start = 0;
-                    while let Some(pos) = content_lower[start..].find(&query_lower) {
+                    while let Some(pos) = content_lower[start..].find(&term_lower) {
                         let actual_start = start + pos;
-                        let actual_end = actual_start + query_text.len();
+                        let actual_end = actual_start + term_lower.len();
 
                         highlights.push(MatchHighlight {
                             message_index: msg_idx,
                             start: actual_start,
                             end: actual_end,
-                            matched_text: message.content[actual_start..actual_end].to_string(),
+                            matched_text: text
+                                .get(actual_start..actual_end)
+                                .unwrap_or(term)
+                                .to_string(),
                             highlight_type: HighlightType::GlobalSearch,
+                            source,
                         });
 
-                        match_count += 1;
-                        if !matched_messages.contains(&msg_idx) {
-                            matched_messages.push(msg_idx);
-                        }
+                        // Advance by one character so overlapping matches are still found
+                        start = actual_start
+                            + content_lower[actual_start..]
+                                .chars()
+                                .next()
+                                .map_or(1, |c| c.len_utf8());
+                    }
+                }
+            }
+        }
+
+        if highlights.is_empty() {
+            highlights = self.find_token_highlights(conversation, term, fields);
+        }
+
+        highlights
+    }
 
-                        start = actual_start + 1;
+    /// Highlight message tokens that match the tokens of the query text
+    fn find_token_highlights(
+        &self,
+        conversation: &Conversation,
+        text: &str,
+        fields: &[SearchField],
+    ) -> Vec<MatchHighlight> {
+        let query_tokens: HashSet<String> = self.extract_words(text).into_iter().collect();
+        let mut highlights = Vec::new();
+
+        if query_tokens.is_empty() {
+            return highlights;
+        }
+
+        for (msg_idx, message) in conversation.messages.iter().enumerate() {
+            for (source, block) in searchable_blocks(message, fields) {
+                // Whole identifiers come before their parts, so skipping tokens inside the
+                // previous highlight avoids highlighting both
+                let mut last_end = 0;
+                for token in self.tokenizer.tokens(&block) {
+                    if token.start >= last_end && query_tokens.contains(&token.text) {
+                        highlights.push(MatchHighlight {
+                            message_index: msg_idx,
+                            start: token.start,
+                            end: token.end,
+                            matched_text: block[token.start..token.end].to_string(),
+                            highlight_type: HighlightType::GlobalSearch,
+                            source,
+                        });
+                        last_end = token.end;
                     }
                 }
+            }
+        }
+
+        highlights
+    }
+
+    /// Unique message indices referenced by highlights, in order of first appearance
+    fn matched_message_indices(highlights: &[MatchHighlight]) -> Vec<usize> {
+        let mut matched_messages = Vec::new();
+        for highlight in highlights {
+            if !matched_messages.contains(&highlight.message_index) {
+                matched_messages.push(highlight.message_index);
+            }
+        }
+        matched_messages
+    }
+
+    /// Score a conversation using TF-IDF
+    fn score_conversation(
+        &self,
+        conversation: &Conversation,
+        query_words: &[String],
+        query_text: &str,
+        mode: &SearchMode,
+        fields: &[SearchField],
+    ) -> SearchResult {
+        let mut highlights = Vec::new();
+        let mut matched_messages = Vec::new();
+        let mut match_count = 0;
+
+        // Calculate TF-IDF score for each query word
+        let mut total_score: f64 = self
+            .term_contributions(conversation, query_words, fields)
+            .iter()
+            .map(|term| term.contribution)
+            .sum::<f64>()
+            * self.weights.term_weight;
+
+        // Find actual matches for highlighting
+        match mode {
+            SearchMode::Text | SearchMode::Advanced => {
+                let query_lower = query_text.to_lowercase();
+
+                highlights = self.find_term_highlights(conversation, query_text, fields);
+                matched_messages = Self::matched_message_indices(&highlights);
+                match_count = highlights.len();
 
                 // Check summary
                 if let Some(ref summary) = conversation.summary {
@@ -798,20 +1317,120 @@ impl SearchEngine {
         }
     }
 
-    /// Calculate term frequency for TF-IDF
-    fn calculate_term_frequency(&self, term: &str, conversation: &Conversation) -> f64 {
-        let term_lower = term.to_lowercase();
-        let term_count = conversation
-            .messages
-            .iter()
-            .map(|msg| msg.content.to_lowercase().matches(&term_lower).count())
-            .sum::<usize>() as f64;
+    /// Per-term TF-IDF contributions of normalized query words to a conversation's score
+    fn term_contributions(
+        &self,
+        conversation: &Conversation,
+        query_words: &[String],
+        fields: &[SearchField],
+    ) -> Vec<TermContribution> {
+        let mut contributions = Vec::new();
 
-        let total_words = conversation
-            .messages
-            .iter()
-            .map(|msg| Self::extract_words(&msg.content).len())
-            .sum::<usize>() as f64;
+        for word in query_words {
+            let occurrences = self
+                .content_index
+                .word_index
+                .get(word)
+                .map_or(0, |entries| {
+                    entries
+                        .iter()
+                        .filter(|entry| {
+                            entry.conversation_id == conversation.session_id
+                                && fields.contains(&entry.field)
+                        })
+                        .count()
+                });
+            if occurrences == 0 {
+                continue;
+            }
+
+            let tf =
+                self.calculate_term_frequency(std::slice::from_ref(word), conversation, fields);
+            let idf = self.calculate_inverse_document_frequency(word);
+            contributions.push(TermContribution {
+                term: word.clone(),
+                occurrences,
+                tf,
+                idf,
+                summary_bonus: 0.0,
+                contribution: occurrences as f64 * tf * idf,
+            });
+        }
+
+        contributions
+    }
+
+    /// Explain how a result's relevance score was computed for a query
+    pub fn explain(&self, result: &SearchResult, query: &SearchQuery) -> ScoreExplanation {
+        let conversation = &result.conversation;
+        let fields = &query.fields;
+        let mut explanation = ScoreExplanation {
+            mode: "filter".to_string(),
+            terms: Vec::new(),
+            term_weight: 1.0,
+            recency_factor: 1.0,
+            length_factor: 1.0,
+            filters: query.describe_filters(),
+            score: result.relevance_score,
+        };
+
+        if let Some(ref boolean_query) = query.boolean_query {
+            explanation.mode = "boolean".to_string();
+            for term in boolean_query.positive_terms() {
+                let term_result = self.evaluate_term(conversation, &term, fields);
+                if !term_result.matches {
+                    continue;
+                }
+                let tf =
+                    self.calculate_term_frequency(&self.extract_words(&term), conversation, fields);
+                let idf = self.calculate_inverse_document_frequency(
+                    &self.tokenizer.normalize_identifier(&term),
+                );
+                let summary_bonus = term_result.score - tf * idf;
+                explanation.terms.push(TermContribution {
+                    term,
+                    occurrences: term_result.match_count,
+                    tf,
+                    idf,
+                    summary_bonus,
+                    contribution: term_result.score,
+                });
+            }
+        } else if query.text.is_some() {
+            let text = query.text.as_deref().unwrap_or_default();
+            explanation.mode = "text".to_string();
+            explanation.terms =
+                self.term_contributions(conversation, &self.extract_words(text), fields);
+            explanation.term_weight = self.weights.term_weight;
+            explanation.recency_factor = self.calculate_recency_boost(conversation);
+            explanation.length_factor = self.calculate_length_normalization(conversation);
+        } else if query.regex_pattern.is_some() {
+            explanation.mode = "regex".to_string();
+        }
+
+        explanation
+    }
+
+    /// Calculate term frequency for TF-IDF from already-normalized tokens
+    fn calculate_term_frequency(
+        &self,
+        terms: &[String],
+        conversation: &Conversation,
+        fields: &[SearchField],
+    ) -> f64 {
+        let mut term_count = 0.0;
+        let mut total_words = 0.0;
+
+        for msg in &conversation.messages {
+            for (_, text) in searchable_blocks(msg, fields) {
+                for word in self.extract_words(&text) {
+                    if terms.contains(&word) {
+                        term_count += 1.0;
+                    }
+                    total_words += 1.0;
+                }
+            }
+        }
 
         if total_words > 0.0 {
             term_count / total_words
@@ -844,9 +1463,10 @@ impl SearchEngine {
             let age = now.signed_duration_since(last_updated);
             let days_old = age.num_days() as f64;
 
-            // Boost recent conversations (decay over 30 days)
-            if days_old < 30.0 {
-                1.0 + (30.0 - days_old) / 30.0 * 0.5
+            // Boost recent conversations (decay over the recency window)
+            let window = self.weights.recency_window_days;
+            if days_old < window {
+                1.0 + (window - days_old) / window * self.weights.recency_boost
             } else {
                 1.0
             }
@@ -857,10 +1477,12 @@ impl SearchEngine {
 
     /// Calculate length normalization factor
     fn calculate_length_normalization(&self, conversation: &Conversation) -> f64 {
-        let message_count = conversation.messages.len() as f64;
-        // Slight boost for conversations with reasonable length (5-50 messages)
-        if message_count >= 5.0 && message_count <= 50.0 {
-            1.1
+        let message_count = conversation.messages.len();
+        // Slight boost for conversations with reasonable length
+        if message_count >= self.weights.length_min_messages
+            && message_count <= self.weights.length_max_messages
+        {
+            self.weights.length_boost
         } else {
             1.0
         }
@@ -924,9 +1546,12 @@ impl SearchEngine {
                     });
                 }
                 MessageRole::Tool => {
-                    // Tool messages are typically Assistant messages with tool uses
-                    candidates
-                        .retain(|conv| conv.messages.iter().any(|msg| !msg.tool_uses.is_empty()));
+                    // Tool calls live on assistant messages, their results on tool messages
+                    candidates.retain(|conv| {
+                        conv.messages
+                            .iter()
+                            .any(|msg| !msg.tool_uses.is_empty() || msg.role == ConvRole::Tool)
+                    });
                 }
             }
         }
@@ -980,21 +1605,148 @@ impl SearchEngine {
         conversations
     }
 
-    /// Extract words from text for indexing
-    fn extract_words(text: &str) -> Vec<String> {
-        text.to_lowercase()
-            .split_whitespace()
-            .filter(|word| word.len() > 2) // Filter very short words
-            .map(|word| {
-                // Remove punctuation
-                word.chars()
-                    .filter(|c| c.is_alphanumeric())
-                    .collect::<String>()
+    /// Whether a conversation with this session ID has been indexed
+    pub fn contains_conversation(&self, session_id: &str) -> bool {
+        self.conversations
+            .iter()
+            .any(|conv| conv.session_id == session_id)
+    }
+
+    /// Find the conversations most similar to an indexed conversation
+    pub fn similar_to_session(
+        &mut self,
+        session_id: &str,
+        limit: usize,
+    ) -> Result<Vec<SimilarConversation>, ClaudeToolsError> {
+        if !self.contains_conversation(session_id) {
+            return Err(ClaudeToolsError::Config(format!(
+                "Conversation not found: {}",
+                session_id
+            )));
+        }
+
+        // A conversation with only ubiquitous terms has an empty vector
+        let reference = self
+            .document_vectors()
+            .get(session_id)
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(self.rank_similar(&reference, Some(session_id), limit))
+    }
+
+    /// Find the conversations most similar to a piece of free text
+    pub fn similar_to_text(&mut self, text: &str, limit: usize) -> Vec<SimilarConversation> {
+        let mut term_counts: HashMap<String, usize> = HashMap::new();
+        for word in self.extract_words(text) {
+            *term_counts.entry(word).or_insert(0) += 1;
+        }
+
+        let idf = self.conversation_idf();
+        let mut reference = TermVector::new();
+        for (term, count) in term_counts {
+            if let Some(&weight) = idf.get(&term) {
+                reference.insert(term, (1.0 + (count as f64).ln()) * weight);
+            }
+        }
+        normalize_vector(&mut reference);
+
+        self.document_vectors();
+        self.rank_similar(&reference, None, limit)
+    }
+
+    /// Rank indexed conversations by cosine similarity to a normalized vector
+    fn rank_similar(
+        &self,
+        reference: &TermVector,
+        exclude: Option<&str>,
+        limit: usize,
+    ) -> Vec<SimilarConversation> {
+        let vectors = match self.document_vectors {
+            Some(ref vectors) => vectors,
+            None => return Vec::new(),
+        };
+
+        let mut similar: Vec<SimilarConversation> = self
+            .conversations
+            .par_iter()
+            .filter(|conv| Some(conv.session_id.as_str()) != exclude)
+            .filter_map(|conv| {
+                let vector = vectors.get(&conv.session_id)?;
+                let similarity = cosine_similarity(reference, vector);
+                if similarity > 0.0 {
+                    Some(SimilarConversation {
+                        conversation: conv.clone(),
+                        similarity,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        similar.par_sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap());
+        similar.truncate(limit);
+        similar
+    }
+
+    /// Get (building if necessary) normalized TF-IDF vectors for every conversation
+    fn document_vectors(&mut self) -> &HashMap<String, TermVector> {
+        if self.document_vectors.is_none() {
+            let idf = self.conversation_idf();
+            let mut vectors: HashMap<String, TermVector> = HashMap::new();
+
+            for (term, entries) in &self.content_index.word_index {
+                let weight = idf.get(term).copied().unwrap_or(0.0);
+                if weight <= 0.0 {
+                    continue;
+                }
+
+                let mut term_counts: HashMap<&str, usize> = HashMap::new();
+                for entry in entries {
+                    *term_counts.entry(&entry.conversation_id).or_insert(0) += entry.term_frequency;
+                }
+
+                for (conversation_id, count) in term_counts {
+                    vectors
+                        .entry(conversation_id.to_string())
+                        .or_default()
+                        .insert(term.clone(), (1.0 + (count as f64).ln()) * weight);
+                }
+            }
+
+            for vector in vectors.values_mut() {
+                normalize_vector(vector);
+            }
+
+            self.document_vectors = Some(vectors);
+        }
+
+        self.document_vectors.as_ref().unwrap()
+    }
+
+    /// Inverse document frequency per term, counted over conversations
+    fn conversation_idf(&self) -> HashMap<String, f64> {
+        let total = self.conversations.len().max(1) as f64;
+
+        self.content_index
+            .word_index
+            .iter()
+            .map(|(term, entries)| {
+                let conversations: HashSet<&str> = entries
+                    .iter()
+                    .map(|entry| entry.conversation_id.as_str())
+                    .collect();
+                (term.clone(), (total / conversations.len() as f64).ln())
             })
-            .filter(|word| !word.is_empty())
             .collect()
     }
 
+    /// Extract normalized tokens from text for indexing and scoring
+    fn extract_words(&self, text: &str) -> Vec<String> {
+        self.tokenizer.tokenize(text)
+    }
+
     /// Calculate hash for query caching
     fn calculate_query_hash(&self, query: &SearchQuery) -> u64 {
         use std::collections::hash_map::DefaultHasher;
@@ -1004,6 +1756,17 @@ impl SearchEngine {
         query.text.hash(&mut hasher);
         query.regex_pattern.hash(&mut hasher);
         query.project_filter.hash(&mut hasher);
+        query.model_filter.hash(&mut hasher);
+        query.tool_filter.hash(&mut hasher);
+        format!("{:?}", query.message_role_filter).hash(&mut hasher);
+        format!("{:?}", query.boolean_query).hash(&mut hasher);
+        format!("{:?}", query.search_mode).hash(&mut hasher);
+        query.min_messages.hash(&mut hasher);
+        query.max_messages.hash(&mut hasher);
+        query.min_duration_minutes.hash(&mut hasher);
+        query.max_duration_minutes.hash(&mut hasher);
+        query.max_results.hash(&mut hasher);
+        query.fields.hash(&mut hasher);
         // Note: DateRange doesn't implement Hash, so we'll hash the string representation
         if let Some(ref range) = query.date_range {
             format!("{:?}", range).hash(&mut hasher);
@@ -1012,6 +1775,160 @@ impl SearchEngine {
     }
 }
 
+impl SearchResult {
+    /// Convert to the stable machine-readable record used by JSON/TSV output
+    pub fn to_record(&self) -> SearchResultRecord {
+        let messages = &self.conversation.messages;
+        let highlights = self
+            .match_highlights
+            .iter()
+            .filter_map(|highlight| {
+                let message = messages.get(highlight.message_index)?;
+                let text = highlight.source.text(message)?;
+                let block_index = match highlight.source {
+                    MatchSource::Text => None,
+                    MatchSource::ToolInput(idx) | MatchSource::ToolResult(idx) => Some(idx),
+                };
+                Some(HighlightRecord {
+                    message_uuid: message.uuid.clone(),
+                    field: highlight.source.field(),
+                    block_index,
+                    start: highlight.start,
+                    end: highlight.end,
+                    matched_text: highlight.matched_text.clone(),
+                    snippet: snippet_around(&text, highlight.start, highlight.end, 40),
+                })
+            })
+            .collect();
+
+        SearchResultRecord {
+            session_id: self.conversation.session_id.clone(),
+            project: self.conversation.project_path.clone(),
+            score: self.relevance_score,
+            match_count: self.match_count,
+            matched_message_uuids: self
+                .matched_messages
+                .iter()
+                .filter_map(|&idx| messages.get(idx).map(|m| m.uuid.clone()))
+                .collect(),
+            highlights,
+            explanation: None,
+        }
+    }
+
+    /// Group highlights into message-level hits with `context` lines before and after
+    ///
+    /// Hits are ordered by message. Overlapping or adjacent context windows within the
+    /// same message block are merged, like `grep -C`.
+    pub fn message_hits(&self, context: usize) -> Vec<MessageHit> {
+        let mut groups: Vec<(usize, MatchSource, Vec<&MatchHighlight>)> = Vec::new();
+        for highlight in &self.match_highlights {
+            match groups.iter_mut().find(|(idx, source, _)| {
+                *idx == highlight.message_index && *source == highlight.source
+            }) {
+                Some((_, _, group)) => group.push(highlight),
+                None => groups.push((highlight.message_index, highlight.source, vec![highlight])),
+            }
+        }
+        groups.sort_by_key(|(idx, _, _)| *idx);
+
+        let mut hits = Vec::new();
+        for (message_index, source, highlights) in groups {
+            let Some(message) = self.conversation.messages.get(message_index) else {
+                continue;
+            };
+            let Some(text) = source.text(message) else {
+                continue;
+            };
+
+            let lines: Vec<&str> = text.split('\n').collect();
+            let mut line_starts = Vec::with_capacity(lines.len());
+            let mut offset = 0;
+            for line in &lines {
+                line_starts.push(offset);
+                offset += line.len() + 1;
+            }
+            let line_of = |pos: usize| line_starts.partition_point(|&start| start <= pos) - 1;
+
+            let mut matched = vec![false; lines.len()];
+            for highlight in highlights {
+                let first = line_of(highlight.start);
+                let last = line_of(highlight.end.saturating_sub(1).max(highlight.start));
+                for flag in &mut matched[first..=last.min(lines.len() - 1)] {
+                    *flag = true;
+                }
+            }
+
+            // Build context windows around matched lines, merging overlaps
+            let mut windows: Vec<(usize, usize)> = Vec::new();
+            for (line_idx, _) in matched.iter().enumerate().filter(|(_, m)| **m) {
+                let start = line_idx.saturating_sub(context);
+                let end = (line_idx + context).min(lines.len() - 1);
+                match windows.last_mut() {
+                    Some((_, last_end)) if start <= *last_end + 1 => *last_end = end,
+                    _ => windows.push((start, end)),
+                }
+            }
+
+            for (start, end) in windows {
+                let matched_lines: Vec<usize> = (start..=end).filter(|&i| matched[i]).collect();
+                hits.push(MessageHit {
+                    message_index,
+                    message_uuid: message.uuid.clone(),
+                    role: message.role.clone(),
+                    timestamp: message.timestamp,
+                    source,
+                    line_range: (
+                        matched_lines.first().map_or(start, |&i| i) + 1,
+                        matched_lines.last().map_or(end, |&i| i) + 1,
+                    ),
+                    lines: (start..=end)
+                        .map(|i| ContextLine {
+                            number: i + 1,
+                            text: lines[i].to_string(),
+                            is_match: matched[i],
+                        })
+                        .collect(),
+                });
+            }
+        }
+
+        hits
+    }
+}
+
+/// Scale a vector to unit length
+fn normalize_vector(vector: &mut TermVector) {
+    let norm = vector.values().map(|w| w * w).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        for weight in vector.values_mut() {
+            *weight /= norm;
+        }
+    }
+}
+
+/// Cosine similarity of two normalized vectors
+fn cosine_similarity(a: &TermVector, b: &TermVector) -> f64 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small
+        .iter()
+        .filter_map(|(term, weight)| large.get(term).map(|other| weight * other))
+        .sum()
+}
+
+/// Single-line excerpt of `text` with up to `radius` bytes on either side of a match
+fn snippet_around(text: &str, start: usize, end: usize, radius: usize) -> String {
+    let mut from = start.saturating_sub(radius).min(text.len());
+    while !text.is_char_boundary(from) {
+        from -= 1;
+    }
+    let mut to = (end + radius).min(text.len());
+    while !text.is_char_boundary(to) {
+        to += 1;
+    }
+    text[from..to].replace(['\n', '\t', '\r'], " ")
+}
+
 impl InvertedIndex {
     fn new() -> Self {
         Self {
@@ -1031,6 +1948,66 @@ impl DateIndex {
 }
 
 impl SearchQuery {
+    /// Human-readable description of the filters this query applies
+    pub fn describe_filters(&self) -> Vec<String> {
+        let mut filters = Vec::new();
+
+        if let Some(ref range) = self.date_range {
+            let format = |date: Option<DateTime<Utc>>| {
+                date.map_or("…".to_string(), |d| {
+                    d.format("%Y-%m-%d %H:%M").to_string()
+                })
+            };
+            filters.push(format!(
+                "started {} – {}",
+                format(range.start),
+                format(range.end)
+            ));
+        }
+        if let Some(ref project) = self.project_filter {
+            filters.push(format!("project contains '{}'", project));
+        }
+        if let Some(ref model) = self.model_filter {
+            filters.push(format!("model contains '{}'", model));
+        }
+        if let Some(ref tool) = self.tool_filter {
+            filters.push(format!("tool contains '{}'", tool));
+        }
+        if let Some(ref role) = self.message_role_filter {
+            filters.push(format!("has {:?} messages", role).to_lowercase());
+        }
+        if self.min_messages.is_some() || self.max_messages.is_some() {
+            filters.push(format!(
+                "messages {}..{}",
+                self.min_messages.map_or(String::new(), |n| n.to_string()),
+                self.max_messages.map_or(String::new(), |n| n.to_string())
+            ));
+        }
+        if self.min_duration_minutes.is_some() || self.max_duration_minutes.is_some() {
+            filters.push(format!(
+                "duration {}..{} min",
+                self.min_duration_minutes
+                    .map_or(String::new(), |n| n.to_string()),
+                self.max_duration_minutes
+                    .map_or(String::new(), |n| n.to_string())
+            ));
+        }
+        if self.fields.len() < SearchField::all().len() {
+            let fields: Vec<&str> = self
+                .fields
+                .iter()
+                .map(|field| match field {
+                    SearchField::Text => "text",
+                    SearchField::Tools => "tools",
+                    SearchField::Results => "results",
+                })
+                .collect();
+            filters.push(format!("in {}", fields.join(",")));
+        }
+
+        filters
+    }
+
     /// Create a simple text search query
     pub fn text(query: &str) -> Self {
         Self {
@@ -1048,6 +2025,7 @@ impl SearchQuery {
             max_duration_minutes: None,
             search_mode: SearchMode::Text,
             max_results: None,
+            fields: SearchField::all(),
         }
     }
 
@@ -1068,6 +2046,7 @@ impl SearchQuery {
             max_duration_minutes: None,
             search_mode: SearchMode::Regex,
             max_results: None,
+            fields: SearchField::all(),
         }
     }
 
@@ -1089,6 +2068,7 @@ impl SearchQuery {
             max_duration_minutes: None,
             search_mode: SearchMode::Advanced,
             max_results: None,
+            fields: SearchField::all(),
         })
     }
 
@@ -1145,6 +2125,51 @@ impl SearchQuery {
         self.max_results = Some(max);
         self
     }
+
+    /// Restrict the search to specific message fields
+    pub fn with_fields(mut self, fields: Vec<SearchField>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Narrow the query to results carrying a facet value
+    pub fn with_facet(self, kind: FacetKind, value: &str) -> Result<Self, ClaudeToolsError> {
+        let invalid =
+            || ClaudeToolsError::Config(format!("Invalid {} facet value: {}", kind.label(), value));
+
+        Ok(match kind {
+            FacetKind::Project => self.with_project(value),
+            FacetKind::Model => self.with_model(value),
+            FacetKind::Tool => self.with_tool(value),
+            FacetKind::Role => self.with_role(match value {
+                "user" => MessageRole::User,
+                "assistant" => MessageRole::Assistant,
+                "system" => MessageRole::System,
+                "tool" => MessageRole::Tool,
+                _ => return Err(invalid()),
+            }),
+            FacetKind::Month => {
+                let start = NaiveDate::parse_from_str(&format!("{}-01", value), "%Y-%m-%d")
+                    .map_err(|_| invalid())?;
+                let next = if start.month() == 12 {
+                    NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+                } else {
+                    NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)
+                }
+                .ok_or_else(invalid)?;
+
+                let start = start.and_hms_opt(0, 0, 0).unwrap().and_utc();
+                let end = next.and_hms_opt(0, 0, 0).unwrap().and_utc() - Duration::nanoseconds(1);
+                self.with_date_range(Some(start), Some(end))
+            }
+            FacetKind::Source => self.with_fields(vec![match value {
+                "text" => SearchField::Text,
+                "tools" => SearchField::Tools,
+                "results" => SearchField::Results,
+                _ => return Err(invalid()),
+            }]),
+        })
+    }
 }
 
 impl Default for SearchQuery {
@@ -1164,6 +2189,7 @@ impl Default for SearchQuery {
             max_duration_minutes: None,
             search_mode: SearchMode::Text,
             max_results: None,
+            fields: SearchField::all(),
         }
     }
 }
@@ -1201,35 +2227,19 @@ mod tests {
     use chrono::Utc;
 
     fn create_test_conversation() -> Conversation {
-        use crate::claude::conversation::{ConversationMessage, MessageRole};
-
-        Conversation {
-            session_id: "test-123".to_string(),
-            project_path: "test-project".to_string(),
-            summary: Some("Test conversation about Rust programming".to_string()),
-            messages: vec![
-                ConversationMessage {
-                    uuid: "msg1".to_string(),
-                    parent_uuid: None,
-                    role: MessageRole::User,
-                    content: "How do I implement error handling in Rust?".to_string(),
-                    timestamp: Utc::now(),
-                    model: None,
-                    tool_uses: vec![],
-                },
-                ConversationMessage {
-                    uuid: "msg2".to_string(),
-                    parent_uuid: Some("msg1".to_string()),
-                    role: MessageRole::Assistant,
-                    content: "Rust has excellent error handling with Result and Option types. You can use match or ? operator.".to_string(),
-                    timestamp: Utc::now(),
-                    model: Some("claude-3".to_string()),
-                    tool_uses: vec![],
-                },
-            ],
-            started_at: Some(Utc::now()),
-            last_updated: Some(Utc::now()),
-        }
+        use crate::claude::conversation::ConversationMessage;
+
+        let messages = vec![
+            ConversationMessage::user(Utc::now())
+                .with_uuid("msg1")
+                .with_content("How do I implement error handling in Rust?"),
+            ConversationMessage::assistant(Utc::now())
+                .with_uuid("msg2")
+                .with_content("Rust has excellent error handling with Result and Option types. You can use match or ? operator.")
+                .with_model("claude-3"),
+        ];
+        Conversation::new_for_test("test-123", "test-project", messages)
+            .with_summary("Test conversation about Rust programming")
     }
 
     #[test]
@@ -1269,6 +2279,151 @@ mod tests {
         assert!(results2[0].match_count > 0);
     }
 
+    #[test]
+    fn test_similar_conversations() {
+        let mut engine = SearchEngine::new();
+        let mut unrelated = create_test_conversation();
+        unrelated.session_id = "css-789".to_string();
+        unrelated.summary = Some("Styling a navigation bar".to_string());
+        unrelated.messages.truncate(1);
+        unrelated.messages[0].content = "Center the flexbox navigation bar in CSS".to_string();
+        engine
+            .build_index(vec![
+                create_test_conversation(),
+                create_tool_conversation(),
+                unrelated,
+            ])
+            .unwrap();
+
+        let similar = engine.similar_to_session("test-123", 5).unwrap();
+        assert_eq!(similar[0].conversation.session_id, "tools-456");
+        assert!(similar
+            .iter()
+            .all(|s| s.conversation.session_id != "test-123"));
+        assert!(similar
+            .iter()
+            .all(|s| s.conversation.session_id != "css-789"));
+        assert!(similar[0].similarity > 0.0 && similar[0].similarity <= 1.0 + 1e-9);
+
+        let by_text = engine.similar_to_text("nextest borrow checker", 5);
+        assert_eq!(by_text[0].conversation.session_id, "tools-456");
+
+        assert!(engine.similar_to_session("missing", 5).is_err());
+    }
+
+    #[test]
+    fn test_search_facets() {
+        let mut engine = SearchEngine::new();
+        let mut other_project = create_test_conversation();
+        other_project.session_id = "test-789".to_string();
+        other_project.project_path = "other-project".to_string();
+        engine
+            .build_index(vec![
+                create_test_conversation(),
+                create_tool_conversation(),
+                other_project,
+            ])
+            .unwrap();
+
+        let query = SearchQuery::text("rust").with_max_results(1);
+        let (results, facets) = engine.search_with_facets(&query).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            facets.projects[0],
+            FacetCount {
+                value: "test-project".to_string(),
+                count: 2
+            }
+        );
+        assert_eq!(facets.tools[0].value, "Bash");
+        assert_eq!(facets.months.len(), 1);
+        // Only the matched messages count towards roles
+        assert!(facets.roles.iter().all(|f| f.value != "tool"));
+        assert!(facets
+            .roles
+            .iter()
+            .any(|f| f.value == "assistant" && f.count == 3));
+
+        let (_, facets) = engine
+            .search_with_facets(&SearchQuery::text("nextest"))
+            .unwrap();
+        // Tool calls are made by assistant messages, so count as assistant
+        let roles: Vec<_> = facets.roles.iter().map(|f| f.value.as_str()).collect();
+        assert_eq!(roles, ["assistant"]);
+
+        let narrowed = SearchQuery::text("rust")
+            .with_facet(FacetKind::Tool, "Bash")
+            .unwrap();
+        let (results, facets) = engine.search_with_facets(&narrowed).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].conversation.session_id, "tools-456");
+        assert_eq!(facets.tools[0].count, 1);
+
+        let month = facets.months[0].value.clone();
+        let by_month = SearchQuery::text("rust")
+            .with_facet(FacetKind::Month, &month)
+            .unwrap();
+        assert_eq!(engine.search(&by_month).unwrap().len(), 3);
+
+        assert!(SearchQuery::text("rust")
+            .with_facet(FacetKind::Role, "robot")
+            .is_err());
+    }
+
+    #[test]
+    fn test_score_explanation() {
+        let mut engine = SearchEngine::new();
+        let mut other_project = create_test_conversation();
+        other_project.session_id = "test-789".to_string();
+        other_project.summary = None;
+        other_project.messages[1].content = "Python uses exceptions.".to_string();
+        engine
+            .build_index(vec![create_test_conversation(), other_project])
+            .unwrap();
+
+        let query = SearchQuery::text("rust error").with_project("test");
+        let results = engine.search(&query).unwrap();
+        let explanation = engine.explain(&results[0], &query);
+
+        assert_eq!(explanation.mode, "text");
+        assert_eq!(explanation.filters, vec!["project contains 'test'"]);
+        let rust = explanation.terms.iter().find(|t| t.term == "rust").unwrap();
+        assert!(rust.occurrences > 0 && rust.tf > 0.0);
+        let recombined = explanation
+            .terms
+            .iter()
+            .map(|t| t.contribution)
+            .sum::<f64>()
+            * explanation.term_weight
+            * explanation.recency_factor
+            * explanation.length_factor;
+        assert!((recombined - explanation.score).abs() < 1e-9);
+
+        let boolean = SearchQuery::boolean("rust AND NOT python").unwrap();
+        let results = engine.search(&boolean).unwrap();
+        let explanation = engine.explain(&results[0], &boolean);
+        assert_eq!(explanation.mode, "boolean");
+        assert_eq!(explanation.terms.len(), 1);
+        assert_eq!(explanation.terms[0].summary_bonus, 0.5);
+    }
+
+    #[test]
+    fn test_ranking_weights() {
+        let mut engine = SearchEngine::new();
+        engine
+            .build_index(vec![create_tool_conversation()])
+            .unwrap();
+        let query = SearchQuery::boolean("rust AND error").unwrap();
+        let baseline = engine.search(&query).unwrap()[0].relevance_score;
+
+        engine.set_ranking_weights(RankingWeights {
+            summary_weight: 2.5,
+            ..RankingWeights::default()
+        });
+        let boosted = engine.search(&query).unwrap()[0].relevance_score;
+        assert!((boosted - baseline - 2.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_date_range_query() {
         let query = SearchQuery::text("test")
@@ -1277,9 +2432,123 @@ mod tests {
         assert!(query.date_range.is_some());
     }
 
+    fn create_tool_conversation() -> Conversation {
+        use crate::claude::conversation::ConversationMessage;
+
+        let mut conversation = create_test_conversation();
+        conversation.session_id = "tools-456".to_string();
+        conversation.messages.push(
+            ConversationMessage::assistant(Utc::now())
+                .with_uuid("msg3")
+                .with_content("Running the test suite.")
+                .with_model("claude-3")
+                .with_tool_use(
+                    "tool1",
+                    "Bash",
+                    serde_json::json!({"command": "cargo nextest run", "description": "Run tests"}),
+                ),
+        );
+        conversation.messages.push(
+            ConversationMessage::user(Utc::now())
+                .with_uuid("msg4")
+                .with_tool_result("tool1", "error[E0382]: borrow of moved value", true),
+        );
+        conversation
+    }
+
+    #[test]
+    fn test_tool_field_search() {
+        let mut engine = SearchEngine::new();
+        engine
+            .build_index(vec![create_tool_conversation()])
+            .unwrap();
+
+        let results = engine.search(&SearchQuery::text("cargo nextest")).unwrap();
+        assert_eq!(results.len(), 1);
+        let highlight = &results[0].match_highlights[0];
+        assert_eq!(highlight.source, MatchSource::ToolInput(0));
+        assert_eq!(highlight.message_index, 2);
+        let text = highlight
+            .source
+            .text(&results[0].conversation.messages[2])
+            .unwrap();
+        assert_eq!(&text[highlight.start..highlight.end], "cargo nextest");
+
+        let results = engine
+            .search(&SearchQuery::text("E0382").with_fields(vec![SearchField::Results]))
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].match_highlights[0].source,
+            MatchSource::ToolResult(0)
+        );
+
+        // Scoping to message text excludes tool inputs and outputs
+        let results = engine
+            .search(&SearchQuery::text("nextest").with_fields(vec![SearchField::Text]))
+            .unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_message_hits_with_context() {
+        let mut conversation = create_test_conversation();
+        conversation.messages[1].content =
+            "line one\nline two\nthe error is here\nline four\nline five\nline six\nanother error"
+                .to_string();
+
+        let mut engine = SearchEngine::new();
+        engine.build_index(vec![conversation]).unwrap();
+        let results = engine
+            .search(&SearchQuery::text("error").with_fields(vec![SearchField::Text]))
+            .unwrap();
+        let result = &results[0];
+
+        let hits = result.message_hits(1);
+        // User message (line 1) plus two separate windows in the assistant message
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].message_uuid, "msg1");
+
+        let first = &hits[1];
+        assert_eq!(first.message_uuid, "msg2");
+        assert_eq!(first.line_range, (3, 3));
+        let numbers: Vec<usize> = first.lines.iter().map(|l| l.number).collect();
+        assert_eq!(numbers, vec![2, 3, 4]);
+        assert!(first.lines[1].is_match && !first.lines[0].is_match);
+
+        assert_eq!(hits[2].line_range, (7, 7));
+        assert_eq!(hits[2].lines.len(), 2);
+
+        // Wider context merges both windows into one hit
+        let merged = result.message_hits(2);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[1].line_range, (3, 7));
+    }
+
+    #[test]
+    fn test_result_record() {
+        let mut engine = SearchEngine::new();
+        engine
+            .build_index(vec![create_tool_conversation()])
+            .unwrap();
+        let results = engine.search(&SearchQuery::text("nextest")).unwrap();
+
+        let record = results[0].to_record();
+        assert_eq!(record.session_id, "tools-456");
+        assert_eq!(record.matched_message_uuids, vec!["msg3"]);
+        let highlight = &record.highlights[0];
+        assert_eq!(highlight.field, SearchField::Tools);
+        assert_eq!(highlight.block_index, Some(0));
+        assert_eq!(highlight.snippet, "Bash command: cargo nextest run");
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["highlights"][0]["field"], "tools");
+    }
+
     #[test]
     fn test_word_extraction() {
-        let words = SearchEngine::extract_words("Hello, world! This is a test.");
+        let engine = SearchEngine::new();
+        let words = engine.extract_words("Hello, world! This is a test.");
         assert!(words.contains(&"hello".to_string()));
         assert!(words.contains(&"world".to_string()));
         assert!(words.contains(&"test".to_string()));
@@ -0,0 +1,402 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::conversation::Conversation;
+use super::tool_outcomes::{ToolOutcome, ToolOutcomeCounts};
+
+/// Programs whose second word selects what they do, e.g. `git status`
+const SUBCOMMAND_PROGRAMS: [&str; 24] = [
+    "apt",
+    "brew",
+    "bun",
+    "cargo",
+    "docker",
+    "dotnet",
+    "gh",
+    "git",
+    "go",
+    "gradle",
+    "helm",
+    "kubectl",
+    "make",
+    "mvn",
+    "npm",
+    "npx",
+    "pip",
+    "pnpm",
+    "poetry",
+    "rustup",
+    "systemctl",
+    "terraform",
+    "uv",
+    "yarn",
+];
+
+/// Subcommands that take the name of a script to run, e.g. `npm run build`
+const SCRIPT_RUNNERS: [&str; 2] = ["run", "exec"];
+
+/// Commands that only set up the shell for the command that follows
+const SETUP_PROGRAMS: [&str; 5] = ["cd", "export", "source", "pushd", "set"];
+
+/// Prefixes that wrap the command actually being run
+const COMMAND_WRAPPERS: [&str; 4] = ["sudo", "time", "nohup", "env"];
+
+/// Program and subcommand of every command in a shell command line, e.g.
+/// `cd api && cargo test | tail` gives `["cargo test"]`. Each command in an
+/// `&&`, `||` or `;` chain counts; only the first command of a pipeline does
+pub fn command_names(command_line: &str) -> Vec<String> {
+    split_commands(command_line, false)
+        .into_iter()
+        .filter_map(command_name)
+        .collect()
+}
+
+/// Commands of a command line split on newlines, `;`, `&&` and `||` outside
+/// quotes. With `pipeline_stages` every command of a pipeline is kept,
+/// otherwise only the first. Heredoc bodies are skipped
+pub fn split_commands(command_line: &str, pipeline_stages: bool) -> Vec<&str> {
+    let mut commands = Vec::new();
+    // Start of the current command; None for the rest of a pipeline
+    let mut start = Some(0);
+    let mut quote = None;
+    // Delimiters of heredocs whose bodies follow the current line
+    let mut heredocs = Vec::new();
+    let mut skip_to = 0;
+    let mut chars = command_line.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if i < skip_to {
+            continue;
+        }
+        if let Some(open) = quote {
+            if c == open {
+                quote = None;
+            } else if c == '\\' && open == '"' {
+                chars.next();
+            }
+            continue;
+        }
+
+        let separator_len = match c {
+            '\'' | '"' => {
+                quote = Some(c);
+                continue;
+            }
+            '\\' => {
+                chars.next();
+                continue;
+            }
+            '<' if chars.peek().is_some_and(|&(_, next)| next == '<') => {
+                chars.next();
+                // `<<<` is a here-string, which has no body
+                if chars.next_if(|&(_, next)| next == '<').is_none() {
+                    chars.next_if(|&(_, next)| next == '-');
+                    let rest = chars.peek().map_or("", |&(j, _)| &command_line[j..]);
+                    heredocs.push(heredoc_delimiter(rest));
+                }
+                continue;
+            }
+            '\n' | ';' => 1,
+            '&' | '|' if chars.peek().is_some_and(|&(_, next)| next == c) => {
+                chars.next();
+                2
+            }
+            '|' if pipeline_stages => 1,
+            '|' => {
+                if let Some(start) = start.take() {
+                    commands.push(&command_line[start..i]);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if let Some(start) = start {
+            commands.push(&command_line[start..i]);
+        }
+        start = Some(i + separator_len);
+        if c == '\n' && !heredocs.is_empty() {
+            skip_to = heredoc_end(command_line, i + 1, &heredocs);
+            heredocs.clear();
+            start = Some(skip_to);
+        }
+    }
+    if let Some(start) = start {
+        commands.push(&command_line[start..]);
+    }
+    commands
+}
+
+/// Delimiter of a heredoc from the text after `<<` or `<<-`, without quotes
+fn heredoc_delimiter(rest: &str) -> String {
+    rest.trim_start_matches([' ', '\t'])
+        .split(|c: char| c.is_whitespace() || matches!(c, ';' | '&' | '|' | '<' | '>' | ')'))
+        .next()
+        .unwrap_or_default()
+        .replace(['\'', '"'], "")
+}
+
+/// Offset just past the terminator line of each heredoc, with the first
+/// body starting at `body_start`
+fn heredoc_end(command_line: &str, body_start: usize, delimiters: &[String]) -> usize {
+    let mut end = body_start;
+    for delimiter in delimiters {
+        while end < command_line.len() {
+            let rest = &command_line[end..];
+            let line_len = rest.find('\n').map_or(rest.len(), |newline| newline + 1);
+            let line = &rest[..line_len];
+            end += line_len;
+            // `<<-` allows the terminator to be indented with tabs
+            if line.trim_start_matches('\t').trim_end() == delimiter {
+                break;
+            }
+        }
+    }
+    end
+}
+
+/// Program and subcommand of a single simple command
+fn command_name(command: &str) -> Option<String> {
+    let mut words = command
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c| c == '"' || c == '\'' || c == '(' || c == ')'))
+        .skip_while(|word| COMMAND_WRAPPERS.contains(word) || is_assignment(word));
+
+    let program = words.next().filter(|program| !program.is_empty())?;
+    if program.starts_with('#') || SETUP_PROGRAMS.contains(&program) {
+        return None;
+    }
+    let program = if program.contains('/') && !program.starts_with('.') {
+        // Absolute paths to system binaries read better as the binary name
+        program.rsplit('/').next().unwrap_or(program)
+    } else {
+        program
+    };
+
+    let mut name = program.to_string();
+    if SUBCOMMAND_PROGRAMS.contains(&program) {
+        if let Some(subcommand) = words.next().filter(|word| is_subcommand(word)) {
+            name.push(' ');
+            name.push_str(subcommand);
+            if SCRIPT_RUNNERS.contains(&subcommand) {
+                if let Some(script) = words.next().filter(|word| !word.starts_with('-')) {
+                    name.push(' ');
+                    name.push_str(script);
+                }
+            }
+        }
+    }
+    Some(name)
+}
+
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+fn is_subcommand(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_ascii_alphabetic())
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+}
+
+/// Runs of one program and subcommand
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandStats {
+    /// Program and subcommand, e.g. `cargo test`
+    pub command: String,
+    pub runs: usize,
+    /// Outcomes of the runs that have a result. A chain of commands returns
+    /// one result, which only counts for its last command
+    pub outcomes: ToolOutcomeCounts,
+    /// Median seconds from the call to its result, for runs that end a chain
+    pub median_duration_secs: Option<f64>,
+    /// Runs per project
+    pub projects: BTreeMap<String, usize>,
+}
+
+impl CommandStats {
+    /// Percentage of runs with a known outcome that failed
+    pub fn failure_rate(&self) -> Option<f64> {
+        self.outcomes.success_rate().map(|rate| 100.0 - rate)
+    }
+
+    /// Projects by runs, most first
+    pub fn top_projects(&self) -> Vec<(&str, usize)> {
+        let mut projects: Vec<(&str, usize)> = self
+            .projects
+            .iter()
+            .map(|(project, runs)| (project.as_str(), *runs))
+            .collect();
+        projects.sort_by_key(|(_, runs)| std::cmp::Reverse(*runs));
+        projects
+    }
+}
+
+/// Shell commands run through the Bash tool, most runs first
+pub fn command_stats<'a>(
+    conversations: impl IntoIterator<Item = &'a Conversation>,
+) -> Vec<CommandStats> {
+    let mut stats: HashMap<String, CommandStats> = HashMap::new();
+    let mut durations: HashMap<String, Vec<f64>> = HashMap::new();
+
+    for conversation in conversations {
+        let results: HashMap<&str, (DateTime<Utc>, ToolOutcome)> = conversation
+            .messages
+            .iter()
+            .flat_map(|m| m.tool_results.iter().map(move |result| (m, result)))
+            .map(|(m, result)| {
+                (
+                    result.tool_use_id.as_str(),
+                    (m.timestamp, ToolOutcome::from_result("Bash", result)),
+                )
+            })
+            .collect();
+
+        for message in &conversation.messages {
+            for tool_use in message.tool_uses.iter().filter(|tool| tool.name == "Bash") {
+                let Some(command_line) = tool_use.input.get("command").and_then(|v| v.as_str())
+                else {
+                    continue;
+                };
+                let result = results.get(tool_use.id.as_str());
+                let names = command_names(command_line);
+                let last = names.len().saturating_sub(1);
+
+                for (i, name) in names.into_iter().enumerate() {
+                    let entry = stats.entry(name.clone()).or_insert_with(|| CommandStats {
+                        command: name.clone(),
+                        ..CommandStats::default()
+                    });
+                    entry.runs += 1;
+                    *entry
+                        .projects
+                        .entry(conversation.project_path.clone())
+                        .or_insert(0) += 1;
+                    if let Some(&(returned_at, outcome)) = result.filter(|_| i == last) {
+                        entry.outcomes.record(outcome);
+                        let seconds = (returned_at - message.timestamp).num_milliseconds();
+                        durations
+                            .entry(name)
+                            .or_default()
+                            .push(seconds.max(0) as f64 / 1000.0);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut stats: Vec<CommandStats> = stats
+        .into_values()
+        .map(|mut command| {
+            if let Some(seconds) = durations.get_mut(&command.command) {
+                seconds.sort_by(f64::total_cmp);
+                command.median_duration_secs = seconds.get(seconds.len() / 2).copied();
+            }
+            command
+        })
+        .collect();
+    stats.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.command.cmp(&b.command)));
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::conversation::ConversationMessage;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(command_names("cargo test --workspace"), ["cargo test"]);
+        assert_eq!(command_names("git status"), ["git status"]);
+        assert_eq!(command_names("npm run build -- --watch"), ["npm run build"]);
+        assert_eq!(
+            command_names("cd api && RUST_LOG=debug cargo build 2>&1 | tail -20"),
+            ["cargo build"]
+        );
+        assert_eq!(
+            command_names("cargo fmt; cargo clippy -- -D warnings || echo failed"),
+            ["cargo fmt", "cargo clippy", "echo"]
+        );
+        assert_eq!(command_names("ls -la src"), ["ls"]);
+        assert_eq!(command_names("/usr/bin/python3 script.py"), ["python3"]);
+        assert_eq!(
+            command_names("./scripts/deploy.sh prod"),
+            ["./scripts/deploy.sh"]
+        );
+        assert_eq!(command_names("git -C repo log"), ["git"]);
+        assert!(command_names("cd /tmp").is_empty());
+
+        // Separators inside quotes are part of an argument
+        assert_eq!(
+            command_names(r#"git commit -m "fix: a; b && c" && git push"#),
+            ["git commit", "git push"]
+        );
+        assert_eq!(command_names("echo 'x | y || z'; ls"), ["echo", "ls"]);
+        assert_eq!(
+            command_names(r#"grep "a\"|b" src && make test"#),
+            ["grep", "make test"]
+        );
+
+        // Heredoc bodies are input, not commands
+        assert_eq!(
+            command_names("python3 - <<'EOF'\nimport sys\nprint(x)\nEOF"),
+            ["python3"]
+        );
+        assert_eq!(
+            command_names("cat <<-EOF > notes.md\n\ta && b; c\n\tEOF\ncargo build"),
+            ["cat", "cargo build"]
+        );
+        assert_eq!(command_names("grep x <<< \"$out\" && ls"), ["grep", "ls"]);
+    }
+
+    #[test]
+    fn test_command_stats() {
+        let start = Utc.with_ymd_and_hms(2024, 6, 1, 10, 0, 0).unwrap();
+        let call = |id: &str, command: &str, second: i64| {
+            ConversationMessage::assistant(start + Duration::seconds(second)).with_tool_use(
+                id,
+                "Bash",
+                serde_json::json!({ "command": command }),
+            )
+        };
+        let result = |id: &str, content: &str, second: i64| {
+            ConversationMessage::user(start + Duration::seconds(second))
+                .with_tool_result(id, content, false)
+        };
+        let messages = vec![
+            call("t1", "cargo test", 0),
+            result("t1", "Exit code 101\ntest failed", 30),
+            call("t2", "cargo test", 40),
+            result("t2", "test result: ok", 50),
+            call("t3", "git status", 60),
+            result("t3", "clean", 61),
+            call("t4", "cargo test --release", 70),
+            // Only the last command of a chain gets the chain's result
+            call("t5", "cargo fmt && git status", 80),
+            result("t5", "clean", 90),
+        ];
+        let conversation = Conversation::new_for_test("s1", "-repo", messages);
+
+        let stats = command_stats([&conversation]);
+        assert_eq!(stats.len(), 3);
+        let cargo_test = &stats[0];
+        assert_eq!(cargo_test.command, "cargo test");
+        assert_eq!(cargo_test.runs, 3);
+        assert_eq!(cargo_test.outcomes.total(), 2);
+        assert_eq!(cargo_test.failure_rate(), Some(50.0));
+        assert_eq!(cargo_test.median_duration_secs, Some(30.0));
+        assert_eq!(cargo_test.top_projects(), [("-repo", 3)]);
+        assert_eq!(stats[1].command, "git status");
+        assert_eq!(stats[1].runs, 2);
+        assert_eq!(stats[1].outcomes.total(), 2);
+        assert_eq!(stats[1].failure_rate(), Some(0.0));
+        assert_eq!(stats[2].command, "cargo fmt");
+        assert_eq!(stats[2].runs, 1);
+        assert_eq!(stats[2].outcomes.total(), 0);
+        assert_eq!(stats[2].median_duration_secs, None);
+    }
+}
@@ -0,0 +1,119 @@
+//! Builders for conversations in unit tests. Every field a test doesn't set
+//! gets a default, so adding a field only touches this file.
+
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::conversation::{
+    Conversation, ConversationMessage, MessageRole, ToolResult, ToolUse, Usage,
+};
+
+/// Source of unique message uuids
+static NEXT_UUID: AtomicUsize = AtomicUsize::new(1);
+
+impl ConversationMessage {
+    /// An empty message with a unique uuid
+    pub fn new_for_test(role: MessageRole, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            uuid: format!("msg-{}", NEXT_UUID.fetch_add(1, Ordering::Relaxed)),
+            parent_uuid: None,
+            role,
+            content: String::new(),
+            timestamp,
+            model: None,
+            tool_uses: vec![],
+            tool_results: vec![],
+            usage: None,
+            message_id: None,
+        }
+    }
+
+    pub fn user(timestamp: DateTime<Utc>) -> Self {
+        Self::new_for_test(MessageRole::User, timestamp)
+    }
+
+    pub fn assistant(timestamp: DateTime<Utc>) -> Self {
+        Self::new_for_test(MessageRole::Assistant, timestamp)
+    }
+
+    pub fn with_uuid(mut self, uuid: impl Into<String>) -> Self {
+        self.uuid = uuid.into();
+        self
+    }
+
+    pub fn with_content(mut self, content: impl Into<String>) -> Self {
+        self.content = content.into();
+        self
+    }
+
+    pub fn with_model(mut self, model: &str) -> Self {
+        self.model = Some(model.to_string());
+        self
+    }
+
+    pub fn with_tool_use(mut self, id: &str, name: &str, input: serde_json::Value) -> Self {
+        self.tool_uses.push(ToolUse {
+            id: id.to_string(),
+            name: name.to_string(),
+            input,
+        });
+        self
+    }
+
+    pub fn with_tool_result(mut self, tool_use_id: &str, content: &str, is_error: bool) -> Self {
+        self.tool_results.push(ToolResult {
+            tool_use_id: tool_use_id.to_string(),
+            content: content.to_string(),
+            is_error,
+        });
+        self
+    }
+
+    pub fn with_usage(mut self, usage: Usage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    pub fn with_message_id(mut self, message_id: &str) -> Self {
+        self.message_id = Some(message_id.to_string());
+        self
+    }
+}
+
+/// Usage with only input and output tokens
+pub fn usage(input_tokens: u32, output_tokens: u32) -> Usage {
+    Usage {
+        input_tokens,
+        output_tokens,
+        ..Usage::default()
+    }
+}
+
+impl Conversation {
+    /// A conversation spanning the timestamps of its messages
+    pub fn new_for_test(
+        session_id: &str,
+        project_path: &str,
+        messages: Vec<ConversationMessage>,
+    ) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            project_path: project_path.to_string(),
+            cwd: None,
+            summary: None,
+            started_at: messages.first().map(|m| m.timestamp),
+            last_updated: messages.last().map(|m| m.timestamp),
+            messages,
+        }
+    }
+
+    pub fn with_cwd(mut self, cwd: &str) -> Self {
+        self.cwd = Some(cwd.to_string());
+        self
+    }
+
+    pub fn with_summary(mut self, summary: &str) -> Self {
+        self.summary = Some(summary.to_string());
+        self
+    }
+}
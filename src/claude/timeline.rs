@@ -1,5 +1,6 @@
-use crate::claude::{Conversation, MessageRole};
-use chrono::{DateTime, Duration, Timelike, Utc};
+use crate::claude::pricing::billed_usage;
+use crate::claude::{Conversation, LinkedCommit, MessageRole, Timezone, Tokenizer};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc, Weekday};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap, HashSet};
 
@@ -14,11 +15,24 @@ pub enum TimePeriod {
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     },
+    ThisWeek,      // Monday of the current ISO week until now
+    PreviousWeek,  // The whole previous ISO week
+    ThisMonth,     // First of the current month until now
+    PreviousMonth, // The whole previous calendar month
+    IsoWeek {
+        year: i32,
+        week: u32,
+    },
+    Quarter {
+        year: i32,
+        quarter: u32,
+    },
 }
 
 impl TimePeriod {
-    /// Get the start time for this time period
-    pub fn start_time(&self) -> DateTime<Utc> {
+    /// Get the start time for this time period, with calendar periods
+    /// starting at midnight in `tz`
+    pub fn start_time(&self, tz: &Timezone) -> DateTime<Utc> {
         let now = Utc::now();
         match self {
             TimePeriod::LastDay => now - Duration::days(1),
@@ -26,30 +40,240 @@ impl TimePeriod {
             TimePeriod::LastWeek => now - Duration::days(7),
             TimePeriod::LastMonth => now - Duration::days(30),
             TimePeriod::Custom { start, .. } => *start,
+            _ => self.calendar_bounds(now, tz).0,
         }
     }
 
     /// Get the end time for this time period
-    pub fn end_time(&self) -> DateTime<Utc> {
+    pub fn end_time(&self, tz: &Timezone) -> DateTime<Utc> {
+        let now = Utc::now();
         match self {
             TimePeriod::Custom { end, .. } => *end,
-            _ => Utc::now(),
+            TimePeriod::LastDay
+            | TimePeriod::LastTwoDay
+            | TimePeriod::LastWeek
+            | TimePeriod::LastMonth => now,
+            _ => self.calendar_bounds(now, tz).1,
         }
     }
 
+    /// Start and (inclusive) end of a calendar-aligned period relative to `now`,
+    /// with days starting at midnight in `tz`
+    fn calendar_bounds(&self, now: DateTime<Utc>, tz: &Timezone) -> (DateTime<Utc>, DateTime<Utc>) {
+        let today = tz.date(now);
+        let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+        let month_start = today.with_day(1).unwrap_or(today);
+
+        // Calendar periods end just before the next one starts
+        let (start, next_start) = match self {
+            TimePeriod::ThisWeek => return (tz.start_of_day(week_start), now),
+            TimePeriod::ThisMonth => return (tz.start_of_day(month_start), now),
+            TimePeriod::PreviousWeek => (week_start - Duration::days(7), week_start),
+            TimePeriod::PreviousMonth => (
+                month_start
+                    .checked_sub_months(Months::new(1))
+                    .unwrap_or(month_start),
+                month_start,
+            ),
+            TimePeriod::IsoWeek { year, week } => {
+                match NaiveDate::from_isoywd_opt(*year, *week, Weekday::Mon) {
+                    Some(start) => (start, start + Duration::days(7)),
+                    None => return (now, now),
+                }
+            }
+            TimePeriod::Quarter { year, quarter } => {
+                let month = quarter.saturating_sub(1) * 3 + 1;
+                match NaiveDate::from_ymd_opt(*year, month, 1) {
+                    Some(start) if (1..=4).contains(quarter) => (start, start + Months::new(3)),
+                    _ => return (now, now),
+                }
+            }
+            TimePeriod::LastDay
+            | TimePeriod::LastTwoDay
+            | TimePeriod::LastWeek
+            | TimePeriod::LastMonth
+            | TimePeriod::Custom { .. } => return (self.start_time(tz), self.end_time(tz)),
+        };
+
+        (
+            tz.start_of_day(start),
+            tz.start_of_day(next_start) - Duration::nanoseconds(1),
+        )
+    }
+
     /// Check if a timestamp falls within this time period
-    pub fn contains(&self, timestamp: DateTime<Utc>) -> bool {
-        timestamp >= self.start_time() && timestamp <= self.end_time()
+    pub fn contains(&self, timestamp: DateTime<Utc>, tz: &Timezone) -> bool {
+        timestamp >= self.start_time(tz) && timestamp <= self.end_time(tz)
+    }
+
+    /// Check if activity spanning `first..=last` overlaps this time period
+    pub fn overlaps(&self, first: DateTime<Utc>, last: DateTime<Utc>, tz: &Timezone) -> bool {
+        first <= self.end_time(tz) && last >= self.start_time(tz)
+    }
+
+    /// Length of the period in days, used for per-day rates
+    pub fn days(&self, tz: &Timezone) -> f64 {
+        match self {
+            TimePeriod::LastDay => 1.0,
+            TimePeriod::LastTwoDay => 2.0,
+            TimePeriod::LastWeek => 7.0,
+            TimePeriod::LastMonth => 30.0,
+            _ => {
+                let seconds = (self.end_time(tz) - self.start_time(tz)).num_seconds() as f64;
+                (seconds / 86_400.0).max(1.0)
+            }
+        }
+    }
+
+    /// Whether the period is aligned to calendar weeks, months or quarters
+    pub fn is_calendar(&self) -> bool {
+        !matches!(
+            self,
+            TimePeriod::LastDay
+                | TimePeriod::LastTwoDay
+                | TimePeriod::LastWeek
+                | TimePeriod::LastMonth
+                | TimePeriod::Custom { .. }
+        )
+    }
+
+    /// The ISO week or quarter before (negative `steps`) or after this one.
+    ///
+    /// Week-aligned periods step as ISO weeks and quarters as quarters; other
+    /// periods can't be stepped.
+    pub fn shifted(&self, steps: i32, tz: &Timezone) -> Option<TimePeriod> {
+        let now = Utc::now();
+        match self {
+            TimePeriod::ThisWeek | TimePeriod::PreviousWeek | TimePeriod::IsoWeek { .. } => {
+                let start = tz.date(self.calendar_bounds(now, tz).0);
+                let week = (start + Duration::weeks(steps as i64)).iso_week();
+                Some(TimePeriod::IsoWeek {
+                    year: week.year(),
+                    week: week.week(),
+                })
+            }
+            TimePeriod::Quarter { year, quarter } => {
+                let index = year * 4 + *quarter as i32 - 1 + steps;
+                Some(TimePeriod::Quarter {
+                    year: index.div_euclid(4),
+                    quarter: index.rem_euclid(4) as u32 + 1,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The period of the same kind immediately before this one, for
+    /// period-over-period comparison. Periods still in progress are compared
+    /// with the same stretch of the previous week or month.
+    pub fn previous(&self, tz: &Timezone) -> TimePeriod {
+        let (start, end) = (self.start_time(tz), self.end_time(tz));
+        match self {
+            TimePeriod::PreviousWeek | TimePeriod::IsoWeek { .. } | TimePeriod::Quarter { .. } => {
+                self.shifted(-1, tz).unwrap_or(*self)
+            }
+            TimePeriod::ThisWeek => TimePeriod::Custom {
+                start: start - Duration::weeks(1),
+                end: end - Duration::weeks(1),
+            },
+            TimePeriod::ThisMonth | TimePeriod::PreviousMonth => {
+                let month_earlier = |time: DateTime<Utc>| {
+                    let wall_clock = tz.naive(time);
+                    wall_clock
+                        .checked_sub_months(Months::new(1))
+                        .map(|earlier| tz.to_utc(earlier))
+                        .unwrap_or(time)
+                };
+                TimePeriod::Custom {
+                    start: month_earlier(start),
+                    end: if *self == TimePeriod::PreviousMonth {
+                        start - Duration::nanoseconds(1)
+                    } else {
+                        month_earlier(end)
+                    },
+                }
+            }
+            TimePeriod::LastDay
+            | TimePeriod::LastTwoDay
+            | TimePeriod::LastWeek
+            | TimePeriod::LastMonth
+            | TimePeriod::Custom { .. } => TimePeriod::Custom {
+                start: start - (end - start),
+                end: start - Duration::nanoseconds(1),
+            },
+        }
+    }
+
+    /// The quarter containing today in `tz`
+    pub fn current_quarter(tz: &Timezone) -> TimePeriod {
+        let today = tz.today();
+        TimePeriod::Quarter {
+            year: today.year(),
+            quarter: today.month0() / 3 + 1,
+        }
     }
 
-    /// Get a human-readable label for this time period
-    pub fn label(&self) -> &'static str {
+    /// Parse an ISO week such as `2024-W23`
+    pub fn parse_iso_week(value: &str) -> Result<TimePeriod, String> {
+        let parsed = value
+            .trim()
+            .to_uppercase()
+            .split_once("-W")
+            .and_then(|(year, week)| Some((year.parse::<i32>().ok()?, week.parse::<u32>().ok()?)));
+
+        match parsed {
+            Some((year, week))
+                if NaiveDate::from_isoywd_opt(year, week, Weekday::Mon).is_some() =>
+            {
+                Ok(TimePeriod::IsoWeek { year, week })
+            }
+            _ => Err(format!(
+                "Invalid ISO week '{}' (expected YYYY-Www, e.g. 2024-W23)",
+                value
+            )),
+        }
+    }
+
+    /// Parse a quarter such as `2024-Q2`
+    pub fn parse_quarter(value: &str) -> Result<TimePeriod, String> {
+        let parsed = value
+            .trim()
+            .to_uppercase()
+            .split_once("-Q")
+            .and_then(|(year, quarter)| {
+                Some((year.parse::<i32>().ok()?, quarter.parse::<u32>().ok()?))
+            });
+
+        match parsed {
+            Some((year, quarter)) if (1..=4).contains(&quarter) => {
+                Ok(TimePeriod::Quarter { year, quarter })
+            }
+            _ => Err(format!(
+                "Invalid quarter '{}' (expected YYYY-Qn, e.g. 2024-Q2)",
+                value
+            )),
+        }
+    }
+
+    /// Get a human-readable label for this time period, showing custom
+    /// ranges as wall-clock times in `tz`
+    pub fn label(&self, tz: &Timezone) -> String {
         match self {
-            TimePeriod::LastDay => "Past 24 hours",
-            TimePeriod::LastTwoDay => "Past 48 hours",
-            TimePeriod::LastWeek => "Past week",
-            TimePeriod::LastMonth => "Past month",
-            TimePeriod::Custom { .. } => "Custom range",
+            TimePeriod::LastDay => "Past 24 hours".to_string(),
+            TimePeriod::LastTwoDay => "Past 48 hours".to_string(),
+            TimePeriod::LastWeek => "Past 7 days".to_string(),
+            TimePeriod::LastMonth => "Past 30 days".to_string(),
+            TimePeriod::Custom { start, end } => format!(
+                "{} to {}",
+                tz.format(*start, "%Y-%m-%d %H:%M"),
+                tz.format(*end, "%Y-%m-%d %H:%M")
+            ),
+            TimePeriod::ThisWeek => "This week".to_string(),
+            TimePeriod::PreviousWeek => "Last week".to_string(),
+            TimePeriod::ThisMonth => "This month".to_string(),
+            TimePeriod::PreviousMonth => "Last month".to_string(),
+            TimePeriod::IsoWeek { year, week } => format!("Week {}-W{:02}", year, week),
+            TimePeriod::Quarter { year, quarter } => format!("{} Q{}", year, quarter),
         }
     }
 }
@@ -65,6 +289,9 @@ pub struct TimelineConfig {
     pub max_conversations_per_project: Option<usize>,
     /// Include empty projects in results
     pub include_empty_projects: bool,
+    /// Zone whose midnights start days, calendar weeks and months
+    #[serde(default)]
+    pub timezone: Timezone,
 }
 
 impl Default for TimelineConfig {
@@ -74,6 +301,7 @@ impl Default for TimelineConfig {
             summary_depth: SummaryDepth::Brief,
             max_conversations_per_project: Some(20),
             include_empty_projects: false,
+            timezone: Timezone::default(),
         }
     }
 }
@@ -116,6 +344,9 @@ pub struct ProjectActivity {
     pub last_activity: Option<DateTime<Utc>>,
     /// Visual indicators for this project
     pub indicators: ActivityIndicators,
+    /// Commits linked to this project's conversations, once correlated with git
+    #[serde(default)]
+    pub agent_commits: Option<usize>,
 }
 
 /// Summary of a conversation for timeline display
@@ -137,6 +368,9 @@ pub struct ConversationSummary {
     pub assistant_message_count: usize,
     /// Tool usage count
     pub tool_usage_count: usize,
+    /// Tokens billed across the conversation's messages
+    #[serde(default)]
+    pub total_tokens: u64,
     /// Main topics discussed
     pub topics: Vec<String>,
     /// Brief content summary
@@ -245,6 +479,9 @@ pub struct ProjectStats {
     pub peak_hour: Option<u8>,
     /// Tool usage frequency
     pub tool_usage: HashMap<String, usize>,
+    /// Tokens billed in period
+    #[serde(default)]
+    pub total_tokens: u64,
     /// Daily conversation frequency (conversations per day)
     pub conversation_frequency: f64,
     /// Message frequency (messages per day)
@@ -312,21 +549,21 @@ impl ActivityTimeline {
     }
 
     /// Filter conversations by time period
-    pub fn filter_conversations_by_time_period(
-        conversations: &[Conversation],
+    pub fn filter_conversations_by_time_period<'a>(
+        conversations: &'a [Conversation],
         period: TimePeriod,
-    ) -> Vec<&Conversation> {
-        let start_time = period.start_time();
-        let end_time = period.end_time();
+        tz: &Timezone,
+    ) -> Vec<&'a Conversation> {
+        let start_time = period.start_time(tz);
+        let end_time = period.end_time(tz);
 
         conversations
             .iter()
             .filter(|conv| {
                 // Check if conversation has activity within the time period
-                if let Some(last_updated) = conv.last_updated {
-                    last_updated >= start_time && last_updated <= end_time
-                } else if let Some(started_at) = conv.started_at {
-                    started_at >= start_time && started_at <= end_time
+                if let Some(first) = conv.started_at.or(conv.last_updated) {
+                    let last = conv.last_updated.unwrap_or(first);
+                    first <= end_time && last >= start_time
                 } else {
                     // If no timestamps, check message timestamps
                     conv.messages
@@ -362,8 +599,11 @@ impl ActivityTimeline {
         let mut timeline = Self::new(config.clone());
 
         // Step 1: Filter by time period
-        let filtered_convs =
-            Self::filter_conversations_by_time_period(&all_conversations, config.period);
+        let filtered_convs = Self::filter_conversations_by_time_period(
+            &all_conversations,
+            config.period,
+            &config.timezone,
+        );
 
         // Step 2: Group by project
         let project_groups = Self::group_conversations_by_project(filtered_convs);
@@ -397,6 +637,7 @@ impl ActivityTimeline {
                 project_path.clone(),
                 limited_conversations,
                 config.summary_depth,
+                &config.timezone,
             );
 
             timeline.projects.insert(project_path, project_activity);
@@ -415,6 +656,30 @@ impl ActivityTimeline {
         Self::create_filtered_timeline(conversations, config)
     }
 
+    /// Count the commits in the timeline period that each project's
+    /// conversations contributed to
+    pub fn attach_agent_commits(&mut self, commits: &[LinkedCommit]) {
+        let (period, tz) = (self.config.period, self.config.timezone);
+        for project in self.projects.values_mut() {
+            let session_ids: HashSet<&str> = project
+                .conversations
+                .iter()
+                .map(|conv| conv.session_id.as_str())
+                .collect();
+            let count = commits
+                .iter()
+                .filter(|linked| period.contains(linked.commit.timestamp, &tz))
+                .filter(|linked| {
+                    linked
+                        .sessions
+                        .iter()
+                        .any(|link| session_ids.contains(link.session_id.as_str()))
+                })
+                .count();
+            project.agent_commits = Some(count);
+        }
+    }
+
     /// Get projects sorted by activity level (most active first)
     pub fn projects_by_activity(&self) -> Vec<&ProjectActivity> {
         let mut projects: Vec<_> = self.projects.values().collect();
@@ -457,13 +722,7 @@ impl ActivityTimeline {
         let mut all_conversation_lengths = Vec::new();
 
         // Calculate time period in days
-        let days = match self.config.period {
-            TimePeriod::LastDay => 1.0,
-            TimePeriod::LastTwoDay => 2.0,
-            TimePeriod::LastWeek => 7.0,
-            TimePeriod::LastMonth => 30.0,
-            TimePeriod::Custom { start, end } => (end - start).num_days() as f64,
-        };
+        let days = self.config.period.days(&self.config.timezone);
 
         // First pass: collect basic statistics
         for (project_path, project) in &self.projects {
@@ -600,12 +859,13 @@ impl ActivityTimeline {
     /// Build temporal index for fast time-based queries
     fn build_temporal_index(&mut self) {
         let mut temporal_index = TemporalIndex::default();
+        let tz = self.config.timezone;
 
         for project in self.projects.values() {
             for conv_summary in &project.conversations {
                 let session_id = conv_summary.session_id.clone();
-                let date = conv_summary.started_at.format("%Y-%m-%d").to_string();
-                let hour = conv_summary.started_at.hour() as u8;
+                let date = tz.day_key(conv_summary.started_at);
+                let hour = tz.hour(conv_summary.started_at) as u8;
 
                 // Index by day
                 temporal_index
@@ -789,12 +1049,13 @@ impl ActivityTimeline {
         &self,
         conversations: &[ConversationSummary],
     ) -> Vec<u32> {
-        // Create daily message count data for the last 7 days
+        // Create daily message count data for the last 7 calendar days
         let mut daily_counts = vec![0u32; 7];
-        let now = Utc::now();
+        let tz = self.config.timezone;
+        let today = tz.today();
 
         for conv in conversations {
-            let days_ago = (now - conv.started_at).num_days();
+            let days_ago = (today - tz.date(conv.started_at)).num_days();
             if days_ago >= 0 && days_ago < 7 {
                 let index = (6 - days_ago) as usize; // Reverse order (most recent first)
                 daily_counts[index] += conv.message_count as u32;
@@ -828,20 +1089,21 @@ impl ActivityTimeline {
     }
 
     /// Efficiently filter large datasets using parallel processing
-    pub fn filter_conversations_parallel(
-        conversations: &[Conversation],
+    pub fn filter_conversations_parallel<'a>(
+        conversations: &'a [Conversation],
         period: TimePeriod,
+        tz: &Timezone,
         batch_size: usize,
-    ) -> Vec<&Conversation> {
+    ) -> Vec<&'a Conversation> {
         if conversations.len() < batch_size {
             // For small datasets, use sequential filtering
-            return Self::filter_conversations_by_time_period(conversations, period);
+            return Self::filter_conversations_by_time_period(conversations, period, tz);
         }
 
         // Process in batches for memory efficiency
         let mut results = Vec::new();
         for chunk in conversations.chunks(batch_size) {
-            let mut chunk_results = Self::filter_conversations_by_time_period(chunk, period);
+            let mut chunk_results = Self::filter_conversations_by_time_period(chunk, period, tz);
             results.append(&mut chunk_results);
         }
 
@@ -852,6 +1114,7 @@ impl ActivityTimeline {
     pub fn filter_conversations_advanced<'a>(
         conversations: &'a [Conversation],
         period: TimePeriod,
+        tz: &Timezone,
         min_messages: Option<usize>,
         project_filter: Option<&str>,
         has_tools: Option<bool>,
@@ -860,14 +1123,12 @@ impl ActivityTimeline {
             .iter()
             .filter(|conv| {
                 // Time period filter
-                let in_time_period = if let Some(last_updated) = conv.last_updated {
-                    period.contains(last_updated)
-                } else if let Some(started_at) = conv.started_at {
-                    period.contains(started_at)
+                let in_time_period = if let Some(first) = conv.started_at.or(conv.last_updated) {
+                    period.overlaps(first, conv.last_updated.unwrap_or(first), tz)
                 } else {
                     conv.messages
                         .iter()
-                        .any(|msg| period.contains(msg.timestamp))
+                        .any(|msg| period.contains(msg.timestamp, tz))
                 };
 
                 if !in_time_period {
@@ -908,7 +1169,7 @@ impl ActivityTimeline {
 
         for project in self.projects.values() {
             for conv_summary in &project.conversations {
-                if custom_period.contains(conv_summary.started_at) {
+                if custom_period.contains(conv_summary.started_at, &self.config.timezone) {
                     stats.total_conversations += 1;
                     stats.total_messages += conv_summary.message_count;
                 }
@@ -930,10 +1191,11 @@ impl ActivityTimeline {
     /// This method can only filter to a shorter or equal time period than the current one
     pub fn filter_to_period(&self, new_period: TimePeriod) -> Result<Self, String> {
         // Check if the new period is within the current period bounds
-        let current_start = self.config.period.start_time();
-        let current_end = self.config.period.end_time();
-        let new_start = new_period.start_time();
-        let new_end = new_period.end_time();
+        let tz = self.config.timezone;
+        let current_start = self.config.period.start_time(&tz);
+        let current_end = self.config.period.end_time(&tz);
+        let new_start = new_period.start_time(&tz);
+        let new_end = new_period.end_time(&tz);
 
         if new_start < current_start || new_end > current_end {
             return Err(
@@ -956,8 +1218,11 @@ impl ActivityTimeline {
                 .iter()
                 .filter(|conv| {
                     // Check if conversation activity falls within the new time period
-                    new_period.contains(conv.started_at)
-                        || new_period.contains(conv.ended_at.unwrap_or(conv.started_at))
+                    new_period.overlaps(
+                        conv.started_at,
+                        conv.ended_at.unwrap_or(conv.started_at),
+                        &tz,
+                    )
                 })
                 .cloned()
                 .collect();
@@ -971,10 +1236,11 @@ impl ActivityTimeline {
                     topical_summary: project_activity.topical_summary.clone(),
                     indicators: project_activity.indicators.clone(),
                     last_activity: project_activity.last_activity,
+                    agent_commits: None,
                 };
 
                 // Recalculate stats for the filtered conversations
-                filtered_project.recalculate_stats(&new_period);
+                filtered_project.recalculate_stats(&new_period, &tz);
 
                 filtered_timeline
                     .projects
@@ -994,11 +1260,12 @@ impl ActivityTimeline {
     /// Update temporal index based on current conversations
     fn update_temporal_index(&mut self) {
         let mut temporal_index = TemporalIndex::default();
+        let tz = self.config.timezone;
 
         for project_activity in self.projects.values() {
             for conv_summary in &project_activity.conversations {
                 // Index by day
-                let day_key = conv_summary.started_at.format("%Y-%m-%d").to_string();
+                let day_key = tz.day_key(conv_summary.started_at);
                 temporal_index
                     .by_day
                     .entry(day_key.clone())
@@ -1006,7 +1273,7 @@ impl ActivityTimeline {
                     .push(conv_summary.session_id.clone());
 
                 // Index by hour
-                let hour = conv_summary.started_at.hour() as u8;
+                let hour = tz.hour(conv_summary.started_at) as u8;
                 temporal_index
                     .by_hour
                     .entry(hour)
@@ -1029,13 +1296,14 @@ impl ProjectActivity {
         project_path: String,
         conversations: Vec<Conversation>,
         summary_depth: SummaryDepth,
+        tz: &Timezone,
     ) -> Self {
         let conversation_summaries: Vec<_> = conversations
             .iter()
             .map(ConversationSummary::from_conversation)
             .collect();
 
-        let stats = ProjectStats::from_conversations(&conversations);
+        let stats = ProjectStats::from_conversations(&conversations, tz);
         let topical_summary = TopicalSummary::from_conversations(&conversations, summary_depth);
 
         let last_activity = conversations.iter().filter_map(|c| c.last_updated).max();
@@ -1056,11 +1324,12 @@ impl ProjectActivity {
             stats,
             last_activity,
             indicators,
+            agent_commits: None,
         }
     }
 
     /// Recalculate project statistics for filtered conversations
-    pub fn recalculate_stats(&mut self, time_period: &TimePeriod) {
+    pub fn recalculate_stats(&mut self, time_period: &TimePeriod, tz: &Timezone) {
         // Reset stats
         self.stats = ProjectStats::default();
 
@@ -1087,11 +1356,12 @@ impl ProjectActivity {
                 .or_default() += conv_summary.assistant_message_count;
 
             // Track hour activity
-            let hour = conv_summary.started_at.hour() as u8;
+            let hour = tz.hour(conv_summary.started_at) as u8;
             *hour_counts.entry(hour).or_default() += conv_summary.message_count;
 
             // Count tool usage
             self.stats.total_messages += conv_summary.tool_usage_count;
+            self.stats.total_tokens += conv_summary.total_tokens;
         }
 
         // Calculate derived statistics
@@ -1108,15 +1378,7 @@ impl ProjectActivity {
             .map(|(&hour, _)| hour);
 
         // Calculate frequencies based on the time period
-        let period_days = match time_period {
-            TimePeriod::LastDay => 1.0,
-            TimePeriod::LastTwoDay => 2.0,
-            TimePeriod::LastWeek => 7.0,
-            TimePeriod::LastMonth => 30.0,
-            TimePeriod::Custom { start, end } => {
-                (end.signed_duration_since(*start).num_days() as f64).max(1.0)
-            }
-        };
+        let period_days = time_period.days(tz);
 
         self.stats.conversation_frequency = self.conversations.len() as f64 / period_days;
         self.stats.message_frequency = self.stats.total_messages as f64 / period_days;
@@ -1160,6 +1422,9 @@ impl ConversationSummary {
                 .iter()
                 .map(|m| m.tool_uses.len())
                 .sum(),
+            total_tokens: billed_usage(&conversation.messages)
+                .map(|(_, usage)| usage.total_tokens())
+                .sum(),
             topics,
             content_summary,
         }
@@ -1168,7 +1433,7 @@ impl ConversationSummary {
 
 impl ProjectStats {
     /// Generate project statistics from conversations
-    pub fn from_conversations(conversations: &[Conversation]) -> Self {
+    pub fn from_conversations(conversations: &[Conversation], tz: &Timezone) -> Self {
         let mut stats = Self::default();
 
         stats.conversation_count = conversations.len();
@@ -1189,7 +1454,7 @@ impl ProjectStats {
                     .or_default() += 1;
 
                 // Track hour activity
-                let hour = message.timestamp.hour() as u8;
+                let hour = tz.hour(message.timestamp) as u8;
                 *hour_counts.entry(hour).or_default() += 1;
 
                 // Count tool usage
@@ -1197,6 +1462,9 @@ impl ProjectStats {
                     *tool_counts.entry(tool_use.name.clone()).or_default() += 1;
                 }
             }
+            stats.total_tokens += billed_usage(&conversation.messages)
+                .map(|(_, usage)| usage.total_tokens())
+                .sum::<u64>();
         }
 
         // Calculate average conversation length
@@ -1277,6 +1545,8 @@ struct TextAnalyzer {
     stop_words: HashSet<String>,
     // Technical terms that are often relevant
     technical_terms: HashSet<String>,
+    // Code-aware tokenizer shared with search (parts only, no stemming)
+    tokenizer: Tokenizer,
 }
 
 impl TextAnalyzer {
@@ -1386,13 +1656,13 @@ impl TextAnalyzer {
         Self {
             stop_words,
             technical_terms,
+            tokenizer: Tokenizer::new().with_identifiers(false),
         }
     }
 
     /// Extract key phrases from text using frequency and position analysis
     fn extract_key_phrases(&self, text: &str, max_phrases: usize) -> Vec<String> {
-        let cleaned_text = self.clean_text(text);
-        let words = self.tokenize(&cleaned_text);
+        let words = self.tokenize(text);
 
         // Extract single word topics
         let mut word_scores = HashMap::new();
@@ -1452,7 +1722,7 @@ impl TextAnalyzer {
 
         // Calculate term frequencies and document frequencies
         for doc in documents {
-            let words = self.tokenize(&self.clean_text(doc));
+            let words = self.tokenize(doc);
             let mut doc_terms = HashSet::new();
 
             for word in &words {
@@ -1507,29 +1777,9 @@ impl TextAnalyzer {
         topics
     }
 
-    /// Clean and normalize text for analysis
-    fn clean_text(&self, text: &str) -> String {
-        text.to_lowercase()
-            .chars()
-            .map(|c| {
-                if c.is_alphanumeric() || c.is_whitespace() {
-                    c
-                } else {
-                    ' '
-                }
-            })
-            .collect::<String>()
-            .split_whitespace()
-            .collect::<Vec<&str>>()
-            .join(" ")
-    }
-
-    /// Tokenize text into meaningful words
+    /// Tokenize text into lowercase words, splitting code identifiers and paths
     fn tokenize(&self, text: &str) -> Vec<String> {
-        text.split_whitespace()
-            .map(|s| s.to_string())
-            .filter(|word| word.len() > 2)
-            .collect()
+        self.tokenizer.tokenize(text)
     }
 
     /// Check if a word is meaningful for topic extraction
@@ -1833,7 +2083,7 @@ fn extract_frequent_tools(conversations: &[Conversation]) -> Vec<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::claude::conversation::ToolUse;
+    use chrono::TimeZone;
 
     #[test]
     fn test_time_period_contains() {
@@ -1842,15 +2092,166 @@ mod tests {
         let two_days_ago = now - Duration::days(2);
 
         let period = TimePeriod::LastDay;
-        assert!(period.contains(yesterday + Duration::hours(1)));
-        assert!(!period.contains(two_days_ago));
+        assert!(period.contains(yesterday + Duration::hours(1), &Timezone::Utc));
+        assert!(!period.contains(two_days_ago, &Timezone::Utc));
     }
 
     #[test]
     fn test_time_period_labels() {
-        assert_eq!(TimePeriod::LastDay.label(), "Past 24 hours");
-        assert_eq!(TimePeriod::LastTwoDay.label(), "Past 48 hours");
-        assert_eq!(TimePeriod::LastWeek.label(), "Past week");
+        let tz = Timezone::Utc;
+        assert_eq!(TimePeriod::LastDay.label(&tz), "Past 24 hours");
+        assert_eq!(TimePeriod::LastTwoDay.label(&tz), "Past 48 hours");
+        assert_eq!(TimePeriod::LastWeek.label(&tz), "Past 7 days");
+    }
+
+    #[test]
+    fn test_calendar_periods() {
+        let tz = Timezone::Utc;
+        // Wednesday 2024-06-12
+        let now = Utc.with_ymd_and_hms(2024, 6, 12, 15, 30, 0).unwrap();
+        let day = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap();
+        let just_before = |y, m, d| day(y, m, d) - Duration::nanoseconds(1);
+
+        assert_eq!(
+            TimePeriod::ThisWeek.calendar_bounds(now, &tz),
+            (day(2024, 6, 10), now)
+        );
+        assert_eq!(
+            TimePeriod::PreviousWeek.calendar_bounds(now, &tz),
+            (day(2024, 6, 3), just_before(2024, 6, 10))
+        );
+        assert_eq!(
+            TimePeriod::ThisMonth.calendar_bounds(now, &tz),
+            (day(2024, 6, 1), now)
+        );
+        assert_eq!(
+            TimePeriod::PreviousMonth.calendar_bounds(now, &tz),
+            (day(2024, 5, 1), just_before(2024, 6, 1))
+        );
+        assert_eq!(
+            TimePeriod::IsoWeek {
+                year: 2024,
+                week: 1
+            }
+            .calendar_bounds(now, &tz),
+            (day(2024, 1, 1), just_before(2024, 1, 8))
+        );
+        assert_eq!(
+            TimePeriod::Quarter {
+                year: 2024,
+                quarter: 4
+            }
+            .calendar_bounds(now, &tz),
+            (day(2024, 10, 1), just_before(2025, 1, 1))
+        );
+
+        let quarter = TimePeriod::Quarter {
+            year: 2024,
+            quarter: 2,
+        };
+        assert!(quarter.contains(day(2024, 6, 30), &tz));
+        assert!(!quarter.contains(day(2024, 7, 1), &tz));
+        assert!(quarter.overlaps(day(2024, 6, 30), day(2024, 7, 2), &tz));
+        assert!((quarter.days(&tz) - 91.0).abs() < 0.001);
+        assert_eq!(quarter.label(&tz), "2024 Q2");
+    }
+
+    #[test]
+    fn test_parse_and_shift_calendar_periods() {
+        let tz = Timezone::Utc;
+        assert_eq!(
+            TimePeriod::parse_iso_week("2024-w23"),
+            Ok(TimePeriod::IsoWeek {
+                year: 2024,
+                week: 23
+            })
+        );
+        assert!(TimePeriod::parse_iso_week("2024-W54").is_err());
+        assert_eq!(
+            TimePeriod::parse_quarter("2024-Q2"),
+            Ok(TimePeriod::Quarter {
+                year: 2024,
+                quarter: 2
+            })
+        );
+        assert!(TimePeriod::parse_quarter("2024-Q5").is_err());
+
+        assert_eq!(
+            TimePeriod::IsoWeek {
+                year: 2024,
+                week: 1
+            }
+            .shifted(-1, &tz),
+            Some(TimePeriod::IsoWeek {
+                year: 2023,
+                week: 52
+            })
+        );
+        assert_eq!(
+            TimePeriod::Quarter {
+                year: 2024,
+                quarter: 4
+            }
+            .shifted(1, &tz),
+            Some(TimePeriod::Quarter {
+                year: 2025,
+                quarter: 1
+            })
+        );
+        assert_eq!(TimePeriod::LastDay.shifted(1, &tz), None);
+    }
+
+    #[test]
+    fn test_previous_period() {
+        let tz = Timezone::Utc;
+        assert_eq!(
+            TimePeriod::IsoWeek {
+                year: 2024,
+                week: 23
+            }
+            .previous(&tz),
+            TimePeriod::IsoWeek {
+                year: 2024,
+                week: 22
+            }
+        );
+
+        let start = Utc.with_ymd_and_hms(2024, 6, 10, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2024, 6, 13, 0, 0, 0).unwrap();
+        let previous = TimePeriod::Custom { start, end }.previous(&tz);
+        assert_eq!(
+            previous.start_time(&tz),
+            Utc.with_ymd_and_hms(2024, 6, 7, 0, 0, 0).unwrap()
+        );
+        assert!(previous.end_time(&tz) < start);
+
+        let last_week = TimePeriod::LastWeek.previous(&tz);
+        assert!(last_week.end_time(&tz) < TimePeriod::LastWeek.start_time(&tz));
+    }
+
+    #[test]
+    fn test_filter_past_period_by_overlap() {
+        let in_week = Utc.with_ymd_and_hms(2024, 6, 5, 12, 0, 0).unwrap();
+        let after_week = Utc.with_ymd_and_hms(2024, 6, 20, 12, 0, 0).unwrap();
+        let mut spanning = create_test_conversation("spanning", in_week);
+        spanning.last_updated = Some(after_week);
+        let conversations = vec![
+            spanning,
+            create_test_conversation("inside", in_week),
+            create_test_conversation("later", after_week),
+        ];
+
+        let week = TimePeriod::IsoWeek {
+            year: 2024,
+            week: 23,
+        };
+        let filtered = ActivityTimeline::filter_conversations_by_time_period(
+            &conversations,
+            week,
+            &Timezone::Utc,
+        );
+        let ids: Vec<&str> = filtered.iter().map(|c| c.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["spanning", "inside"]);
     }
 
     #[test]
@@ -1918,6 +2319,7 @@ mod tests {
         let filtered_24h = ActivityTimeline::filter_conversations_by_time_period(
             &conversations,
             TimePeriod::LastDay,
+            &Timezone::Utc,
         );
         assert_eq!(filtered_24h.len(), 1);
         assert_eq!(filtered_24h[0].session_id, "conv1");
@@ -1926,6 +2328,7 @@ mod tests {
         let filtered_48h = ActivityTimeline::filter_conversations_by_time_period(
             &conversations,
             TimePeriod::LastTwoDay,
+            &Timezone::Utc,
         );
         assert_eq!(filtered_48h.len(), 2);
 
@@ -1933,6 +2336,7 @@ mod tests {
         let filtered_week = ActivityTimeline::filter_conversations_by_time_period(
             &conversations,
             TimePeriod::LastWeek,
+            &Timezone::Utc,
         );
         assert_eq!(filtered_week.len(), 2);
     }
@@ -1968,6 +2372,7 @@ mod tests {
         let filtered = ActivityTimeline::filter_conversations_advanced(
             &conversations,
             TimePeriod::LastDay,
+            &Timezone::Utc,
             Some(3),
             None,
             Some(true),
@@ -1987,12 +2392,14 @@ mod tests {
         let filtered_batch = ActivityTimeline::filter_conversations_parallel(
             &conversations,
             TimePeriod::LastTwoDay,
+            &Timezone::Utc,
             10, // batch size
         );
 
         let filtered_normal = ActivityTimeline::filter_conversations_by_time_period(
             &conversations,
             TimePeriod::LastTwoDay,
+            &Timezone::Utc,
         );
 
         // Should produce same results
@@ -2014,6 +2421,7 @@ mod tests {
             summary_depth: SummaryDepth::Brief,
             max_conversations_per_project: Some(5),
             include_empty_projects: false,
+            timezone: Timezone::Utc,
         };
 
         let timeline = ActivityTimeline::create_filtered_timeline(conversations, config);
@@ -2030,26 +2438,21 @@ mod tests {
 
     // Helper functions for creating test data
     fn create_test_conversation(session_id: &str, timestamp: DateTime<Utc>) -> Conversation {
-        Conversation {
-            session_id: session_id.to_string(),
-            project_path: "/default/project".to_string(),
-            summary: Some(format!("Test conversation {}", session_id)),
-            messages: vec![create_test_message(timestamp)],
-            started_at: Some(timestamp),
-            last_updated: Some(timestamp),
-        }
+        Conversation::new_for_test(
+            session_id,
+            "/default/project",
+            vec![create_test_message(timestamp)],
+        )
+        .with_summary(&format!("Test conversation {}", session_id))
     }
 
     fn create_test_conversation_with_project(session_id: &str, project_path: &str) -> Conversation {
-        let now = Utc::now();
-        Conversation {
-            session_id: session_id.to_string(),
-            project_path: project_path.to_string(),
-            summary: Some(format!("Test conversation {}", session_id)),
-            messages: vec![create_test_message(now)],
-            started_at: Some(now),
-            last_updated: Some(now),
-        }
+        Conversation::new_for_test(
+            session_id,
+            project_path,
+            vec![create_test_message(Utc::now())],
+        )
+        .with_summary(&format!("Test conversation {}", session_id))
     }
 
     fn create_test_conversation_with_tools(
@@ -2062,37 +2465,18 @@ mod tests {
         for i in 0..message_count {
             let mut msg = create_test_message(timestamp + Duration::minutes(i as i64));
             if has_tools && i == 0 {
-                msg.tool_uses = vec![ToolUse {
-                    id: "tool1".to_string(),
-                    name: "test_tool".to_string(),
-                    input: serde_json::json!({"param": "value"}),
-                }];
+                msg =
+                    msg.with_tool_use("tool1", "test_tool", serde_json::json!({"param": "value"}));
             }
             messages.push(msg);
         }
 
-        Conversation {
-            session_id: session_id.to_string(),
-            project_path: "/test/project".to_string(),
-            summary: Some(format!("Test conversation {}", session_id)),
-            messages,
-            started_at: Some(timestamp),
-            last_updated: Some(timestamp),
-        }
+        Conversation::new_for_test(session_id, "/test/project", messages)
+            .with_summary(&format!("Test conversation {}", session_id))
     }
 
     fn create_test_message(timestamp: DateTime<Utc>) -> crate::claude::ConversationMessage {
-        use crate::claude::ConversationMessage;
-
-        ConversationMessage {
-            uuid: format!("msg-{}", timestamp.timestamp()),
-            parent_uuid: None,
-            role: MessageRole::User,
-            content: "Test message content".to_string(),
-            timestamp,
-            model: None,
-            tool_uses: vec![],
-        }
+        crate::claude::ConversationMessage::user(timestamp).with_content("Test message content")
     }
 
     #[test]
@@ -2106,9 +2490,12 @@ mod tests {
         assert!(!analyzer.is_meaningful_word("123"));
         assert!(!analyzer.is_meaningful_word("a"));
 
-        // Test text cleaning
-        let cleaned = analyzer.clean_text("Hello, World! This is a test.");
-        assert_eq!(cleaned, "hello world this is a test");
+        // Test tokenization (punctuation stripped, identifiers split)
+        let words = analyzer.tokenize("Hello, World! See parseConversationFile.");
+        assert_eq!(
+            words,
+            vec!["hello", "world", "see", "parse", "conversation", "file"]
+        );
     }
 
     #[test]
@@ -2191,6 +2578,7 @@ mod tests {
             "test-project".to_string(),
             conversations,
             SummaryDepth::Detailed,
+            &Timezone::Utc,
         );
 
         // Should have extracted meaningful topics
@@ -2254,36 +2642,15 @@ mod tests {
         use crate::claude::ConversationMessage;
 
         let messages = vec![
-            ConversationMessage {
-                uuid: format!("{}-user", session_id),
-                parent_uuid: None,
-                role: MessageRole::User,
-                content: user_message.to_string(),
-                timestamp,
-                model: None,
-                tool_uses: vec![],
-            },
-            ConversationMessage {
-                uuid: format!("{}-assistant", session_id),
-                parent_uuid: Some(format!("{}-user", session_id)),
-                role: MessageRole::Assistant,
-                content: format!(
+            ConversationMessage::user(timestamp).with_content(user_message),
+            ConversationMessage::assistant(timestamp + Duration::minutes(1))
+                .with_content(format!(
                     "I can help you with {}. Let me provide some guidance.",
                     summary.to_lowercase()
-                ),
-                timestamp: timestamp + Duration::minutes(1),
-                model: Some("claude-3".to_string()),
-                tool_uses: vec![],
-            },
+                ))
+                .with_model("claude-3"),
         ];
 
-        Conversation {
-            session_id: session_id.to_string(),
-            project_path: "/test/project".to_string(),
-            summary: Some(summary.to_string()),
-            messages,
-            started_at: Some(timestamp),
-            last_updated: Some(timestamp + Duration::minutes(2)),
-        }
+        Conversation::new_for_test(session_id, "/test/project", messages).with_summary(summary)
     }
 }
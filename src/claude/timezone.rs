@@ -0,0 +1,187 @@
+use chrono::{
+    DateTime, Datelike, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone,
+    Timelike, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Time zone used to bucket timestamps into days, hours and weekdays
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Timezone {
+    /// The system's local time zone
+    #[default]
+    Local,
+    Utc,
+    /// An IANA time zone, e.g. America/Los_Angeles
+    Named(Tz),
+}
+
+impl Timezone {
+    /// Parse `local`, `UTC` or an IANA time zone name
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("local") {
+            Ok(Timezone::Local)
+        } else if name.eq_ignore_ascii_case("utc") || name.eq_ignore_ascii_case("z") {
+            Ok(Timezone::Utc)
+        } else {
+            name.parse::<Tz>().map(Timezone::Named).map_err(|_| {
+                format!(
+                    "Unknown time zone '{}': use 'local', 'UTC' or an IANA name like America/Los_Angeles",
+                    name
+                )
+            })
+        }
+    }
+
+    /// Wall-clock date and time of a timestamp in this zone
+    pub fn naive(&self, timestamp: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Timezone::Local => timestamp.with_timezone(&Local).naive_local(),
+            Timezone::Utc => timestamp.naive_utc(),
+            Timezone::Named(tz) => timestamp.with_timezone(tz).naive_local(),
+        }
+    }
+
+    /// Calendar date of a timestamp in this zone
+    pub fn date(&self, timestamp: DateTime<Utc>) -> NaiveDate {
+        self.naive(timestamp).date()
+    }
+
+    /// Hour of day (0-23) of a timestamp in this zone
+    pub fn hour(&self, timestamp: DateTime<Utc>) -> u32 {
+        self.naive(timestamp).hour()
+    }
+
+    pub fn weekday(&self, timestamp: DateTime<Utc>) -> Weekday {
+        self.naive(timestamp).weekday()
+    }
+
+    /// `YYYY-MM-DD` key of the day a timestamp falls on
+    pub fn day_key(&self, timestamp: DateTime<Utc>) -> String {
+        self.date(timestamp).format("%Y-%m-%d").to_string()
+    }
+
+    /// Today's date in this zone
+    pub fn today(&self) -> NaiveDate {
+        self.date(Utc::now())
+    }
+
+    /// The instant a wall-clock time occurs. Times skipped by a DST change are
+    /// moved forward by the length of the gap, so midnight on a spring-forward
+    /// day resolves to the first instant of that day.
+    pub fn to_utc(&self, naive: NaiveDateTime) -> DateTime<Utc> {
+        fn resolve<Z: TimeZone>(zone: &Z, naive: NaiveDateTime) -> DateTime<Utc> {
+            match zone.from_local_datetime(&naive) {
+                LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => {
+                    time.with_timezone(&Utc)
+                }
+                LocalResult::None => {
+                    // Spring-forward gap: the offset before the gap gives the instant after it
+                    let offset = zone.offset_from_utc_datetime(&naive).fix();
+                    naive.and_utc() - chrono::Duration::seconds(offset.local_minus_utc() as i64)
+                }
+            }
+        }
+        match self {
+            Timezone::Local => resolve(&Local, naive),
+            Timezone::Utc => naive.and_utc(),
+            Timezone::Named(tz) => resolve(tz, naive),
+        }
+    }
+
+    /// Midnight at the start of a date in this zone
+    pub fn start_of_day(&self, date: NaiveDate) -> DateTime<Utc> {
+        self.to_utc(date.and_time(NaiveTime::MIN))
+    }
+
+    /// Format a timestamp as wall-clock time in this zone
+    pub fn format(&self, timestamp: DateTime<Utc>, format: &str) -> String {
+        self.naive(timestamp).format(format).to_string()
+    }
+
+    /// A timestamp as `YYYY-MM-DD HH:MM:SS` followed by the zone name, or by
+    /// the UTC offset for local time
+    pub fn format_with_zone(&self, timestamp: DateTime<Utc>) -> String {
+        let time = self.format(timestamp, "%Y-%m-%d %H:%M:%S");
+        match self {
+            Timezone::Local => {
+                format!("{} {}", time, timestamp.with_timezone(&Local).format("%:z"))
+            }
+            _ => format!("{} {}", time, self),
+        }
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Timezone::Local => write!(f, "local"),
+            Timezone::Utc => write!(f, "UTC"),
+            Timezone::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+impl TryFrom<String> for Timezone {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Timezone::parse(&name)
+    }
+}
+
+impl From<Timezone> for String {
+    fn from(timezone: Timezone) -> Self {
+        timezone.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(Timezone::parse("local").unwrap(), Timezone::Local);
+        assert_eq!(Timezone::parse("UTC").unwrap(), Timezone::Utc);
+        assert_eq!(
+            Timezone::parse("America/Los_Angeles").unwrap().to_string(),
+            "America/Los_Angeles"
+        );
+        assert!(Timezone::parse("Mars/Olympus_Mons").is_err());
+    }
+
+    #[test]
+    fn test_bucketing_in_named_zone() {
+        let tz = Timezone::parse("America/Los_Angeles").unwrap();
+        // 03:30 UTC on June 4th is 20:30 on June 3rd in Los Angeles (UTC-7)
+        let timestamp = Utc.with_ymd_and_hms(2024, 6, 4, 3, 30, 0).unwrap();
+        assert_eq!(tz.day_key(timestamp), "2024-06-03");
+        assert_eq!(tz.hour(timestamp), 20);
+        assert_eq!(tz.weekday(timestamp), Weekday::Mon);
+        assert_eq!(Timezone::Utc.day_key(timestamp), "2024-06-04");
+
+        let date = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        assert_eq!(
+            tz.start_of_day(date),
+            Utc.with_ymd_and_hms(2024, 6, 3, 7, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_to_utc_in_dst_gap() {
+        let tz = Timezone::parse("America/Los_Angeles").unwrap();
+        // 02:30 doesn't exist on 2024-03-10; clocks jump from 02:00 PST to 03:00 PDT
+        let naive = NaiveDate::from_ymd_opt(2024, 3, 10)
+            .unwrap()
+            .and_hms_opt(2, 30, 0)
+            .unwrap();
+        assert_eq!(
+            tz.to_utc(naive),
+            Utc.with_ymd_and_hms(2024, 3, 10, 10, 30, 0).unwrap()
+        );
+    }
+}
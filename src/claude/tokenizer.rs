@@ -0,0 +1,452 @@
+/// Characters that join the parts of a compound identifier or path
+const CONNECTORS: [char; 5] = ['_', '-', '.', '/', '\\'];
+
+/// Code-aware tokenizer shared by search indexing, highlighting and topic extraction
+///
+/// Splits camelCase, snake_case, kebab-case and path segments into their parts and,
+/// optionally, keeps the whole identifier as an extra token so that
+/// `parseConversationFile`, `parse_conversation_file` and `parse conversation file`
+/// all produce matching tokens.
+#[derive(Debug, Clone)]
+pub struct Tokenizer {
+    /// Emit the joined form of compound identifiers as an extra token
+    keep_identifiers: bool,
+    /// Apply English (Porter) stemming to identifier parts
+    stemming: bool,
+    /// Minimum length (in characters) of an emitted part
+    min_length: usize,
+}
+
+/// A token together with its byte range in the source text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    /// Normalized token text
+    pub text: String,
+    /// Byte offset where the token starts in the source text
+    pub start: usize,
+    /// Byte offset where the token ends in the source text
+    pub end: usize,
+}
+
+impl Tokenizer {
+    /// Create a tokenizer that keeps whole identifiers and does not stem
+    pub fn new() -> Self {
+        Self {
+            keep_identifiers: true,
+            stemming: false,
+            min_length: 3,
+        }
+    }
+
+    /// Enable or disable English stemming
+    pub fn with_stemming(mut self, stemming: bool) -> Self {
+        self.stemming = stemming;
+        self
+    }
+
+    /// Enable or disable emitting whole identifiers as extra tokens
+    pub fn with_identifiers(mut self, keep_identifiers: bool) -> Self {
+        self.keep_identifiers = keep_identifiers;
+        self
+    }
+
+    /// Set the minimum part length
+    pub fn with_min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self
+    }
+
+    /// Tokenize text into normalized tokens
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        self.tokens(text)
+            .into_iter()
+            .map(|token| token.text)
+            .collect()
+    }
+
+    /// Tokenize text, keeping the byte range of every token for highlighting
+    pub fn tokens(&self, text: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+
+        for (start, end) in Self::compound_ranges(text) {
+            let parts = Self::split_parts(text, start, end);
+
+            if self.keep_identifiers && parts.len() > 1 {
+                tokens.push(Token {
+                    text: self.normalize_identifier(&text[start..end]),
+                    start,
+                    end,
+                });
+            }
+
+            for (part_start, part_end) in parts {
+                let part = &text[part_start..part_end];
+                if part.chars().count() < self.min_length {
+                    continue;
+                }
+                tokens.push(Token {
+                    text: self.normalize(part),
+                    start: part_start,
+                    end: part_end,
+                });
+            }
+        }
+
+        tokens
+    }
+
+    /// Normalize a single word: lowercase and, if enabled, stem
+    pub fn normalize(&self, word: &str) -> String {
+        let lower = word.to_lowercase();
+        if self.stemming {
+            stem(&lower)
+        } else {
+            lower
+        }
+    }
+
+    /// Normalize a whole identifier the way its joined token is indexed, so
+    /// `parse_conversation_file` and `parseConversationFile` look up the same term
+    pub fn normalize_identifier(&self, identifier: &str) -> String {
+        let joined: String = identifier
+            .chars()
+            .filter(|ch| !CONNECTORS.contains(ch))
+            .collect();
+        self.normalize(&joined)
+    }
+
+    /// Find byte ranges of compound words (alphanumerics joined by connectors)
+    fn compound_ranges(text: &str) -> Vec<(usize, usize)> {
+        let mut ranges = Vec::new();
+        let mut current: Option<(usize, usize)> = None;
+
+        for (idx, ch) in text.char_indices() {
+            if ch.is_alphanumeric() {
+                let end = idx + ch.len_utf8();
+                current = match current {
+                    Some((start, _)) => Some((start, end)),
+                    None => Some((idx, end)),
+                };
+            } else if CONNECTORS.contains(&ch) && current.is_some() {
+                // Connector may continue the compound; only alphanumerics extend its end
+                continue;
+            } else if let Some(range) = current.take() {
+                ranges.push(range);
+            }
+        }
+
+        if let Some(range) = current {
+            ranges.push(range);
+        }
+
+        ranges
+    }
+
+    /// Split a compound word into parts at connectors and case boundaries
+    fn split_parts(text: &str, start: usize, end: usize) -> Vec<(usize, usize)> {
+        let mut parts = Vec::new();
+        let chars: Vec<(usize, char)> = text[start..end]
+            .char_indices()
+            .map(|(idx, ch)| (start + idx, ch))
+            .collect();
+
+        let mut part_start: Option<usize> = None;
+        for (i, &(idx, ch)) in chars.iter().enumerate() {
+            if !ch.is_alphanumeric() {
+                if let Some(s) = part_start.take() {
+                    parts.push((s, idx));
+                }
+                continue;
+            }
+
+            if let Some(s) = part_start {
+                let prev = chars[i - 1].1;
+                let next = chars.get(i + 1).map(|&(_, c)| c);
+                // fooBar -> foo|Bar, HTTPServer -> HTTP|Server
+                let camel_boundary = ch.is_uppercase()
+                    && (prev.is_lowercase()
+                        || prev.is_numeric()
+                        || (prev.is_uppercase() && next.is_some_and(|n| n.is_lowercase())));
+                if camel_boundary {
+                    parts.push((s, idx));
+                    part_start = Some(idx);
+                }
+            } else {
+                part_start = Some(idx);
+            }
+        }
+
+        if let Some(s) = part_start {
+            parts.push((s, end));
+        }
+
+        parts
+    }
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reduce an English word to its stem using the Porter algorithm
+///
+/// Words that are not plain ASCII letters, or are too short, are returned unchanged.
+pub fn stem(word: &str) -> String {
+    if word.len() <= 2 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        return word.to_string();
+    }
+
+    let mut w = word.as_bytes().to_vec();
+    step_1a(&mut w);
+    step_1b(&mut w);
+    step_1c(&mut w);
+    step_2(&mut w);
+    step_3(&mut w);
+    step_4(&mut w);
+    step_5(&mut w);
+
+    String::from_utf8(w).unwrap_or_else(|_| word.to_string())
+}
+
+fn is_consonant(w: &[u8], i: usize) -> bool {
+    match w[i] {
+        b'a' | b'e' | b'i' | b'o' | b'u' => false,
+        b'y' => i == 0 || !is_consonant(w, i - 1),
+        _ => true,
+    }
+}
+
+/// Number of vowel-consonant sequences in the stem
+fn measure(w: &[u8]) -> usize {
+    let mut count = 0;
+    let mut prev_vowel = false;
+    for i in 0..w.len() {
+        let consonant = is_consonant(w, i);
+        if consonant && prev_vowel {
+            count += 1;
+        }
+        prev_vowel = !consonant;
+    }
+    count
+}
+
+fn contains_vowel(w: &[u8]) -> bool {
+    (0..w.len()).any(|i| !is_consonant(w, i))
+}
+
+fn ends_double_consonant(w: &[u8]) -> bool {
+    let n = w.len();
+    n >= 2 && w[n - 1] == w[n - 2] && is_consonant(w, n - 1)
+}
+
+/// Stem ends consonant-vowel-consonant, where the final consonant is not w, x or y
+fn ends_cvc(w: &[u8]) -> bool {
+    let n = w.len();
+    n >= 3
+        && is_consonant(w, n - 3)
+        && !is_consonant(w, n - 2)
+        && is_consonant(w, n - 1)
+        && !matches!(w[n - 1], b'w' | b'x' | b'y')
+}
+
+/// Replace `suffix` with `replacement` if the remaining stem has measure above `min_measure`
+fn replace_suffix(w: &mut Vec<u8>, suffix: &str, replacement: &str, min_measure: usize) -> bool {
+    if !w.ends_with(suffix.as_bytes()) {
+        return false;
+    }
+    let stem_len = w.len() - suffix.len();
+    if measure(&w[..stem_len]) > min_measure {
+        w.truncate(stem_len);
+        w.extend_from_slice(replacement.as_bytes());
+    }
+    true
+}
+
+fn step_1a(w: &mut Vec<u8>) {
+    if w.ends_with(b"sses") || w.ends_with(b"ies") {
+        w.truncate(w.len() - 2);
+    } else if w.ends_with(b"s") && !w.ends_with(b"ss") {
+        w.pop();
+    }
+}
+
+fn step_1b(w: &mut Vec<u8>) {
+    if w.ends_with(b"eed") {
+        if measure(&w[..w.len() - 3]) > 0 {
+            w.pop();
+        }
+        return;
+    }
+
+    let suffix_len = if w.ends_with(b"ed") {
+        2
+    } else if w.ends_with(b"ing") {
+        3
+    } else {
+        return;
+    };
+
+    if !contains_vowel(&w[..w.len() - suffix_len]) {
+        return;
+    }
+    w.truncate(w.len() - suffix_len);
+
+    if w.ends_with(b"at") || w.ends_with(b"bl") || w.ends_with(b"iz") {
+        w.push(b'e');
+    } else if ends_double_consonant(w) && !matches!(w[w.len() - 1], b'l' | b's' | b'z') {
+        w.pop();
+    } else if measure(w) == 1 && ends_cvc(w) {
+        w.push(b'e');
+    }
+}
+
+fn step_1c(w: &mut [u8]) {
+    let n = w.len();
+    if n > 1 && w[n - 1] == b'y' && contains_vowel(&w[..n - 1]) {
+        w[n - 1] = b'i';
+    }
+}
+
+fn step_2(w: &mut Vec<u8>) {
+    const RULES: [(&str, &str); 20] = [
+        ("ational", "ate"),
+        ("tional", "tion"),
+        ("enci", "ence"),
+        ("anci", "ance"),
+        ("izer", "ize"),
+        ("abli", "able"),
+        ("alli", "al"),
+        ("entli", "ent"),
+        ("eli", "e"),
+        ("ousli", "ous"),
+        ("ization", "ize"),
+        ("ation", "ate"),
+        ("ator", "ate"),
+        ("alism", "al"),
+        ("iveness", "ive"),
+        ("fulness", "ful"),
+        ("ousness", "ous"),
+        ("aliti", "al"),
+        ("iviti", "ive"),
+        ("biliti", "ble"),
+    ];
+    for (suffix, replacement) in RULES {
+        if replace_suffix(w, suffix, replacement, 0) {
+            return;
+        }
+    }
+}
+
+fn step_3(w: &mut Vec<u8>) {
+    const RULES: [(&str, &str); 7] = [
+        ("icate", "ic"),
+        ("ative", ""),
+        ("alize", "al"),
+        ("iciti", "ic"),
+        ("ical", "ic"),
+        ("ful", ""),
+        ("ness", ""),
+    ];
+    for (suffix, replacement) in RULES {
+        if replace_suffix(w, suffix, replacement, 0) {
+            return;
+        }
+    }
+}
+
+fn step_4(w: &mut Vec<u8>) {
+    const SUFFIXES: [&str; 19] = [
+        "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ion",
+        "ou", "ism", "ate", "iti", "ous", "ive", "ize",
+    ];
+    for suffix in SUFFIXES {
+        if !w.ends_with(suffix.as_bytes()) {
+            continue;
+        }
+        let stem_len = w.len() - suffix.len();
+        if suffix == "ion" && !(stem_len > 0 && matches!(w[stem_len - 1], b's' | b't')) {
+            return;
+        }
+        if measure(&w[..stem_len]) > 1 {
+            w.truncate(stem_len);
+        }
+        return;
+    }
+}
+
+fn step_5(w: &mut Vec<u8>) {
+    if w.ends_with(b"e") {
+        let stem = &w[..w.len() - 1];
+        let m = measure(stem);
+        if m > 1 || (m == 1 && !ends_cvc(stem)) {
+            w.pop();
+        }
+    }
+    if w.ends_with(b"ll") && measure(w) > 1 {
+        w.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifier_styles_share_tokens() {
+        let tokenizer = Tokenizer::new();
+        let camel = tokenizer.tokenize("parseConversationFile");
+        let snake = tokenizer.tokenize("parse_conversation_file");
+        let words = tokenizer.tokenize("parse conversation file");
+
+        assert_eq!(camel, snake);
+        assert!(camel.contains(&"parseconversationfile".to_string()));
+        for part in ["parse", "conversation", "file"] {
+            assert!(words.contains(&part.to_string()));
+            assert!(camel.contains(&part.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_paths_and_acronyms() {
+        let tokenizer = Tokenizer::new().with_identifiers(false);
+        assert_eq!(
+            tokenizer.tokenize("see src/claude/search.rs."),
+            vec!["see", "src", "claude", "search"]
+        );
+        assert_eq!(tokenizer.tokenize("HTTPServer"), vec!["http", "server"]);
+    }
+
+    #[test]
+    fn test_token_spans() {
+        let tokenizer = Tokenizer::new();
+        let text = "call fooBar now";
+        let tokens = tokenizer.tokens(text);
+
+        let whole = tokens.iter().find(|t| t.text == "foobar").unwrap();
+        assert_eq!(&text[whole.start..whole.end], "fooBar");
+        let part = tokens.iter().find(|t| t.text == "bar").unwrap();
+        assert_eq!(&text[part.start..part.end], "Bar");
+    }
+
+    #[test]
+    fn test_stemming() {
+        assert_eq!(stem("handling"), "handl");
+        assert_eq!(stem("handled"), stem("handles"));
+        assert_eq!(stem("caresses"), "caress");
+        assert_eq!(stem("ponies"), "poni");
+        assert_eq!(stem("relational"), "relat");
+        assert_eq!(stem("hopeful"), "hope");
+
+        let tokenizer = Tokenizer::new().with_stemming(true);
+        assert_eq!(tokenizer.tokenize("parsing"), tokenizer.tokenize("parses"));
+
+        // The joined identifier is stemmed like a looked-up term
+        let joined = tokenizer.normalize_identifier("parse_conversation_files");
+        assert_eq!(joined, stem("parseconversationfiles"));
+        assert!(tokenizer
+            .tokenize("parseConversationFiles")
+            .contains(&joined));
+    }
+}
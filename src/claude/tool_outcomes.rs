@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::conversation::{Conversation, ToolResult, ToolUse};
+
+/// Calls with a known outcome a tool needs before it's ranked by reliability
+pub const MIN_RATED_CALLS: usize = 3;
+
+/// Text Claude Code puts in the result of a tool call the user declined
+const REJECTION_MARKER: &str = "doesn't want to proceed with this tool use";
+
+/// How a tool call ended, judged from its result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolOutcome {
+    Success,
+    /// The tool reported an error
+    Error,
+    /// The user declined the tool call
+    Rejected,
+    /// A shell command ran but exited with a non-zero status
+    NonZeroExit,
+}
+
+impl ToolOutcome {
+    /// Classify the result of a call to `tool_name`
+    pub fn from_result(tool_name: &str, result: &ToolResult) -> Self {
+        if result.content.contains(REJECTION_MARKER) {
+            Self::Rejected
+        } else if tool_name == "Bash" && nonzero_exit_code(&result.content) {
+            Self::NonZeroExit
+        } else if result.is_error {
+            Self::Error
+        } else {
+            Self::Success
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Error => "error",
+            Self::Rejected => "rejected",
+            Self::NonZeroExit => "non-zero exit",
+        }
+    }
+}
+
+/// Whether shell output reports an `Exit code N` line with N other than 0
+fn nonzero_exit_code(output: &str) -> bool {
+    output.lines().any(|line| {
+        line.trim()
+            .strip_prefix("Exit code ")
+            .and_then(|code| code.trim().parse::<i32>().ok())
+            .is_some_and(|code| code != 0)
+    })
+}
+
+/// Tally of tool call outcomes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolOutcomeCounts {
+    pub succeeded: usize,
+    pub errored: usize,
+    pub rejected: usize,
+    pub nonzero_exit: usize,
+}
+
+impl ToolOutcomeCounts {
+    pub fn record(&mut self, outcome: ToolOutcome) {
+        match outcome {
+            ToolOutcome::Success => self.succeeded += 1,
+            ToolOutcome::Error => self.errored += 1,
+            ToolOutcome::Rejected => self.rejected += 1,
+            ToolOutcome::NonZeroExit => self.nonzero_exit += 1,
+        }
+    }
+
+    pub fn add(&mut self, other: &ToolOutcomeCounts) {
+        self.succeeded += other.succeeded;
+        self.errored += other.errored;
+        self.rejected += other.rejected;
+        self.nonzero_exit += other.nonzero_exit;
+    }
+
+    /// Calls with a known outcome
+    pub fn total(&self) -> usize {
+        self.succeeded + self.failures()
+    }
+
+    pub fn failures(&self) -> usize {
+        self.errored + self.rejected + self.nonzero_exit
+    }
+
+    /// Percentage of calls that succeeded, if any call has a known outcome
+    pub fn success_rate(&self) -> Option<f64> {
+        let total = self.total();
+        (total > 0).then(|| self.succeeded as f64 / total as f64 * 100.0)
+    }
+
+    /// Failures by kind, e.g. `2 errors, 1 rejected`
+    pub fn failure_summary(&self) -> String {
+        let plural = |count: usize, noun: &str| {
+            format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
+        };
+        let mut parts = Vec::new();
+        if self.errored > 0 {
+            parts.push(plural(self.errored, "error"));
+        }
+        if self.rejected > 0 {
+            parts.push(format!("{} rejected", self.rejected));
+        }
+        if self.nonzero_exit > 0 {
+            parts.push(plural(self.nonzero_exit, "non-zero exit"));
+        }
+        if parts.is_empty() {
+            "no failures".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// A success rate as `87.5%`, or `n/a` when no call had a known outcome
+pub fn format_success_rate(rate: Option<f64>) -> String {
+    rate.map_or_else(|| "n/a".to_string(), |rate| format!("{:.1}%", rate))
+}
+
+/// Every tool call in a conversation that has a result, with its outcome
+pub fn tool_outcomes(conversation: &Conversation) -> Vec<(&ToolUse, ToolOutcome)> {
+    let results: HashMap<&str, &ToolResult> = conversation
+        .messages
+        .iter()
+        .flat_map(|m| &m.tool_results)
+        .map(|result| (result.tool_use_id.as_str(), result))
+        .collect();
+
+    conversation
+        .messages
+        .iter()
+        .flat_map(|m| &m.tool_uses)
+        .filter_map(|tool| {
+            results
+                .get(tool.id.as_str())
+                .map(|result| (tool, ToolOutcome::from_result(&tool.name, result)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(content: &str, is_error: bool) -> ToolResult {
+        ToolResult {
+            tool_use_id: "t1".to_string(),
+            content: content.to_string(),
+            is_error,
+        }
+    }
+
+    #[test]
+    fn test_classify_results() {
+        assert_eq!(
+            ToolOutcome::from_result("Read", &result("fn main() {}", false)),
+            ToolOutcome::Success
+        );
+        assert_eq!(
+            ToolOutcome::from_result("Edit", &result("File has not been read yet", true)),
+            ToolOutcome::Error
+        );
+        assert_eq!(
+            ToolOutcome::from_result(
+                "Edit",
+                &result(
+                    "The user doesn't want to proceed with this tool use. The tool use was rejected.",
+                    true
+                )
+            ),
+            ToolOutcome::Rejected
+        );
+        assert_eq!(
+            ToolOutcome::from_result("Bash", &result("Exit code 101\ntest failed", true)),
+            ToolOutcome::NonZeroExit
+        );
+        assert_eq!(
+            ToolOutcome::from_result("Bash", &result("Exit code 0\nok", false)),
+            ToolOutcome::Success
+        );
+        // Only shell output is checked for exit codes
+        assert_eq!(
+            ToolOutcome::from_result("Read", &result("Exit code 1", false)),
+            ToolOutcome::Success
+        );
+    }
+
+    #[test]
+    fn test_outcome_counts() {
+        let mut counts = ToolOutcomeCounts::default();
+        assert_eq!(counts.success_rate(), None);
+
+        for outcome in [
+            ToolOutcome::Success,
+            ToolOutcome::Success,
+            ToolOutcome::Success,
+            ToolOutcome::Rejected,
+        ] {
+            counts.record(outcome);
+        }
+        assert_eq!(counts.total(), 4);
+        assert_eq!(counts.failures(), 1);
+        assert_eq!(counts.success_rate(), Some(75.0));
+        assert_eq!(counts.failure_summary(), "1 rejected");
+
+        counts.record(ToolOutcome::NonZeroExit);
+        counts.record(ToolOutcome::NonZeroExit);
+        assert_eq!(counts.failure_summary(), "1 rejected, 2 non-zero exits");
+    }
+}
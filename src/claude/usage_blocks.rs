@@ -0,0 +1,228 @@
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use super::conversation::Conversation;
+use super::pricing::{billed_usage, message_cost};
+
+/// Length of a usage block; subscription limits are enforced over rolling
+/// windows of this many hours, starting with the first message in the window
+pub const BLOCK_HOURS: i64 = 5;
+
+/// Usage within one rolling window, starting at the hour of its first message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageBlock {
+    /// First message's timestamp, rounded down to the hour
+    pub start: DateTime<Utc>,
+    /// `BLOCK_HOURS` after the start
+    pub end: DateTime<Utc>,
+    pub first_activity: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    /// Assistant messages with usage
+    pub messages: usize,
+    /// Input, output and cache tokens
+    pub tokens: u64,
+    /// Estimated cost in USD
+    pub cost: f64,
+    pub models: BTreeSet<String>,
+}
+
+impl UsageBlock {
+    fn starting_at(timestamp: DateTime<Utc>) -> Self {
+        let start = timestamp
+            .duration_trunc(Duration::hours(1))
+            .unwrap_or(timestamp);
+        Self {
+            start,
+            end: start + Duration::hours(BLOCK_HOURS),
+            first_activity: timestamp,
+            last_activity: timestamp,
+            messages: 0,
+            tokens: 0,
+            cost: 0.0,
+            models: BTreeSet::new(),
+        }
+    }
+
+    /// Whether `now` falls inside the block's window
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.start <= now && now < self.end
+    }
+
+    /// Tokens per minute between the first and last message, once the block
+    /// has a minute of activity to measure
+    pub fn burn_rate(&self) -> Option<f64> {
+        let minutes = (self.last_activity - self.first_activity).num_seconds() as f64 / 60.0;
+        (minutes >= 1.0).then(|| self.tokens as f64 / minutes)
+    }
+
+    /// Where the block is heading at `now`, against an optional token limit
+    pub fn projection(&self, now: DateTime<Utc>, token_limit: Option<u64>) -> BlockProjection {
+        let remaining = if self.is_active(now) {
+            self.end - now
+        } else {
+            Duration::zero()
+        };
+        let burn_rate = self.burn_rate();
+        let projected_tokens = self.tokens
+            + burn_rate.map_or(0, |rate| {
+                (rate * remaining.num_seconds() as f64 / 60.0) as u64
+            });
+
+        let exhausted_at = token_limit.and_then(|limit| {
+            if self.tokens >= limit {
+                return Some(self.last_activity);
+            }
+            let minutes = (limit - self.tokens) as f64 / burn_rate?;
+            let at = now.max(self.last_activity) + Duration::seconds((minutes * 60.0) as i64);
+            (at < self.end).then_some(at)
+        });
+
+        BlockProjection {
+            remaining_minutes: remaining.num_minutes(),
+            burn_rate,
+            projected_tokens,
+            token_limit,
+            exhausted_at,
+        }
+    }
+}
+
+/// Burn rate and projected usage of a block
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BlockProjection {
+    /// Minutes until the block ends, 0 once it has
+    pub remaining_minutes: i64,
+    /// Tokens per minute so far
+    pub burn_rate: Option<f64>,
+    /// Tokens by the end of the block at the current burn rate
+    pub projected_tokens: u64,
+    pub token_limit: Option<u64>,
+    /// When the limit is or will be reached at the current burn rate, if
+    /// before the block ends
+    pub exhausted_at: Option<DateTime<Utc>>,
+}
+
+impl BlockProjection {
+    /// Share of the token limit used, if a limit is known
+    pub fn limit_ratio(&self, tokens: u64) -> Option<f64> {
+        self.token_limit
+            .filter(|limit| *limit > 0)
+            .map(|limit| tokens as f64 / limit as f64)
+    }
+}
+
+/// Split usage across conversations into blocks, oldest first. A block starts
+/// with the first message after the previous one ends
+pub fn usage_blocks<'a>(
+    conversations: impl IntoIterator<Item = &'a Conversation>,
+) -> Vec<UsageBlock> {
+    let mut messages: Vec<_> =
+        billed_usage(conversations.into_iter().flat_map(|conv| &conv.messages)).collect();
+    messages.sort_by_key(|(message, _)| message.timestamp);
+
+    let mut blocks: Vec<UsageBlock> = Vec::new();
+    for (message, usage) in messages {
+        let starts_block = blocks
+            .last()
+            .is_none_or(|block| message.timestamp >= block.end);
+        if starts_block {
+            blocks.push(UsageBlock::starting_at(message.timestamp));
+        }
+        let Some(block) = blocks.last_mut() else {
+            continue;
+        };
+
+        block.last_activity = message.timestamp;
+        block.messages += 1;
+        block.tokens += usage.total_tokens();
+        block.cost += message_cost(message, usage);
+        if let Some(model) = &message.model {
+            block.models.insert(model.clone());
+        }
+    }
+    blocks
+}
+
+/// The most tokens used in any block that has ended, as a stand-in for the
+/// subscription limit when none is configured
+pub fn largest_completed_block(blocks: &[UsageBlock], now: DateTime<Utc>) -> Option<u64> {
+    blocks
+        .iter()
+        .filter(|block| block.end <= now)
+        .map(|block| block.tokens)
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::conversation::ConversationMessage;
+    use crate::claude::test_support::usage;
+    use chrono::TimeZone;
+
+    fn conversation(session_id: &str, messages: &[(DateTime<Utc>, u32)]) -> Conversation {
+        let messages = messages
+            .iter()
+            .map(|&(timestamp, tokens)| {
+                ConversationMessage::assistant(timestamp)
+                    .with_model("claude-sonnet-4")
+                    .with_usage(usage(tokens, 0))
+            })
+            .collect();
+        Conversation::new_for_test(session_id, "-repo", messages)
+    }
+
+    #[test]
+    fn test_usage_blocks() {
+        let at =
+            |hour: u32, minute: u32| Utc.with_ymd_and_hms(2024, 6, 1, hour, minute, 0).unwrap();
+        let conversations = [
+            conversation("s1", &[(at(9, 30), 1_000), (at(10, 30), 2_000)]),
+            // Past the first block's 14:00 end, so starts a new block at 14:00
+            conversation("s2", &[(at(14, 10), 500), (at(14, 40), 3_000)]),
+        ];
+
+        let blocks = usage_blocks(&conversations);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].start, at(9, 0));
+        assert_eq!(blocks[0].end, at(14, 0));
+        assert_eq!(blocks[0].tokens, 3_000);
+        assert_eq!(blocks[0].messages, 2);
+        assert_eq!(blocks[1].start, at(14, 0));
+        assert_eq!(blocks[1].first_activity, at(14, 10));
+        assert_eq!(blocks[1].tokens, 3_500);
+
+        assert_eq!(largest_completed_block(&blocks, at(15, 0)), Some(3_000));
+        assert_eq!(largest_completed_block(&blocks, at(12, 0)), None);
+    }
+
+    #[test]
+    fn test_block_projection() {
+        let at =
+            |hour: u32, minute: u32| Utc.with_ymd_and_hms(2024, 6, 1, hour, minute, 0).unwrap();
+        let blocks = usage_blocks(&[conversation(
+            "s1",
+            &[(at(10, 0), 1_000), (at(10, 30), 2_000)],
+        )]);
+        let block = &blocks[0];
+        assert!(block.is_active(at(11, 0)));
+        assert!(!block.is_active(at(15, 0)));
+        assert_eq!(block.burn_rate(), Some(100.0));
+
+        // 4 hours left at 100 tokens a minute
+        let projection = block.projection(at(11, 0), Some(10_000));
+        assert_eq!(projection.remaining_minutes, 240);
+        assert_eq!(projection.projected_tokens, 27_000);
+        assert_eq!(projection.exhausted_at, Some(at(12, 10)));
+        assert_eq!(projection.limit_ratio(block.tokens), Some(0.3));
+
+        // Never reached before the block ends
+        assert_eq!(block.projection(at(11, 0), Some(50_000)).exhausted_at, None);
+        // Already over the limit
+        assert_eq!(
+            block.projection(at(11, 0), Some(2_000)).exhausted_at,
+            Some(at(10, 30))
+        );
+    }
+}
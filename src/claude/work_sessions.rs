@@ -0,0 +1,376 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::conversation::Conversation;
+use super::timezone::Timezone;
+
+/// Default gap between messages after which a work session is considered over
+pub const DEFAULT_IDLE_THRESHOLD_MINUTES: i64 = 30;
+
+/// A stretch of continuous activity: consecutive messages, across any number
+/// of conversations, with no gap longer than the idle threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkSession {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Projects with activity in this work session
+    pub projects: Vec<String>,
+    /// Conversations with activity in this work session
+    pub session_ids: Vec<String>,
+    pub message_count: usize,
+}
+
+impl WorkSession {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    /// This work session cut to `start..=end`, if they overlap
+    pub fn clipped(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<WorkSession> {
+        if self.end < start || self.start > end {
+            return None;
+        }
+        Some(WorkSession {
+            start: self.start.max(start),
+            end: self.end.min(end),
+            ..self.clone()
+        })
+    }
+}
+
+/// Split all message activity into work sessions, oldest first
+pub fn detect_work_sessions<'a>(
+    conversations: impl IntoIterator<Item = &'a Conversation>,
+    idle_threshold: Duration,
+) -> Vec<WorkSession> {
+    let mut activity: Vec<(DateTime<Utc>, &Conversation)> = conversations
+        .into_iter()
+        .flat_map(|conv| conv.messages.iter().map(move |m| (m.timestamp, conv)))
+        .collect();
+    activity.sort_by_key(|(timestamp, _)| *timestamp);
+
+    let mut sessions = Vec::new();
+    let mut current: Option<(WorkSession, BTreeSet<String>, BTreeSet<String>)> = None;
+
+    for (timestamp, conv) in activity {
+        if let Some((session, projects, session_ids)) = current.as_mut() {
+            if timestamp - session.end <= idle_threshold {
+                session.end = timestamp;
+                session.message_count += 1;
+                projects.insert(conv.project_path.clone());
+                session_ids.insert(conv.session_id.clone());
+                continue;
+            }
+        }
+        if let Some(finished) = current.take() {
+            sessions.push(finish_session(finished));
+        }
+        current = Some((
+            WorkSession {
+                start: timestamp,
+                end: timestamp,
+                projects: Vec::new(),
+                session_ids: Vec::new(),
+                message_count: 1,
+            },
+            BTreeSet::from([conv.project_path.clone()]),
+            BTreeSet::from([conv.session_id.clone()]),
+        ));
+    }
+    sessions.extend(current.map(finish_session));
+    sessions
+}
+
+fn finish_session(
+    (mut session, projects, session_ids): (WorkSession, BTreeSet<String>, BTreeSet<String>),
+) -> WorkSession {
+    session.projects = projects.into_iter().collect();
+    session.session_ids = session_ids.into_iter().collect();
+    session
+}
+
+/// A work session on a single project
+#[derive(Debug, Clone)]
+pub struct ProjectWorkSession<'a> {
+    pub project_path: &'a str,
+    /// The project's conversations with activity in the work session
+    pub conversations: Vec<&'a Conversation>,
+    /// Start of the work session before it was clipped to the range
+    pub unclipped_start: DateTime<Utc>,
+    pub session: WorkSession,
+}
+
+/// Detect work sessions per project, so time spent on two projects in
+/// parallel counts towards both, optionally clipped to `range`. Ordered by
+/// project, then start
+pub fn project_work_sessions(
+    conversations: &[Conversation],
+    idle_threshold: Duration,
+    range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+) -> Vec<ProjectWorkSession<'_>> {
+    let mut by_project: BTreeMap<&str, Vec<&Conversation>> = BTreeMap::new();
+    for conv in conversations {
+        by_project
+            .entry(conv.project_path.as_str())
+            .or_default()
+            .push(conv);
+    }
+
+    let mut sessions = Vec::new();
+    for (project_path, project_conversations) in by_project {
+        for session in detect_work_sessions(project_conversations.iter().copied(), idle_threshold) {
+            let unclipped_start = session.start;
+            let session = match range {
+                Some((start, end)) => match session.clipped(start, end) {
+                    Some(session) => session,
+                    None => continue,
+                },
+                None => session,
+            };
+            let conversations = project_conversations
+                .iter()
+                .copied()
+                .filter(|conv| session.session_ids.contains(&conv.session_id))
+                .collect();
+            sessions.push(ProjectWorkSession {
+                project_path,
+                conversations,
+                unclipped_start,
+                session,
+            });
+        }
+    }
+    sessions
+}
+
+/// How timesheet rows are bucketed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimesheetGrouping {
+    Day,
+    Week,
+}
+
+impl TimesheetGrouping {
+    /// Bucket label for a date: `2024-06-03` by day, `2024-W23` by week
+    pub fn period_label(&self, date: NaiveDate) -> String {
+        match self {
+            TimesheetGrouping::Day => date.format("%Y-%m-%d").to_string(),
+            TimesheetGrouping::Week => {
+                let week = date.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+        }
+    }
+}
+
+/// Active time on one project in one day or week
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimesheetEntry {
+    pub period: String,
+    pub project: String,
+    pub active_seconds: i64,
+    /// Work sessions that overlapped the period
+    pub work_sessions: usize,
+}
+
+/// Active time per project, bucketed by day or ISO week in a time zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timesheet {
+    pub grouping: TimesheetGrouping,
+    pub idle_threshold_minutes: i64,
+    /// Entries ordered by period, then project
+    pub entries: Vec<TimesheetEntry>,
+}
+
+impl Timesheet {
+    /// Build a timesheet from per-project work sessions, optionally limited
+    /// to activity within `range`
+    pub fn build(
+        conversations: &[Conversation],
+        idle_threshold: Duration,
+        grouping: TimesheetGrouping,
+        range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+        tz: &Timezone,
+    ) -> Self {
+        let mut buckets: BTreeMap<(String, String), (i64, usize)> = BTreeMap::new();
+        for ProjectWorkSession {
+            project_path,
+            session,
+            ..
+        } in project_work_sessions(conversations, idle_threshold, range)
+        {
+            let mut periods = BTreeMap::new();
+            for (date, seconds) in split_by_day(session.start, session.end, tz) {
+                *periods.entry(grouping.period_label(date)).or_insert(0) += seconds;
+            }
+            for (period, seconds) in periods {
+                let bucket = buckets
+                    .entry((period, project_path.to_string()))
+                    .or_insert((0, 0));
+                bucket.0 += seconds;
+                bucket.1 += 1;
+            }
+        }
+
+        let entries = buckets
+            .into_iter()
+            .map(
+                |((period, project), (active_seconds, work_sessions))| TimesheetEntry {
+                    period,
+                    project,
+                    active_seconds,
+                    work_sessions,
+                },
+            )
+            .collect();
+
+        Self {
+            grouping,
+            idle_threshold_minutes: idle_threshold.num_minutes(),
+            entries,
+        }
+    }
+
+    pub fn total_seconds(&self) -> i64 {
+        self.entries.iter().map(|entry| entry.active_seconds).sum()
+    }
+
+    /// Active seconds per project, busiest first
+    pub fn project_totals(&self) -> Vec<(String, i64)> {
+        let mut totals: BTreeMap<&str, i64> = BTreeMap::new();
+        for entry in &self.entries {
+            *totals.entry(entry.project.as_str()).or_insert(0) += entry.active_seconds;
+        }
+        let mut totals: Vec<(String, i64)> = totals
+            .into_iter()
+            .map(|(project, seconds)| (project.to_string(), seconds))
+            .collect();
+        totals.sort_by_key(|(_, seconds)| std::cmp::Reverse(*seconds));
+        totals
+    }
+}
+
+/// Seconds of `start..end` falling on each day in `tz`
+fn split_by_day(start: DateTime<Utc>, end: DateTime<Utc>, tz: &Timezone) -> Vec<(NaiveDate, i64)> {
+    let mut pieces = Vec::new();
+    let mut cursor = start;
+    loop {
+        let date = tz.date(cursor);
+        let next_day = tz.start_of_day(date + Duration::days(1));
+        let piece_end = end.min(next_day);
+        pieces.push((date, (piece_end - cursor).num_seconds()));
+        if piece_end >= end {
+            return pieces;
+        }
+        cursor = piece_end;
+    }
+}
+
+/// Format a number of seconds as e.g. `2h 05m` or `12m`
+pub fn format_active_time(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::conversation::ConversationMessage;
+    use chrono::TimeZone;
+
+    fn conversation(session_id: &str, project: &str, times: &[(u32, u32, u32)]) -> Conversation {
+        let messages = times
+            .iter()
+            .map(|&(day, hour, minute)| {
+                ConversationMessage::user(
+                    Utc.with_ymd_and_hms(2024, 6, day, hour, minute, 0).unwrap(),
+                )
+            })
+            .collect();
+        Conversation::new_for_test(session_id, project, messages)
+    }
+
+    #[test]
+    fn test_detect_work_sessions_splits_on_idle_gaps() {
+        // One conversation left open overnight, and a second one interleaved
+        let conversations = vec![
+            conversation("a", "-repo", &[(3, 9, 0), (3, 9, 20), (4, 10, 0)]),
+            conversation("b", "-other", &[(3, 9, 40), (4, 10, 10)]),
+        ];
+        let sessions = detect_work_sessions(&conversations, Duration::minutes(30));
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].duration(), Duration::minutes(40));
+        assert_eq!(sessions[0].session_ids, vec!["a", "b"]);
+        assert_eq!(sessions[0].message_count, 3);
+        assert_eq!(sessions[1].duration(), Duration::minutes(10));
+        assert_eq!(sessions[1].projects, vec!["-other", "-repo"]);
+    }
+
+    #[test]
+    fn test_timesheet_by_day_and_week() {
+        let conversations = vec![
+            conversation("a", "-repo", &[(3, 23, 30), (4, 0, 15), (4, 9, 0)]),
+            conversation("b", "-other", &[(4, 9, 0), (4, 9, 50)]),
+        ];
+
+        let daily = Timesheet::build(
+            &conversations,
+            Duration::minutes(60),
+            TimesheetGrouping::Day,
+            None,
+            &Timezone::Utc,
+        );
+        let rows: Vec<(&str, &str, i64)> = daily
+            .entries
+            .iter()
+            .map(|e| (e.period.as_str(), e.project.as_str(), e.active_seconds / 60))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("2024-06-03", "-repo", 30),
+                ("2024-06-04", "-other", 50),
+                ("2024-06-04", "-repo", 15),
+            ]
+        );
+        assert_eq!(daily.total_seconds(), 95 * 60);
+        assert_eq!(daily.project_totals()[0], ("-other".to_string(), 50 * 60));
+
+        let weekly = Timesheet::build(
+            &conversations,
+            Duration::minutes(60),
+            TimesheetGrouping::Week,
+            None,
+            &Timezone::Utc,
+        );
+        assert_eq!(weekly.entries.len(), 2);
+        assert_eq!(weekly.entries[1].period, "2024-W23");
+        assert_eq!(weekly.entries[1].active_seconds, 45 * 60);
+        assert_eq!(weekly.entries[1].work_sessions, 2);
+
+        let start = Utc.with_ymd_and_hms(2024, 6, 4, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2024, 6, 4, 23, 59, 59).unwrap();
+        let clipped = Timesheet::build(
+            &conversations,
+            Duration::minutes(60),
+            TimesheetGrouping::Day,
+            Some((start, end)),
+            &Timezone::Utc,
+        );
+        assert_eq!(clipped.total_seconds(), 65 * 60);
+    }
+
+    #[test]
+    fn test_format_active_time() {
+        assert_eq!(format_active_time(59), "0m");
+        assert_eq!(format_active_time(12 * 60), "12m");
+        assert_eq!(format_active_time(125 * 60), "2h 05m");
+    }
+}
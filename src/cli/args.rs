@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -13,6 +13,7 @@ QUICK START:
     claude-tools list --detailed       # List all conversations with details
     claude-tools search \"rust code\"    # Search for conversations containing 'rust code'
     claude-tools show <id>             # Show specific conversation content
+    claude-tools files src/main.rs     # Sessions that touched a file
 
 EXAMPLES:
     # Interactive browsing with search and navigation
@@ -43,6 +44,11 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub verbose: bool,
 
+    /// Time zone for days and hours: local (default), UTC or an IANA name.
+    /// Overrides the `timezone` config setting
+    #[arg(long, global = true, value_name = "ZONE")]
+    pub tz: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -59,7 +65,8 @@ EXAMPLES:
     claude-tools list --detailed          # Show detailed conversation info
     claude-tools list --since 7           # Show conversations from last 7 days
     claude-tools list --project \"my-app\"   # Filter by project path
-    claude-tools list --since 3 --detailed # Recent detailed conversations"
+    claude-tools list --since 3 --detailed # Recent detailed conversations
+    claude-tools list --collection auth    # Conversations in a collection"
     )]
     List {
         /// Show only recent conversations (last N days)
@@ -81,6 +88,10 @@ EXAMPLES:
         /// Show detailed information including message counts and timestamps
         #[arg(short, long)]
         detailed: bool,
+
+        /// Only list conversations in this collection
+        #[arg(long, value_name = "NAME")]
+        collection: Option<String>,
     },
 
     /// Show detailed conversation content
@@ -95,13 +106,17 @@ EXAMPLES:
     claude-tools show abc --role user          # Show only user messages
     claude-tools show abc --role assistant     # Show only assistant responses
     claude-tools show abc --export markdown --output conversation.md  # Export to file
+    claude-tools show --collection auth --export html --output exports/  # Export a collection
 
 The conversation ID can be a full ID or a unique prefix. Use 'list' command to find IDs."
     )]
     Show {
         /// Conversation ID or partial ID (use 'list' to find IDs)
-        #[arg(help = "Conversation ID or unique prefix (e.g., 'abc123' or just 'abc')")]
-        conversation_id: String,
+        #[arg(
+            help = "Conversation ID or unique prefix (e.g., 'abc123' or just 'abc')",
+            required_unless_present = "collection"
+        )]
+        conversation_id: Option<String>,
 
         /// Output format: human (default), json, markdown, or text
         #[arg(long, value_enum, default_value = "human")]
@@ -130,6 +145,15 @@ The conversation ID can be a full ID or a unique prefix. Use 'list' command to f
         /// Include timestamps
         #[arg(long)]
         include_timestamps: bool,
+
+        /// Export every conversation in this collection (--output is a directory)
+        #[arg(
+            long,
+            value_name = "NAME",
+            conflicts_with = "conversation_id",
+            requires = "export"
+        )]
+        collection: Option<String>,
     },
 
     /// Search conversations
@@ -143,83 +167,88 @@ EXAMPLES:
     claude-tools search \"(rust OR python) AND error\"    # Boolean search
     claude-tools search --ignore-case \"ERROR\"           # Case-insensitive search
     claude-tools search \"function\" --context 2          # Show 2 lines of context
+    claude-tools search \"function\" --max-hits 0         # Every matching message, not just 3
     claude-tools search \"async\" --model claude-3        # Filter by model
     claude-tools search \"debug\" --tool bash --after \"2024-01-01\"
+    claude-tools search \"cargo nextest\" --in tools   # Search shell commands and file paths
+    claude-tools search \"E0382\" --in results         # Search tool output
+    claude-tools search \"deploy\" --format jsonl        # One JSON record per result
+    claude-tools search \"deploy\" -l                    # Print matching session file paths
+    claude-tools search \"deploy\" --facets              # Counts by project, model, tool, role, month
+    claude-tools search \"deploy\" --project api --tool Bash   # Narrow by facet values
+    claude-tools search \"deploy\" --explain             # Show how each score was computed
+    claude-tools search \"deploy\" --tool Bash --save deploys   # Save query and filters
+    claude-tools search --run deploys                  # Re-run a saved search
+    claude-tools search --saved                        # List saved searches
+    claude-tools search --history                      # Recent searches
+
+Ranking weights (recency boost, length boost, term weight) can be tuned in the
+\"search\" section of ~/.claude/claude-tools-config.json. Words are stemmed, so
+\"parsing\" also finds \"parses\"; set \"stemming\": false there to match exact forms.
 
 TIP: Use the interactive mode (claude-tools interactive) for real-time search
      with visual highlighting and navigation between results."
     )]
-    Search {
-        /// Search query (supports text patterns, boolean logic, or regex with --regex)
-        #[arg(
-            help = "Text to search for in conversation content. Supports boolean operators (AND, OR, NOT) and parentheses"
-        )]
-        query: String,
+    Search(SearchOptions),
 
-        /// Use regular expressions for pattern matching
-        #[arg(
-            short,
-            long,
-            help = "Enable regex pattern matching (e.g., 'error.*handling')"
-        )]
-        regex: bool,
-
-        /// Case insensitive search (ignore letter case)
-        #[arg(short, long)]
-        ignore_case: bool,
+    /// Find conversations similar to a given one
+    #[command(
+        alias = "more-like-this",
+        long_about = "Find conversations that cover similar ground, ranked by TF-IDF cosine similarity.
 
-        /// Show context around matches (number of lines before/after)
-        #[arg(short = 'C', long, default_value = "0")]
-        context: usize,
+EXAMPLES:
+    claude-tools similar abc123                       # Conversations similar to abc123
+    claude-tools similar abc123 --limit 5            # Only the top 5
+    claude-tools similar --text \"tokio deadlock\"      # Conversations similar to free text
 
-        /// Filter by model used in conversation
-        #[arg(long, help = "Only search conversations that used this model")]
-        model: Option<String>,
+Similarity is computed over message text, tool inputs and tool results using
+the same code-aware tokenizer as search."
+    )]
+    Similar {
+        /// Conversation ID to find similar conversations for
+        #[arg(required_unless_present = "text", conflicts_with = "text")]
+        conversation_id: Option<String>,
 
-        /// Filter by tool used in conversation
-        #[arg(long, help = "Only search conversations that used this tool")]
-        tool: Option<String>,
+        /// Find conversations similar to this text instead of a conversation
+        #[arg(long)]
+        text: Option<String>,
 
-        /// Filter by message role
-        #[arg(long, value_enum, help = "Only search messages from this role")]
-        role: Option<MessageRole>,
+        /// Maximum number of similar conversations to show
+        #[arg(long, default_value = "10")]
+        limit: usize,
+    },
 
-        /// Search conversations after this date (YYYY-MM-DD or relative like '7 days ago')
-        #[arg(long, help = "Only search conversations after this date")]
-        after: Option<String>,
+    /// Find conversations that touched a file
+    #[command(
+        long_about = "List the sessions that read, edited or wrote a file, with timestamps and operation types.
 
-        /// Search conversations before this date (YYYY-MM-DD or relative like '1 week ago')
-        #[arg(long, help = "Only search conversations before this date")]
-        before: Option<String>,
+Paths come from Read, Edit, MultiEdit, Write and NotebookEdit tool calls, plus
+file paths found in Bash commands, resolved against the session working directory.
 
-        /// Filter by minimum number of messages
-        #[arg(
-            long,
-            help = "Only search conversations with at least this many messages"
-        )]
-        min_messages: Option<usize>,
+A relative path matches any file ending with it; '*' and '?' match within a path
+component and '**' matches across components.
 
-        /// Filter by maximum number of messages
-        #[arg(
-            long,
-            help = "Only search conversations with at most this many messages"
-        )]
-        max_messages: Option<usize>,
+EXAMPLES:
+    claude-tools files src/ui/app.rs                 # Sessions that touched app.rs
+    claude-tools files src/ui/app.rs --modified      # Only sessions that changed it
+    claude-tools files 'src/**/*.rs' --format tsv    # Every Rust file under src/
+    claude-tools files /home/me/proj/Cargo.toml      # An exact absolute path"
+    )]
+    Files {
+        /// File path or glob pattern
+        pattern: String,
 
-        /// Filter by minimum conversation duration in minutes
-        #[arg(
-            long,
-            help = "Only search conversations that lasted at least this long"
-        )]
-        min_duration: Option<u32>,
+        /// Only count edits and writes, not reads or Bash mentions
+        #[arg(long)]
+        modified: bool,
 
-        /// Filter by maximum conversation duration in minutes
-        #[arg(long, help = "Only search conversations that lasted at most this long")]
-        max_duration: Option<u32>,
+        /// Filter by project path
+        #[arg(long)]
+        project: Option<String>,
 
-        /// Maximum number of results to return
-        #[arg(long, default_value = "50")]
-        limit: usize,
+        /// Output format
+        #[arg(long, value_enum, default_value = "human")]
+        format: SearchOutputFormat,
     },
 
     /// Show conversation statistics
@@ -231,26 +260,171 @@ EXAMPLES:
     claude-tools stats                    # Quick overview of conversation history
     claude-tools stats --global          # Detailed global statistics
     claude-tools stats abc123            # Statistics for specific conversation
+    claude-tools stats --collection auth # Analytics for a collection
+    claude-tools stats commands          # Shell commands run through Bash
 
 Statistics include message counts by role, model usage, conversation length
-distribution, and temporal activity patterns."
+distribution, and temporal activity patterns.",
+        args_conflicts_with_subcommands = true
     )]
-    Stats {
-        /// Show statistics for specific conversation (optional)
-        #[arg(help = "Conversation ID to analyze (omit for global stats)")]
-        conversation_id: Option<String>,
+    Stats(StatsOptions),
 
-        /// Show comprehensive global statistics and analytics
+    /// Calendar heatmap of activity
+    #[command(
+        long_about = "Show a GitHub-style calendar heatmap of activity, one cell per day.
+
+Days are shaded by conversations, messages, tokens or estimated cost. Without
+--year the heatmap covers the last 52 weeks.
+
+EXAMPLES:
+    claude-tools heatmap                       # Messages over the last year
+    claude-tools heatmap --metric tokens       # Shade by token usage
+    claude-tools heatmap --year 2024           # A calendar year
+    claude-tools heatmap --svg activity.svg    # Write an SVG for reports
+
+TIP: In interactive mode press 'H' in the analytics or timeline dashboard to
+     select a day and list its sessions."
+    )]
+    Heatmap {
+        /// What to shade each day by
+        #[arg(long, value_enum, default_value = "messages")]
+        metric: HeatmapMetricArg,
+
+        /// Show a calendar year instead of the last 52 weeks
         #[arg(long)]
-        global: bool,
+        year: Option<i32>,
 
-        /// Export analytics data to file (csv, json)
-        #[arg(long, value_enum)]
-        export: Option<ExportFormat>,
+        /// Write the heatmap as SVG to this file
+        #[arg(long, value_name = "FILE")]
+        svg: Option<PathBuf>,
+    },
+
+    /// Time spent per project, from detected work sessions
+    #[command(long_about = "Report active time per project per day or week.
+
+Activity is split into work sessions wherever messages are more than the idle
+threshold apart (30 minutes by default, configurable as
+work_sessions.idle_threshold_minutes), so conversations left open overnight
+don't count as work. Sessions are detected per project, and time is bucketed
+by day or ISO week in the configured time zone (see --tz).
+
+EXAMPLES:
+    claude-tools timesheet                          # All time, by day
+    claude-tools timesheet --by week                # Weekly totals
+    claude-tools timesheet --period last-week       # The previous calendar week
+    claude-tools timesheet --from 2024-06-01 --to 2024-06-30 --format csv
+    claude-tools timesheet --idle-minutes 15 --format json")]
+    Timesheet(TimesheetOptions),
+
+    /// Markdown standup digest of recent activity
+    #[command(
+        long_about = "Generate a Markdown report of recent activity, one section per project.
 
-        /// Show detailed analytics dashboard
+Each project lists its conversations, top topics, files touched, shell commands
+run, failed tool calls and todo items that are still open, ready to paste into
+a standup.
+
+EXAMPLES:
+    claude-tools digest                        # The last 24 hours
+    claude-tools digest --period week          # The last 7 days
+    claude-tools digest --output standup.md    # Write to a file"
+    )]
+    Digest {
+        /// How far back the digest looks
+        #[arg(long, value_enum, default_value = "day")]
+        period: DigestPeriod,
+
+        /// Only include projects whose path contains this text
         #[arg(long)]
-        detailed: bool,
+        project: Option<String>,
+
+        /// Write the digest to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Git commits produced with Claude's help
+    #[command(
+        long_about = "Link git commits to the conversations that produced them.
+
+For every project whose working directory is a git repository, the commit log
+is read and each commit is linked to conversations that were active in the
+window before it, and to conversations that edited the committed files since
+they were last committed.
+
+EXAMPLES:
+    claude-tools commits                          # All linked commits
+    claude-tools commits --session abc123         # Commits produced by one session
+    claude-tools commits --since \"1 week ago\"     # Recent commits only
+    claude-tools commits --window-minutes 15      # Tighter activity window"
+    )]
+    Commits(CommitsOptions),
+
+    /// Usage in rolling 5-hour blocks
+    #[command(
+        long_about = "Show token usage in the rolling 5-hour blocks that subscription limits apply to.
+
+A block starts at the hour of the first message after the previous block ends,
+and lasts 5 hours. For the active block the burn rate so far is projected to
+the end of the block and, against a token limit, to when the limit runs out.
+Without --token-limit the largest earlier block is used as the limit.
+
+EXAMPLES:
+    claude-tools blocks                        # Recent blocks and the active one
+    claude-tools blocks --active               # Only the active block
+    claude-tools blocks --token-limit 5000000  # Project against a known limit
+    claude-tools blocks --format json          # Machine-readable blocks"
+    )]
+    Blocks {
+        /// Only show the active block
+        #[arg(long)]
+        active: bool,
+
+        /// Number of most recent blocks to list
+        #[arg(long, default_value = "10")]
+        recent: usize,
+
+        /// Tokens allowed per block (default: the largest completed block)
+        #[arg(long, value_name = "TOKENS")]
+        token_limit: Option<u64>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "human")]
+        format: BlocksFormat,
+    },
+
+    /// Check usage against configured budgets
+    #[command(
+        long_about = "Report token and estimated cost spend against the budgets in the config file.
+
+Budgets are listed under \"budgets\" in ~/.claude/claude-tools-config.json or
+a project's .claude-tools.json. Each has a metric (tokens or cost), a period
+(day, week, month or total), a limit and optionally a project filter and name:
+
+    \"budgets\": [
+      { \"metric\": \"cost\", \"period\": \"day\", \"limit\": 20.0 },
+      { \"name\": \"api\", \"metric\": \"tokens\", \"period\": \"week\",
+        \"project\": \"api\", \"limit\": 50000000 }
+    ]
+
+Periods start at midnight, Monday or the first of the month in the configured
+time zone (see --tz). The command only reads local files, and exits with code 3
+when any budget is exceeded, so it can run from cron or a Claude Code hook
+(code 1 means it failed and code 2 that its arguments were invalid).
+
+EXAMPLES:
+    claude-tools budget                  # Spend against every budget
+    claude-tools budget --quiet          # Only print exceeded budgets
+    claude-tools budget --format json    # Machine-readable report"
+    )]
+    Budget {
+        /// Only print budgets that are over their limit
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "human")]
+        format: BudgetFormat,
     },
 
     /// Interactive browse mode
@@ -286,7 +460,7 @@ KEYBOARD SHORTCUTS:
 
 The timeline dashboard provides:
 • Project-level activity summaries with ranking indicators
-• Time period filtering (24h, 48h, 1 week, 1 month)
+• Time period filtering (24h, 48h, rolling 7d/30d, calendar weeks/months/quarters, custom ranges)
 • Message frequency analysis and visual activity bars  
 • Tool usage tracking and top tools by project
 • Topic extraction and topical summaries
@@ -295,43 +469,26 @@ The timeline dashboard provides:
 EXAMPLES:
     claude-tools timeline                    # Show default timeline (48h)
     claude-tools timeline --period day      # Last 24 hours
-    claude-tools timeline --period week     # Last 7 days
+    claude-tools timeline --period 7d       # Rolling last 7 days
+    claude-tools timeline --period last-month       # The previous calendar month
+    claude-tools timeline --week 2024-W23           # A specific ISO week
+    claude-tools timeline --quarter 2024-Q2         # A specific quarter
+    claude-tools timeline --from 2024-06-01 --to 2024-06-15  # Custom range
+    claude-tools timeline --from \"3 days ago\"      # From a relative date until now
     claude-tools timeline --detailed        # Comprehensive view
     claude-tools timeline --export json     # Export timeline data
+    claude-tools timeline --period 30d --export ics     # Work sessions as calendar events
     claude-tools timeline --format markdown # Timeline in markdown
+    claude-tools timeline --commits         # Count agent-assisted git commits
+    claude-tools timeline --period last-week --compare previous  # Week over week
+
+7d and 30d are rolling windows ending now; this-week, last-week, this-month
+and last-month follow calendar weeks (Monday to Sunday) and months.
 
 TIP: Use the interactive mode (claude-tools interactive) and press 't' 
      for a full terminal UI with navigation and real-time filtering."
     )]
-    Timeline {
-        /// Time period to analyze
-        #[arg(long, value_enum, default_value = "two-day")]
-        period: TimelinePeriod,
-
-        /// Show detailed project breakdowns and statistics  
-        #[arg(short, long)]
-        detailed: bool,
-
-        /// Output format for timeline display
-        #[arg(long, value_enum, default_value = "human")]
-        format: OutputFormat,
-
-        /// Export timeline data to file
-        #[arg(long, value_enum)]
-        export: Option<ExportFormat>,
-
-        /// Output file path (use with --export)
-        #[arg(long, value_name = "FILE")]
-        output: Option<String>,
-
-        /// Maximum conversations per project to analyze
-        #[arg(long, default_value = "20")]
-        max_conversations: usize,
-
-        /// Include projects with no activity in the period
-        #[arg(long)]
-        include_empty: bool,
-    },
+    Timeline(TimelineOptions),
 
     /// Manage MCP servers
     #[command(
@@ -352,6 +509,381 @@ Use the interactive mode for a better server management experience."
         #[command(subcommand)]
         action: McpAction,
     },
+
+    /// Manage named collections of conversations
+    #[command(long_about = "Create and manage named collections of conversations.
+
+A collection is either a saved search (re-evaluated each time it is used) or a
+hand-picked set of session IDs. Collections can be targeted by list, show
+--export and stats with --collection NAME.
+
+EXAMPLES:
+    claude-tools collection list                          # List collections
+    claude-tools collection create auth --from-search auth-bugs   # From a saved search
+    claude-tools collection create picks --sessions abc,def       # Hand-picked sessions
+    claude-tools collection add picks ghi                 # Add sessions
+    claude-tools collection delete picks                  # Delete a collection
+    claude-tools list --collection picks                  # Use a collection")]
+    Collection {
+        #[command(subcommand)]
+        action: CollectionAction,
+    },
+}
+
+/// Arguments of the search command
+#[derive(Args, Clone, Debug)]
+pub struct SearchOptions {
+    /// Search query (supports text patterns, boolean logic, or regex with --regex)
+    #[arg(
+        help = "Text to search for in conversation content. Supports boolean operators (AND, OR, NOT) and parentheses",
+        required_unless_present_any = ["run", "saved", "history", "delete_saved"]
+    )]
+    pub query: Option<String>,
+
+    /// Use regular expressions for pattern matching
+    #[arg(
+        short,
+        long,
+        help = "Enable regex pattern matching (e.g., 'error.*handling')"
+    )]
+    pub regex: bool,
+
+    /// Case insensitive search (ignore letter case)
+    #[arg(short, long)]
+    pub ignore_case: bool,
+
+    /// Show context around matches (number of lines before/after)
+    #[arg(short = 'C', long, default_value = "0")]
+    pub context: usize,
+
+    /// Maximum matching messages to show per conversation (0 shows all)
+    #[arg(long, value_name = "N", default_value = "3")]
+    pub max_hits: usize,
+
+    /// Filter by project path
+    #[arg(
+        long,
+        help = "Only search conversations whose project path contains this"
+    )]
+    pub project: Option<String>,
+
+    /// Filter by model used in conversation
+    #[arg(long, help = "Only search conversations that used this model")]
+    pub model: Option<String>,
+
+    /// Filter by tool used in conversation
+    #[arg(long, help = "Only search conversations that used this tool")]
+    pub tool: Option<String>,
+
+    /// Filter by message role
+    #[arg(long, value_enum, help = "Only search messages from this role")]
+    pub role: Option<MessageRole>,
+
+    /// Search conversations after this date (YYYY-MM-DD or relative like '7 days ago')
+    #[arg(long, help = "Only search conversations after this date")]
+    pub after: Option<String>,
+
+    /// Search conversations before this date (YYYY-MM-DD or relative like '1 week ago')
+    #[arg(long, help = "Only search conversations before this date")]
+    pub before: Option<String>,
+
+    /// Filter by minimum number of messages
+    #[arg(
+        long,
+        help = "Only search conversations with at least this many messages"
+    )]
+    pub min_messages: Option<usize>,
+
+    /// Filter by maximum number of messages
+    #[arg(
+        long,
+        help = "Only search conversations with at most this many messages"
+    )]
+    pub max_messages: Option<usize>,
+
+    /// Filter by minimum conversation duration in minutes
+    #[arg(
+        long,
+        help = "Only search conversations that lasted at least this long"
+    )]
+    pub min_duration: Option<u32>,
+
+    /// Filter by maximum conversation duration in minutes
+    #[arg(long, help = "Only search conversations that lasted at most this long")]
+    pub max_duration: Option<u32>,
+
+    /// Maximum number of results to return
+    #[arg(long, default_value = "50")]
+    pub limit: usize,
+
+    /// Restrict the search to message text, tool inputs or tool results
+    #[arg(
+        long = "in",
+        value_enum,
+        value_delimiter = ',',
+        value_name = "FIELDS",
+        help = "Only search these fields (comma-separated; default: all)"
+    )]
+    pub fields: Vec<SearchScope>,
+
+    /// Output format: human (default), json, jsonl or tsv
+    #[arg(long, value_enum, default_value = "human")]
+    pub format: SearchOutputFormat,
+
+    /// Print only the paths of session files that contain matches
+    #[arg(short = 'l', long)]
+    pub files_with_matches: bool,
+
+    /// Show facet counts (project, model, tool, role, month, source) over all matches.
+    /// With --format json the facets are printed to stderr as a JSON object
+    #[arg(
+        long,
+        help = "Show facet counts over all matches (with --format json, on stderr)"
+    )]
+    pub facets: bool,
+
+    /// Explain each result's score: per-term TF/IDF, boosts and filters
+    #[arg(long)]
+    pub explain: bool,
+
+    /// Save this query and its filters under a name
+    #[arg(long, value_name = "NAME")]
+    pub save: Option<String>,
+
+    /// Run a saved search instead of QUERY
+    #[arg(long, value_name = "NAME", conflicts_with = "query")]
+    pub run: Option<String>,
+
+    /// List saved searches
+    #[arg(long)]
+    pub saved: bool,
+
+    /// Show recent search history
+    #[arg(long)]
+    pub history: bool,
+
+    /// Delete a saved search
+    #[arg(long, value_name = "NAME")]
+    pub delete_saved: Option<String>,
+}
+
+/// Arguments of the timeline command
+#[derive(Args, Clone, Debug)]
+pub struct TimelineOptions {
+    /// Time period to analyze
+    #[arg(long, value_enum, default_value = "two-day")]
+    pub period: TimelinePeriod,
+
+    /// Start of a custom range (YYYY-MM-DD, \"3 days ago\", \"last week\", ...)
+    #[arg(long, value_name = "DATE", conflicts_with_all = ["week", "quarter"])]
+    pub from: Option<String>,
+
+    /// End of a custom range (defaults to now; a bare date includes that whole day)
+    #[arg(long, value_name = "DATE", requires = "from")]
+    pub to: Option<String>,
+
+    /// A specific ISO week, e.g. 2024-W23
+    #[arg(long, value_name = "YYYY-Www", conflicts_with = "quarter")]
+    pub week: Option<String>,
+
+    /// A specific quarter, e.g. 2024-Q2
+    #[arg(long, value_name = "YYYY-Qn")]
+    pub quarter: Option<String>,
+
+    /// Show detailed project breakdowns and statistics  
+    #[arg(short, long)]
+    pub detailed: bool,
+
+    /// Output format for timeline display
+    #[arg(long, value_enum, default_value = "human")]
+    pub format: OutputFormat,
+
+    /// Export timeline data to file
+    #[arg(long, value_enum)]
+    pub export: Option<TimelineExportFormat>,
+
+    /// Output file path (use with --export)
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<String>,
+
+    /// Maximum conversations per project to analyze
+    #[arg(long, default_value = "20")]
+    pub max_conversations: usize,
+
+    /// Include projects with no activity in the period
+    #[arg(long)]
+    pub include_empty: bool,
+
+    /// Read each project's git log and count the commits its conversations contributed to
+    #[arg(long)]
+    pub commits: bool,
+
+    /// Compare with another period instead of listing projects
+    #[arg(long, value_enum, value_name = "PERIOD", conflicts_with = "export")]
+    pub compare: Option<TimelineCompareArg>,
+}
+
+/// Arguments of the stats command
+#[derive(Args, Clone, Debug)]
+pub struct StatsOptions {
+    #[command(subcommand)]
+    pub view: Option<StatsView>,
+
+    /// Show statistics for specific conversation (optional)
+    #[arg(help = "Conversation ID to analyze (omit for global stats)")]
+    pub conversation_id: Option<String>,
+
+    /// Show comprehensive global statistics and analytics
+    #[arg(long)]
+    pub global: bool,
+
+    /// Export analytics data to file (csv, json)
+    #[arg(long, value_enum)]
+    pub export: Option<ExportFormat>,
+
+    /// Show detailed analytics dashboard
+    #[arg(long)]
+    pub detailed: bool,
+
+    /// Analyze only conversations in this collection
+    #[arg(long, value_name = "NAME", conflicts_with = "conversation_id")]
+    pub collection: Option<String>,
+}
+
+/// Arguments of the timesheet command
+#[derive(Args, Clone, Debug)]
+pub struct TimesheetOptions {
+    /// Bucket active time by day or ISO week
+    #[arg(long, value_enum, default_value = "day")]
+    pub by: TimesheetGroupingArg,
+
+    /// Gap between messages that ends a work session (overrides the config)
+    #[arg(long, value_name = "MINUTES", value_parser = clap::value_parser!(u64).range(1..=1440))]
+    pub idle_minutes: Option<u64>,
+
+    /// Only report activity in this period (default: all time)
+    #[arg(long, value_enum, conflicts_with_all = ["from", "week", "quarter"])]
+    pub period: Option<TimelinePeriod>,
+
+    /// Start of a custom range (YYYY-MM-DD, \"3 days ago\", \"last week\", ...)
+    #[arg(long, value_name = "DATE", conflicts_with_all = ["week", "quarter"])]
+    pub from: Option<String>,
+
+    /// End of a custom range (defaults to now; a bare date includes that whole day)
+    #[arg(long, value_name = "DATE", requires = "from")]
+    pub to: Option<String>,
+
+    /// A specific ISO week, e.g. 2024-W23
+    #[arg(long, value_name = "YYYY-Www", conflicts_with = "quarter")]
+    pub week: Option<String>,
+
+    /// A specific quarter, e.g. 2024-Q2
+    #[arg(long, value_name = "YYYY-Qn")]
+    pub quarter: Option<String>,
+
+    /// Only include projects whose path contains this text
+    #[arg(long)]
+    pub project: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "human")]
+    pub format: TimesheetFormat,
+}
+
+/// Arguments of the commits command
+#[derive(Args, Clone, Debug)]
+pub struct CommitsOptions {
+    /// Only show commits linked to this conversation
+    #[arg(long, value_name = "ID")]
+    pub session: Option<String>,
+
+    /// Only include projects whose path contains this text
+    #[arg(long)]
+    pub project: Option<String>,
+
+    /// Only include commits after this date (YYYY-MM-DD, \"3 days ago\", ...)
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<String>,
+
+    /// Minutes before a commit in which active conversations are linked to it
+    #[arg(long, value_name = "MINUTES", default_value = "60", value_parser = clap::value_parser!(u64).range(1..=1440))]
+    pub window_minutes: u64,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "human")]
+    pub format: CommitsFormat,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum StatsView {
+    /// Shell commands run through the Bash tool: frequency, failure rate,
+    /// typical duration and projects
+    #[command(
+        long_about = "Break Bash tool calls down by program and subcommand (cargo test, git status,
+npm run build) with how often each ran, how often it failed, its median
+duration and the projects it ran in. Useful for deciding what to put in
+allowed_tools and which scripts to wrap.
+
+EXAMPLES:
+    claude-tools stats commands                   # All commands, most run first
+    claude-tools stats commands --project api     # Commands run in one project
+    claude-tools stats commands --export csv      # Write claude_commands_<time>.csv"
+    )]
+    Commands {
+        /// Only include projects whose path contains this text
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Only count commands run in conversations in this collection
+        #[arg(long, value_name = "NAME")]
+        collection: Option<String>,
+
+        /// Number of commands to show
+        #[arg(long, default_value_t = 25)]
+        limit: usize,
+
+        /// Export every command to a file (csv, json) instead of printing
+        #[arg(long, value_enum)]
+        export: Option<ExportFormat>,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum CollectionAction {
+    /// List collections
+    #[command(alias = "ls")]
+    List,
+
+    /// Create (or replace) a collection from a saved search or session IDs
+    Create {
+        /// Collection name
+        name: String,
+
+        /// Saved search whose results make up the collection
+        #[arg(long, value_name = "SAVED_SEARCH", conflicts_with = "sessions")]
+        from_search: Option<String>,
+
+        /// Comma-separated session IDs
+        #[arg(long, value_delimiter = ',', required_unless_present = "from_search")]
+        sessions: Vec<String>,
+    },
+
+    /// Add sessions to a hand-picked collection
+    Add {
+        /// Collection name
+        name: String,
+
+        /// Session IDs to add
+        #[arg(required = true)]
+        session_ids: Vec<String>,
+    },
+
+    /// Delete a collection
+    #[command(alias = "rm")]
+    Delete {
+        /// Collection name
+        name: String,
+    },
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
@@ -378,6 +910,28 @@ pub enum MessageRole {
     Tool,
 }
 
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum SearchOutputFormat {
+    /// Human-readable output with grep-style context (default)
+    Human,
+    /// JSON array of result records
+    Json,
+    /// One JSON result record per line
+    Jsonl,
+    /// Tab-separated values, one row per highlight, with a header row
+    Tsv,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum SearchScope {
+    /// Message text
+    Text,
+    /// Tool inputs (commands, file paths, patterns, edited strings)
+    Tools,
+    /// Tool result output
+    Results,
+}
+
 #[derive(clap::ValueEnum, Clone, Debug)]
 pub enum ExportFormat {
     /// CSV format for spreadsheet analysis
@@ -386,16 +940,106 @@ pub enum ExportFormat {
     Json,
 }
 
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum TimelineExportFormat {
+    /// CSV with one row per project
+    Csv,
+    /// Full timeline as JSON
+    Json,
+    /// iCalendar (.ics) file with one event per detected work session
+    Ics,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum TimelineCompareArg {
+    /// The period of the same length just before
+    Previous,
+}
+
 #[derive(clap::ValueEnum, Clone, Debug)]
 pub enum TimelinePeriod {
     /// Last 24 hours of activity
     Day,
     /// Last 48 hours of activity (default)
     TwoDay,
-    /// Last 7 days of activity
+    /// Rolling last 7 days of activity (`week` is an alias)
+    #[value(name = "7d", alias = "week")]
+    SevenDays,
+    /// Rolling last 30 days of activity (`month` is an alias)
+    #[value(name = "30d", alias = "month")]
+    ThirtyDays,
+    /// Since Monday of the current week
+    ThisWeek,
+    /// The previous calendar week (Monday to Sunday)
+    LastWeek,
+    /// Since the first of the current month
+    ThisMonth,
+    /// The previous calendar month
+    LastMonth,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum HeatmapMetricArg {
+    /// Conversations started per day
+    Conversations,
+    /// Messages per day
+    Messages,
+    /// Tokens (input, output and cache) per day
+    Tokens,
+    /// Estimated cost per day
+    Cost,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum DigestPeriod {
+    /// The last 24 hours (default)
+    Day,
+    /// The last 7 days
     Week,
-    /// Last 30 days of activity
-    Month,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum TimesheetGroupingArg {
+    /// One row per project per day (default)
+    Day,
+    /// One row per project per ISO week
+    Week,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum TimesheetFormat {
+    /// Table grouped by period, with per-project totals (default)
+    Human,
+    /// CSV with one row per period and project
+    Csv,
+    /// JSON timesheet including the idle threshold used
+    Json,
+    /// iCalendar events, one per work session
+    Ics,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum CommitsFormat {
+    /// Commits with their linked conversations (default)
+    Human,
+    /// JSON array of linked commits
+    Json,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum BlocksFormat {
+    /// Table of blocks with the active block's projection (default)
+    Human,
+    /// JSON object with the blocks and the active block's projection
+    Json,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum BudgetFormat {
+    /// One line per budget with a usage bar (default)
+    Human,
+    /// JSON array of budget statuses
+    Json,
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
@@ -1,11 +1,19 @@
+use crate::claude::analytics::POOR_CACHE_HIT_RATIO;
 use crate::claude::conversation::MessageRole as ConvMessageRole;
+use crate::claude::latency::format_latency;
+use crate::claude::shell_commands::command_stats;
+use crate::claude::tool_outcomes::{format_success_rate, MIN_RATED_CALLS};
 use crate::claude::{
-    ActivityTimeline, AnalyticsEngine, ClaudeDirectory, ConversationExporter, ConversationParser,
-    ExportConfig, SummaryDepth, TimePeriod, TimelineConfig,
+    ActivityTimeline, AnalyticsEngine, CacheUsage, ClaudeDirectory, CollectionSource,
+    ConversationExporter, ConversationParser, ExportConfig, LatencyStats, ProjectChange,
+    SearchStore, SummaryDepth, TimePeriod, TimelineComparison, TimelineConfig, Timezone,
 };
 use crate::cli::args::{
-    Commands, ConversationExportFormat, ExportFormat, McpAction, MessageRole, OutputFormat,
-    ServerSortField, ServerStatusFilter, TimelinePeriod,
+    BlocksFormat, BudgetFormat, CollectionAction, Commands, CommitsFormat, CommitsOptions,
+    ConversationExportFormat, DigestPeriod, ExportFormat, HeatmapMetricArg, McpAction, MessageRole,
+    OutputFormat, SearchOptions, SearchOutputFormat, SearchScope, ServerSortField,
+    ServerStatusFilter, StatsOptions, StatsView, TimelineCompareArg, TimelineExportFormat,
+    TimelineOptions, TimelinePeriod, TimesheetFormat, TimesheetGroupingArg, TimesheetOptions,
 };
 use crate::config::AppConfig;
 use crate::errors::Result;
@@ -21,6 +29,7 @@ use std::{io, path::PathBuf, time::Duration};
 pub fn execute_command(
     claude_dir: ClaudeDirectory,
     command: Commands,
+    timezone: Timezone,
     verbose: bool,
 ) -> Result<()> {
     match command {
@@ -28,7 +37,8 @@ pub fn execute_command(
             since,
             project,
             detailed,
-        } => execute_list(claude_dir, since, project, detailed, verbose),
+            collection,
+        } => execute_list(claude_dir, since, project, detailed, collection, verbose),
         Commands::Show {
             conversation_id,
             format,
@@ -38,86 +48,176 @@ pub fn execute_command(
             include_metadata,
             include_tools,
             include_timestamps,
-        } => execute_show(
-            claude_dir,
+            collection,
+        } => match (collection, export) {
+            (Some(collection), Some(export_format)) => execute_collection_export(
+                claude_dir,
+                &collection,
+                conversation_export_config(
+                    &export_format,
+                    PathBuf::from(output.unwrap_or_else(|| ".".to_string())),
+                    include_metadata,
+                    include_tools,
+                    include_timestamps,
+                    timezone,
+                ),
+                verbose,
+            ),
+            // clap enforces --export alongside --collection
+            (Some(_), None) => Ok(()),
+            (None, export) => execute_show(
+                claude_dir,
+                conversation_id.unwrap_or_default(),
+                format,
+                role,
+                export,
+                output,
+                include_metadata,
+                include_tools,
+                include_timestamps,
+                timezone,
+                verbose,
+            ),
+        },
+        Commands::Search(options) => execute_search(claude_dir, options, &timezone, verbose),
+        Commands::Similar {
             conversation_id,
-            format,
-            role,
-            export,
-            output,
-            include_metadata,
-            include_tools,
-            include_timestamps,
-            verbose,
-        ),
-        Commands::Search {
-            query,
-            regex,
-            ignore_case,
-            context,
-            model,
-            tool,
-            role,
-            after,
-            before,
-            min_messages,
-            max_messages,
-            min_duration,
-            max_duration,
-            limit,
-        } => execute_search(
-            claude_dir,
-            query,
-            regex,
-            ignore_case,
-            context,
-            model,
-            tool,
-            role,
-            after,
-            before,
-            min_messages,
-            max_messages,
-            min_duration,
-            max_duration,
+            text,
             limit,
-            verbose,
-        ),
-        Commands::Stats {
-            conversation_id,
-            global,
-            export,
-            detailed,
-        } => execute_stats(
-            claude_dir,
-            conversation_id,
-            global,
-            export,
-            detailed,
-            verbose,
-        ),
-        Commands::Timeline {
-            period,
-            detailed,
+        } => execute_similar(claude_dir, conversation_id, text, limit, verbose),
+        Commands::Files {
+            pattern,
+            modified,
+            project,
             format,
-            export,
+        } => execute_files(claude_dir, pattern, modified, project, format, verbose),
+        Commands::Stats(StatsOptions {
+            view:
+                Some(StatsView::Commands {
+                    project,
+                    collection,
+                    limit,
+                    export,
+                }),
+            ..
+        }) => execute_stats_commands(claude_dir, project, collection, limit, export, verbose),
+        Commands::Stats(options) => execute_stats(claude_dir, options, &timezone, verbose),
+        Commands::Timeline(options) => execute_timeline(claude_dir, options, &timezone, verbose),
+        Commands::Heatmap { metric, year, svg } => {
+            execute_heatmap(claude_dir, metric, year, svg, &timezone, verbose)
+        }
+        Commands::Timesheet(options) => execute_timesheet(claude_dir, options, &timezone, verbose),
+        Commands::Digest {
+            period,
+            project,
             output,
-            max_conversations,
-            include_empty,
-        } => execute_timeline(
+        } => execute_digest(claude_dir, period, project, output, &timezone, verbose),
+        Commands::Commits(options) => execute_commits(claude_dir, options, &timezone, verbose),
+        Commands::Blocks {
+            active,
+            recent,
+            token_limit,
+            format,
+        } => execute_blocks(
             claude_dir,
-            period,
-            detailed,
+            active,
+            recent,
+            token_limit,
             format,
-            export,
-            output,
-            max_conversations,
-            include_empty,
+            &timezone,
             verbose,
         ),
-        Commands::Interactive => execute_interactive(claude_dir, verbose),
+        Commands::Budget { quiet, format } => {
+            execute_budget(claude_dir, quiet, format, &timezone, verbose)
+        }
+        Commands::Interactive => execute_interactive(claude_dir, timezone, verbose),
         Commands::Mcp { action } => execute_mcp(action, verbose),
+        Commands::Collection { action } => execute_collection(claude_dir, action),
+    }
+}
+
+/// Conversations in a named collection
+fn collection_conversations(
+    parser: &ConversationParser,
+    name: &str,
+) -> Result<Vec<crate::claude::Conversation>> {
+    let store = SearchStore::load_default()?;
+    // Fail on unknown names before parsing everything
+    store.collection(name)?;
+    store.collection_conversations(name, parser.parse_all_conversations()?)
+}
+
+fn execute_collection(claude_dir: ClaudeDirectory, action: CollectionAction) -> Result<()> {
+    let mut store = SearchStore::load_default()?;
+
+    match action {
+        CollectionAction::List => {
+            if store.collections.is_empty() {
+                println!("No collections. Create one with: claude-tools collection create NAME");
+                return Ok(());
+            }
+
+            println!("📚 Collections ({}):", store.collections.len());
+            for (name, collection) in &store.collections {
+                match &collection.source {
+                    CollectionSource::Query { query_text, .. } => {
+                        println!("   {} → search '{}'", name, query_text)
+                    }
+                    CollectionSource::Sessions { session_ids } => {
+                        println!("   {} → {} session(s)", name, session_ids.len())
+                    }
+                }
+            }
+        }
+        CollectionAction::Create {
+            name,
+            from_search,
+            sessions,
+        } => {
+            let source = match from_search {
+                Some(search_name) => {
+                    let saved = store.saved_search(&search_name)?;
+                    CollectionSource::Query {
+                        query_text: saved.query_text.clone(),
+                        query: Box::new(saved.query.clone()),
+                    }
+                }
+                None => CollectionSource::Sessions {
+                    session_ids: sessions,
+                },
+            };
+            store.create_collection(&name, source);
+            store.save()?;
+            println!("📚 Created collection '{}'", name);
+        }
+        CollectionAction::Add { name, session_ids } => {
+            // Skip IDs that don't match any conversation
+            let parser = ConversationParser::new(claude_dir);
+            let mut known = Vec::new();
+            for id in session_ids {
+                if parser.get_conversation(&id)?.is_some() {
+                    known.push(id);
+                } else {
+                    eprintln!("⚠️  Warning: Conversation not found: {}", id);
+                }
+            }
+            let session_ids = known;
+
+            store.add_to_collection(&name, &session_ids)?;
+            store.save()?;
+            println!("📚 Added {} session(s) to '{}'", session_ids.len(), name);
+        }
+        CollectionAction::Delete { name } => {
+            if store.collections.remove(&name).is_some() {
+                store.save()?;
+                println!("🗑️  Deleted collection '{}'", name);
+            } else {
+                println!("❌ No collection named '{}'", name);
+            }
+        }
     }
+
+    Ok(())
 }
 
 fn execute_list(
@@ -125,6 +225,7 @@ fn execute_list(
     _since: Option<u32>,
     project: Option<String>,
     detailed: bool,
+    collection: Option<String>,
     verbose: bool,
 ) -> Result<()> {
     if verbose {
@@ -133,7 +234,13 @@ fn execute_list(
 
     let parser = ConversationParser::new(claude_dir.clone());
 
-    let conversations = if let Some(project_path) = project {
+    let conversations = if let Some(name) = collection {
+        let mut conversations = collection_conversations(&parser, &name)?;
+        if let Some(project_path) = project {
+            conversations.retain(|conv| conv.project_path.contains(&project_path));
+        }
+        conversations
+    } else if let Some(project_path) = project {
         parser.get_project_conversations(&project_path)?
     } else {
         parser.parse_all_conversations()?
@@ -188,6 +295,7 @@ fn execute_show(
     include_metadata: bool,
     include_tools: bool,
     include_timestamps: bool,
+    timezone: Timezone,
     verbose: bool,
 ) -> Result<()> {
     if verbose {
@@ -200,15 +308,23 @@ fn execute_show(
         Some(conversation) => {
             // Handle export functionality first
             if let Some(export_format) = export {
-                return handle_conversation_export(
-                    &conversation,
-                    export_format,
-                    output,
+                let output_path = match output {
+                    Some(path) => PathBuf::from(path),
+                    None => PathBuf::from(format!(
+                        "conversation_{}.{}",
+                        &conversation.session_id[..8],
+                        internal_export_format(&export_format).extension()
+                    )),
+                };
+                let config = conversation_export_config(
+                    &export_format,
+                    output_path,
                     include_metadata,
                     include_tools,
                     include_timestamps,
-                    verbose,
+                    timezone,
                 );
+                return handle_conversation_export(&conversation, config, verbose);
             }
 
             // Handle regular display formats
@@ -226,7 +342,7 @@ fn execute_show(
                             Some(MessageRole::User) => msg.role == ConvMessageRole::User,
                             Some(MessageRole::Assistant) => msg.role == ConvMessageRole::Assistant,
                             Some(MessageRole::System) => msg.role == ConvMessageRole::System,
-                            Some(MessageRole::Tool) => false, // Tool messages are shown as part of assistant messages
+                            Some(MessageRole::Tool) => msg.role == ConvMessageRole::Tool,
                             None => true,
                         };
 
@@ -235,6 +351,7 @@ fn execute_show(
                                 ConvMessageRole::User => "👤 User",
                                 ConvMessageRole::Assistant => "🤖 Assistant",
                                 ConvMessageRole::System => "⚙️ System",
+                                ConvMessageRole::Tool => "🔧 Tool",
                             };
 
                             println!(
@@ -245,7 +362,9 @@ fn execute_show(
                             if let Some(model) = &msg.model {
                                 println!("   Model: {}", model);
                             }
-                            println!("{}", msg.content);
+                            if msg.role != ConvMessageRole::Tool {
+                                println!("{}", msg.content);
+                            }
 
                             if !msg.tool_uses.is_empty() {
                                 println!("   🛠️ Tool uses:");
@@ -253,6 +372,16 @@ fn execute_show(
                                     println!("      - {}: {}", tool.name, tool.id);
                                 }
                             }
+                            if !msg.tool_results.is_empty() {
+                                println!("   📤 Tool results:");
+                                for result in &msg.tool_results {
+                                    println!(
+                                        "      - {}: {}",
+                                        result.tool_use_id,
+                                        result.summary()
+                                    );
+                                }
+                            }
                             println!();
                         }
                     }
@@ -272,6 +401,7 @@ fn execute_show(
                             ConvMessageRole::User => "User",
                             ConvMessageRole::Assistant => "Assistant",
                             ConvMessageRole::System => "System",
+                            ConvMessageRole::Tool => "Tool",
                         };
                         println!(
                             "\n{} [{}]:\n{}",
@@ -279,6 +409,9 @@ fn execute_show(
                             msg.timestamp.format("%Y-%m-%d %H:%M:%S"),
                             msg.content
                         );
+                        for result in &msg.tool_results {
+                            println!("- {}: {}", result.tool_use_id, result.summary());
+                        }
                     }
                 }
             }
@@ -293,30 +426,81 @@ fn execute_show(
 
 fn execute_search(
     claude_dir: ClaudeDirectory,
-    query: String,
-    regex: bool,
-    ignore_case: bool,
-    _context: usize,
-    model: Option<String>,
-    tool: Option<String>,
-    role: Option<MessageRole>,
-    after: Option<String>,
-    before: Option<String>,
-    min_messages: Option<usize>,
-    max_messages: Option<usize>,
-    min_duration: Option<u32>,
-    max_duration: Option<u32>,
-    limit: usize,
+    options: SearchOptions,
+    timezone: &Timezone,
     verbose: bool,
 ) -> Result<()> {
     use crate::claude::search::{
-        BooleanQueryParser, DateRange, MessageRole as SearchRole, SearchEngine, SearchMode,
-        SearchQuery,
+        BooleanQueryParser, DateRange, MatchSource, MessageRole as SearchRole, SearchEngine,
+        SearchField, SearchMode, SearchQuery,
+    };
+
+    let SearchOptions {
+        query,
+        regex,
+        ignore_case,
+        context,
+        max_hits,
+        project,
+        model,
+        tool,
+        role,
+        after,
+        before,
+        min_messages,
+        max_messages,
+        min_duration,
+        max_duration,
+        limit,
+        fields,
+        format,
+        files_with_matches,
+        facets: show_facets,
+        explain,
+        save,
+        run,
+        saved,
+        history,
+        delete_saved,
+    } = options;
+
+    // An unreadable store shouldn't stop searching; it is left untouched
+    // rather than overwritten with this search's history
+    let (mut store, store_loaded) = match SearchStore::load_default() {
+        Ok(store) => (store, true),
+        Err(e) => {
+            eprintln!(
+                "⚠️  Warning: Could not load saved searches and history: {}",
+                e
+            );
+            (SearchStore::default(), false)
+        }
     };
-    use chrono::{DateTime, Utc};
+
+    if saved {
+        display_saved_searches(&store);
+        return Ok(());
+    }
+    if history {
+        display_search_history(&store);
+        return Ok(());
+    }
+    if let Some(name) = delete_saved {
+        if store.saved.remove(&name).is_some() {
+            store.save()?;
+            println!("🗑️  Deleted saved search '{}'", name);
+        } else {
+            println!("❌ No saved search named '{}'", name);
+        }
+        return Ok(());
+    }
 
     if verbose {
-        eprintln!("🔍 Searching for: {}", query);
+        if let Some(ref name) = run {
+            eprintln!("🔍 Running saved search: {}", name);
+        } else if let Some(ref query) = query {
+            eprintln!("🔍 Searching for: {}", query);
+        }
         if model.is_some() || tool.is_some() || after.is_some() || before.is_some() {
             eprintln!(
                 "📊 Filters applied: model={:?}, tool={:?}, date_range={:?}-{:?}",
@@ -329,93 +513,177 @@ fn execute_search(
     let parser = ConversationParser::new(claude_dir);
     let conversations = parser.parse_all_conversations()?;
 
-    let mut search_engine = SearchEngine::new();
+    let app_config = AppConfig::load_hierarchical(None, None).unwrap_or_default();
+    let mut search_engine = SearchEngine::new()
+        .with_stemming(app_config.search.stemming)
+        .with_ranking_weights(app_config.search.ranking_weights());
     search_engine.build_index(conversations)?;
 
-    // Build search query
-    let mut search_query = SearchQuery::default();
-
-    // Determine search mode and set query
-    if regex {
-        search_query.regex_pattern = Some(query.clone());
-        search_query.search_mode = SearchMode::Regex;
-    } else if query.contains("AND")
-        || query.contains("OR")
-        || query.contains("NOT")
-        || query.contains('(')
-    {
-        // Try boolean search if it looks like boolean syntax
-        match BooleanQueryParser::parse(&query) {
-            Ok(boolean_query) => {
-                search_query.boolean_query = Some(boolean_query);
-                search_query.search_mode = SearchMode::Advanced;
-            }
-            Err(_) => {
-                // Fall back to text search if boolean parsing fails
+    // Build search query, or load a saved one
+    let (query, search_query) = match run {
+        Some(ref name) => {
+            let saved = store.saved_search(name)?;
+            (saved.query_text.clone(), saved.query.clone())
+        }
+        None => {
+            let query = query.unwrap_or_default();
+            let mut search_query = SearchQuery::default();
+
+            // Determine search mode and set query
+            if regex {
+                search_query.regex_pattern = Some(query.clone());
+                search_query.search_mode = SearchMode::Regex;
+            } else if query.contains("AND")
+                || query.contains("OR")
+                || query.contains("NOT")
+                || query.contains('(')
+            {
+                // Try boolean search if it looks like boolean syntax
+                match BooleanQueryParser::parse(&query) {
+                    Ok(boolean_query) => {
+                        search_query.boolean_query = Some(boolean_query);
+                        search_query.search_mode = SearchMode::Advanced;
+                    }
+                    Err(_) => {
+                        // Fall back to text search if boolean parsing fails
+                        search_query.text = Some(query.clone());
+                        search_query.search_mode = if ignore_case {
+                            SearchMode::Text
+                        } else {
+                            SearchMode::Text
+                        };
+                    }
+                }
+            } else {
                 search_query.text = Some(query.clone());
-                search_query.search_mode = if ignore_case {
-                    SearchMode::Text
-                } else {
-                    SearchMode::Text
+                search_query.search_mode = SearchMode::Text;
+            }
+
+            // Apply filters
+            if let Some(project_filter) = project {
+                search_query.project_filter = Some(project_filter);
+            }
+
+            if let Some(model_filter) = model {
+                search_query.model_filter = Some(model_filter);
+            }
+
+            if let Some(tool_filter) = tool {
+                search_query.tool_filter = Some(tool_filter);
+            }
+
+            if let Some(role_filter) = role {
+                let search_role = match role_filter {
+                    MessageRole::User => SearchRole::User,
+                    MessageRole::Assistant => SearchRole::Assistant,
+                    MessageRole::System => SearchRole::System,
+                    MessageRole::Tool => SearchRole::Tool,
                 };
+                search_query.message_role_filter = Some(search_role);
+            }
+
+            // Parse date filters
+            let mut date_range = DateRange {
+                start: None,
+                end: None,
+            };
+            if let Some(after_str) = after {
+                if let Ok(date) = parse_date_string(&after_str, timezone) {
+                    date_range.start = Some(date);
+                } else {
+                    eprintln!("⚠️  Warning: Could not parse 'after' date: {}", after_str);
+                }
+            }
+            if let Some(before_str) = before {
+                if let Ok(date) = parse_date_string(&before_str, timezone) {
+                    date_range.end = Some(date);
+                } else {
+                    eprintln!("⚠️  Warning: Could not parse 'before' date: {}", before_str);
+                }
             }
+            if date_range.start.is_some() || date_range.end.is_some() {
+                search_query.date_range = Some(date_range);
+            }
+
+            // Apply other filters
+            search_query.min_messages = min_messages;
+            search_query.max_messages = max_messages;
+            search_query.min_duration_minutes = min_duration;
+            search_query.max_duration_minutes = max_duration;
+            search_query.max_results = Some(limit);
+            if !fields.is_empty() {
+                search_query.fields = fields
+                    .iter()
+                    .map(|scope| match scope {
+                        SearchScope::Text => SearchField::Text,
+                        SearchScope::Tools => SearchField::Tools,
+                        SearchScope::Results => SearchField::Results,
+                    })
+                    .collect();
+            }
+            (query, search_query)
         }
-    } else {
-        search_query.text = Some(query.clone());
-        search_query.search_mode = SearchMode::Text;
-    }
+    };
 
-    // Apply filters
-    if let Some(model_filter) = model {
-        search_query.model_filter = Some(model_filter);
-    }
+    // Execute search
+    let (results, facets) = if show_facets {
+        let (results, facets) = search_engine.search_with_facets(&search_query)?;
+        (results, Some(facets))
+    } else {
+        (search_engine.search(&search_query)?, None)
+    };
 
-    if let Some(tool_filter) = tool {
-        search_query.tool_filter = Some(tool_filter);
+    if store_loaded {
+        if let Some(ref name) = save {
+            store.save_search(name, &query, search_query.clone());
+            eprintln!("💾 Saved search '{}'", name);
+        }
+        store.record_history(&query, search_query.clone(), results.len());
+        if let Err(e) = store.save() {
+            eprintln!("⚠️  Warning: Could not save search history: {}", e);
+        }
+    } else if let Some(ref name) = save {
+        eprintln!("⚠️  Warning: Search '{}' was not saved", name);
     }
 
-    if let Some(role_filter) = role {
-        let search_role = match role_filter {
-            MessageRole::User => SearchRole::User,
-            MessageRole::Assistant => SearchRole::Assistant,
-            MessageRole::System => SearchRole::System,
-            MessageRole::Tool => SearchRole::Tool,
-        };
-        search_query.message_role_filter = Some(search_role);
+    if files_with_matches {
+        for result in &results {
+            println!(
+                "{}",
+                parser
+                    .conversation_file_path(&result.conversation)
+                    .display()
+            );
+        }
+        return Ok(());
     }
 
-    // Parse date filters
-    let mut date_range = DateRange {
-        start: None,
-        end: None,
-    };
-    if let Some(after_str) = after {
-        if let Ok(date) = parse_date_string(&after_str) {
-            date_range.start = Some(date);
-        } else {
-            eprintln!("⚠️  Warning: Could not parse 'after' date: {}", after_str);
+    match format {
+        SearchOutputFormat::Human => {}
+        SearchOutputFormat::Json => {
+            let records: Vec<_> = results
+                .iter()
+                .map(|r| search_record(&search_engine, r, &search_query, explain))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&records)?);
+            // Facets go to stderr so stdout stays the results array
+            if let Some(ref facets) = facets {
+                eprintln!("{}", serde_json::to_string_pretty(facets)?);
+            }
+            return Ok(());
         }
-    }
-    if let Some(before_str) = before {
-        if let Ok(date) = parse_date_string(&before_str) {
-            date_range.end = Some(date);
-        } else {
-            eprintln!("⚠️  Warning: Could not parse 'before' date: {}", before_str);
+        SearchOutputFormat::Jsonl => {
+            for result in &results {
+                let record = search_record(&search_engine, result, &search_query, explain);
+                println!("{}", serde_json::to_string(&record)?);
+            }
+            return Ok(());
+        }
+        SearchOutputFormat::Tsv => {
+            display_search_tsv(&results);
+            return Ok(());
         }
     }
-    if date_range.start.is_some() || date_range.end.is_some() {
-        search_query.date_range = Some(date_range);
-    }
-
-    // Apply other filters
-    search_query.min_messages = min_messages;
-    search_query.max_messages = max_messages;
-    search_query.min_duration_minutes = min_duration;
-    search_query.max_duration_minutes = max_duration;
-    search_query.max_results = Some(limit);
-
-    // Execute search
-    let results = search_engine.search(&search_query)?;
 
     if results.is_empty() {
         println!("❌ No conversations found matching the search criteria");
@@ -427,6 +695,9 @@ fn execute_search(
         results.len(),
         query
     );
+    if let Some(ref facets) = facets {
+        display_search_facets(facets, results.len());
+    }
     println!();
 
     for result in results {
@@ -444,126 +715,658 @@ fn execute_search(
             conv.messages.len(),
             result.match_count
         );
+        if explain {
+            display_score_explanation(&search_engine.explain(&result, &search_query));
+        }
 
-        // Show highlighted matches
-        if !result.match_highlights.is_empty() {
-            println!("   🎯 Highlights:");
-            for highlight in result.match_highlights.iter().take(3) {
-                // Show up to 3 highlights
-                if let Some(message) = conv.messages.get(highlight.message_index) {
-                    let role_str = match message.role {
-                        ConvMessageRole::User => "User",
-                        ConvMessageRole::Assistant => "Assistant",
-                        ConvMessageRole::System => "System",
-                    };
-
-                    let start = highlight.start.saturating_sub(30);
-                    let end = (highlight.end + 30).min(message.content.len());
-                    let snippet = &message.content[start..end];
+        // Show message-level hits, grep style: "N:" for matching lines, "N-" for context
+        let hits = result.message_hits(context);
+        let shown = if max_hits == 0 {
+            hits.len()
+        } else {
+            hits.len().min(max_hits)
+        };
+        for (hit_idx, hit) in hits.iter().take(shown).enumerate() {
+            let Some(message) = conv.messages.get(hit.message_index) else {
+                continue;
+            };
+            if hit_idx > 0 {
+                println!("   --");
+            }
 
-                    println!(
-                        "      {} {}...{}",
-                        role_str,
-                        if start > 0 { "..." } else { "" },
-                        snippet.replace('\n', " ")
-                    );
-                }
+            let role_str = match hit.role {
+                ConvMessageRole::User => "User",
+                ConvMessageRole::Assistant => "Assistant",
+                ConvMessageRole::System => "System",
+                ConvMessageRole::Tool => "Tool",
+            };
+            let block_label = match hit.source {
+                MatchSource::Text => String::new(),
+                MatchSource::ToolInput(idx) => message
+                    .tool_uses
+                    .get(idx)
+                    .map(|tool_use| format!(" [tool: {}]", tool_use.name))
+                    .unwrap_or_default(),
+                MatchSource::ToolResult(_) => " [tool result]".to_string(),
+            };
+            println!(
+                "   🎯 {} {} {} lines {}-{}{}",
+                hit.message_uuid,
+                role_str,
+                hit.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                hit.line_range.0,
+                hit.line_range.1,
+                block_label
+            );
+            for line in &hit.lines {
+                let separator = if line.is_match { ':' } else { '-' };
+                println!("   {:>5}{}{}", line.number, separator, line.text);
             }
         }
+        if hits.len() > shown {
+            println!(
+                "   … {} more matching message(s) (--max-hits 0 shows all)",
+                hits.len() - shown
+            );
+        }
         println!();
     }
 
     Ok(())
 }
 
-fn execute_stats(
+fn execute_similar(
     claude_dir: ClaudeDirectory,
     conversation_id: Option<String>,
-    global: bool,
-    export: Option<ExportFormat>,
-    detailed: bool,
+    text: Option<String>,
+    limit: usize,
     verbose: bool,
 ) -> Result<()> {
-    if verbose {
-        if let Some(id) = &conversation_id {
-            eprintln!("Getting stats for conversation: {}", id);
-        } else {
-            eprintln!("Generating comprehensive analytics...");
-        }
-    }
+    use crate::claude::SearchEngine;
 
     let parser = ConversationParser::new(claude_dir);
+    let conversations = parser.parse_all_conversations()?;
 
-    if let Some(id) = conversation_id {
-        // Stats for specific conversation (existing implementation)
-        match parser.get_conversation(&id)? {
-            Some(conv) => {
-                println!("📊 Conversation Statistics");
-                println!("   Session ID: {}", conv.session_id);
-                println!("   Project: {}", conv.project_path);
-                println!("   Total messages: {}", conv.messages.len());
-                println!("   User messages: {}", conv.user_message_count());
-                println!("   Assistant messages: {}", conv.assistant_message_count());
+    if verbose {
+        eprintln!("📊 Indexing {} conversations", conversations.len());
+    }
 
-                if let Some(duration) = conv.duration() {
-                    let minutes = duration.num_minutes();
-                    println!("   Duration: {} minutes", minutes);
-                }
+    let app_config = AppConfig::load_hierarchical(None, None).unwrap_or_default();
+    let mut search_engine = SearchEngine::new().with_stemming(app_config.search.stemming);
+    search_engine.build_index(conversations)?;
 
-                // Count tool uses
-                let tool_uses: usize = conv.messages.iter().map(|m| m.tool_uses.len()).sum();
-                if tool_uses > 0 {
-                    println!("   Tool uses: {}", tool_uses);
-                }
-            }
-            None => {
+    let (similar, reference) = match (conversation_id, text) {
+        (Some(id), _) => {
+            if !search_engine.contains_conversation(&id) {
                 println!("❌ Conversation not found: {}", id);
+                return Ok(());
             }
+            (search_engine.similar_to_session(&id, limit)?, id)
         }
-    } else {
-        // Global analytics with new engine
-        let conversations = parser.parse_all_conversations()?;
-        let mut analytics_engine = AnalyticsEngine::new(conversations);
-        let analytics = analytics_engine.generate_analytics()?;
+        (None, Some(text)) => (
+            search_engine.similar_to_text(&text, limit),
+            format!("'{}'", text),
+        ),
+        (None, None) => unreachable!("clap requires a conversation id or --text"),
+    };
 
-        // Handle export first if requested
-        if let Some(export_format) = export {
-            return handle_export(analytics, export_format, verbose);
-        }
+    if similar.is_empty() {
+        println!("❌ No similar conversations found for {}", reference);
+        return Ok(());
+    }
 
-        // Display analytics based on detail level
-        if detailed || global {
-            display_detailed_analytics(analytics);
-        } else {
-            display_basic_analytics(analytics);
+    println!(
+        "🔗 Found {} conversation(s) similar to {}",
+        similar.len(),
+        reference
+    );
+    println!();
+
+    for item in similar {
+        let conv = &item.conversation;
+        println!(
+            "📄 Session: {} (Similarity: {:.2})",
+            conv.session_id, item.similarity
+        );
+        println!("   Project: {}", conv.project_path);
+        if let Some(summary) = &conv.summary {
+            println!("   Summary: {}", summary);
         }
+        println!("   Messages: {}", conv.messages.len());
+        println!();
     }
 
     Ok(())
 }
 
-/// Display basic analytics summary
-fn display_basic_analytics(analytics: &crate::claude::ConversationAnalytics) {
-    let stats = &analytics.basic_stats;
-
-    println!("📊 Conversation Analytics Summary");
-    println!();
-    println!("📈 Basic Statistics:");
-    println!("   Total conversations: {}", stats.total_conversations);
-    println!("   Total messages: {}", stats.total_messages);
-    println!("   User messages: {}", stats.total_user_messages);
-    println!("   Assistant messages: {}", stats.total_assistant_messages);
-    println!("   System messages: {}", stats.total_system_messages);
-    println!("   Tool uses: {}", stats.total_tool_uses);
-    println!(
-        "   Avg. messages per conversation: {:.1}",
-        stats.average_messages_per_conversation
-    );
+fn execute_files(
+    claude_dir: ClaudeDirectory,
+    pattern: String,
+    modified: bool,
+    project: Option<String>,
+    format: SearchOutputFormat,
+    verbose: bool,
+) -> Result<()> {
+    use crate::claude::FileIndex;
 
-    if let Some(date_range) = &stats.date_range.earliest {
-        println!("   First conversation: {}", date_range.format("%Y-%m-%d"));
+    let parser = ConversationParser::new(claude_dir);
+    let mut conversations = parser.parse_all_conversations()?;
+    if let Some(project_path) = project {
+        conversations.retain(|conv| conv.project_path.contains(&project_path));
     }
-    if let Some(date_range) = &stats.date_range.latest {
+
+    let index = FileIndex::build(&conversations);
+    if verbose {
+        eprintln!(
+            "📊 Indexed {} files across {} conversations",
+            index.len(),
+            conversations.len()
+        );
+    }
+
+    let matches = index.find(&pattern, modified);
+
+    match format {
+        SearchOutputFormat::Human => {}
+        SearchOutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&matches)?);
+            return Ok(());
+        }
+        SearchOutputFormat::Jsonl => {
+            for file in &matches {
+                for session in &file.sessions {
+                    let record = serde_json::json!({
+                        "path": file.path,
+                        "session": session,
+                    });
+                    println!("{}", serde_json::to_string(&record)?);
+                }
+            }
+            return Ok(());
+        }
+        SearchOutputFormat::Tsv => {
+            println!("path\tsession_id\tproject\tfirst_touched\tlast_touched\toperations\ttouches");
+            for file in &matches {
+                for session in &file.sessions {
+                    println!(
+                        "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                        file.path,
+                        session.session_id,
+                        session.project_path,
+                        session.first_touched.to_rfc3339(),
+                        session.last_touched.to_rfc3339(),
+                        file_operations_label(&session.operations, ","),
+                        session.touches
+                    );
+                }
+            }
+            return Ok(());
+        }
+    }
+
+    if matches.is_empty() {
+        println!("❌ No conversations touched files matching: {}", pattern);
+        return Ok(());
+    }
+
+    let session_count = matches
+        .iter()
+        .flat_map(|file| file.sessions.iter().map(|s| s.session_id.as_str()))
+        .collect::<std::collections::HashSet<_>>()
+        .len();
+    println!(
+        "📁 {} file(s) touched in {} conversation(s)",
+        matches.len(),
+        session_count
+    );
+
+    for file in &matches {
+        println!();
+        println!("📄 {}", file.path);
+        for session in &file.sessions {
+            let when = if session.first_touched == session.last_touched {
+                session.last_touched.format("%Y-%m-%d %H:%M").to_string()
+            } else {
+                format!(
+                    "{} → {}",
+                    session.first_touched.format("%Y-%m-%d %H:%M"),
+                    session.last_touched.format("%Y-%m-%d %H:%M")
+                )
+            };
+            println!(
+                "   {}  {}  {} ({}×)",
+                session.session_id,
+                when,
+                file_operations_label(&session.operations, ", "),
+                session.touches
+            );
+            if verbose {
+                println!("      Project: {}", session.project_path);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn file_operations_label(operations: &[crate::claude::FileOperation], separator: &str) -> String {
+    operations
+        .iter()
+        .map(|op| op.label())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Print saved searches and collections
+fn display_saved_searches(store: &SearchStore) {
+    if store.saved.is_empty() {
+        println!("No saved searches. Save one with: claude-tools search QUERY --save NAME");
+        return;
+    }
+
+    println!("💾 Saved searches ({}):", store.saved.len());
+    for (name, saved) in &store.saved {
+        let filters = saved.query.describe_filters();
+        println!("   {} → '{}'", name, saved.query_text);
+        if !filters.is_empty() {
+            println!("      filters: {}", filters.join("; "));
+        }
+    }
+}
+
+/// Print recent search history, newest first
+fn display_search_history(store: &SearchStore) {
+    let recent = store.recent_history();
+    if recent.is_empty() {
+        println!("No search history yet");
+        return;
+    }
+
+    println!("🕘 Recent searches:");
+    for entry in recent {
+        println!(
+            "   {}  '{}' ({} result(s))",
+            entry.searched_at.format("%Y-%m-%d %H:%M"),
+            entry.query_text,
+            entry.result_count
+        );
+    }
+}
+
+/// Machine-readable search record, with the score breakdown when requested
+fn search_record(
+    engine: &crate::claude::SearchEngine,
+    result: &crate::claude::SearchResult,
+    query: &crate::claude::SearchQuery,
+    explain: bool,
+) -> crate::claude::search::SearchResultRecord {
+    let mut record = result.to_record();
+    if explain {
+        record.explanation = Some(engine.explain(result, query));
+    }
+    record
+}
+
+/// Print how a result's score was computed
+fn display_score_explanation(explanation: &crate::claude::ScoreExplanation) {
+    let term_sum: f64 = explanation.terms.iter().map(|t| t.contribution).sum();
+    match explanation.mode.as_str() {
+        "text" => println!(
+            "   🧮 Score {:.4} = Σ terms {:.4} × term weight {:.2} × recency {:.3} × length {:.2}",
+            explanation.score,
+            term_sum,
+            explanation.term_weight,
+            explanation.recency_factor,
+            explanation.length_factor
+        ),
+        "boolean" => println!(
+            "   🧮 Score {:.4} = Σ matching terms {:.4} (NOT clauses add 1.0 when they hold)",
+            explanation.score, term_sum
+        ),
+        "regex" => println!(
+            "   🧮 Score {:.4} = number of regex matches",
+            explanation.score
+        ),
+        _ => println!(
+            "   🧮 Score {:.4} (no text query, filters only)",
+            explanation.score
+        ),
+    }
+
+    for term in &explanation.terms {
+        if explanation.mode == "text" {
+            println!(
+                "      '{}': {} block(s) × tf {:.4} × idf {:.4} = {:.4}",
+                term.term, term.occurrences, term.tf, term.idf, term.contribution
+            );
+        } else {
+            println!(
+                "      '{}': tf {:.4} × idf {:.4} + summary {:.2} = {:.4} ({} match(es))",
+                term.term,
+                term.tf,
+                term.idf,
+                term.summary_bonus,
+                term.contribution,
+                term.occurrences
+            );
+        }
+    }
+
+    if !explanation.filters.is_empty() {
+        println!("      filters: {}", explanation.filters.join("; "));
+    }
+}
+
+/// Print facet counts, at most a handful of values per facet
+fn display_search_facets(facets: &crate::claude::SearchFacets, shown: usize) {
+    const MAX_VALUES: usize = 8;
+
+    let total: usize = facets.projects.iter().map(|f| f.count).sum();
+    if total > shown {
+        println!("   (showing {} of {} matching conversations)", shown, total);
+    }
+
+    println!();
+    println!("📊 Facets:");
+    for (kind, values) in facets.groups() {
+        if values.is_empty() {
+            continue;
+        }
+        let mut line: Vec<String> = values
+            .iter()
+            .take(MAX_VALUES)
+            .map(|facet| format!("{} ({})", facet.value, facet.count))
+            .collect();
+        if values.len() > MAX_VALUES {
+            line.push(format!("+{} more", values.len() - MAX_VALUES));
+        }
+        println!("   {:<8} {}", format!("{}:", kind.label()), line.join(", "));
+    }
+}
+
+/// Print search results as TSV, one row per highlight
+fn display_search_tsv(results: &[crate::claude::SearchResult]) {
+    println!("session_id\tproject\tscore\tmessage_uuid\tfield\tblock_index\tstart\tend\tsnippet");
+    for result in results {
+        let record = result.to_record();
+        let clean = |value: &str| value.replace(['\t', '\n', '\r'], " ");
+
+        if record.highlights.is_empty() {
+            println!(
+                "{}\t{}\t{:.4}\t\t\t\t\t\t",
+                record.session_id,
+                clean(&record.project),
+                record.score
+            );
+            continue;
+        }
+
+        for highlight in &record.highlights {
+            let field = match highlight.field {
+                crate::claude::SearchField::Text => "text",
+                crate::claude::SearchField::Tools => "tools",
+                crate::claude::SearchField::Results => "results",
+            };
+            println!(
+                "{}\t{}\t{:.4}\t{}\t{}\t{}\t{}\t{}\t{}",
+                record.session_id,
+                clean(&record.project),
+                record.score,
+                highlight.message_uuid,
+                field,
+                highlight
+                    .block_index
+                    .map(|idx| idx.to_string())
+                    .unwrap_or_default(),
+                highlight.start,
+                highlight.end,
+                clean(&highlight.snippet)
+            );
+        }
+    }
+}
+
+fn execute_stats(
+    claude_dir: ClaudeDirectory,
+    options: StatsOptions,
+    timezone: &Timezone,
+    verbose: bool,
+) -> Result<()> {
+    let StatsOptions {
+        conversation_id,
+        global,
+        export,
+        detailed,
+        collection,
+        ..
+    } = options;
+    if verbose {
+        if let Some(id) = &conversation_id {
+            eprintln!("Getting stats for conversation: {}", id);
+        } else if let Some(name) = &collection {
+            eprintln!("Generating analytics for collection: {}", name);
+        } else {
+            eprintln!("Generating comprehensive analytics...");
+        }
+    }
+
+    let parser = ConversationParser::new(claude_dir);
+
+    if let Some(id) = conversation_id {
+        // Stats for specific conversation (existing implementation)
+        match parser.get_conversation(&id)? {
+            Some(conv) => {
+                println!("📊 Conversation Statistics");
+                println!("   Session ID: {}", conv.session_id);
+                println!("   Project: {}", conv.project_path);
+                println!("   Total messages: {}", conv.messages.len());
+                println!("   User messages: {}", conv.user_message_count());
+                println!("   Assistant messages: {}", conv.assistant_message_count());
+
+                if let Some(duration) = conv.duration() {
+                    let minutes = duration.num_minutes();
+                    println!("   Duration: {} minutes", minutes);
+                }
+
+                // Count tool uses
+                let tool_uses: usize = conv.messages.iter().map(|m| m.tool_uses.len()).sum();
+                if tool_uses > 0 {
+                    println!("   Tool uses: {}", tool_uses);
+                }
+
+                let cache = CacheUsage::for_conversation(&conv);
+                if let Some(hit_ratio) = cache.hit_ratio() {
+                    println!(
+                        "   Prompt cache hit ratio: {:.1}% ({} of {} prompt tokens, est. ${:.2} saved)",
+                        hit_ratio,
+                        cache.cache_read_tokens,
+                        cache.prompt_tokens(),
+                        cache.estimated_savings
+                    );
+                }
+            }
+            None => {
+                println!("❌ Conversation not found: {}", id);
+            }
+        }
+    } else {
+        // Global analytics with new engine
+        let conversations = match collection {
+            Some(ref name) => collection_conversations(&parser, name)?,
+            None => parser.parse_all_conversations()?,
+        };
+        let mut analytics_engine = AnalyticsEngine::new(conversations).with_timezone(*timezone);
+        let analytics = analytics_engine.generate_analytics()?;
+
+        // Handle export first if requested
+        if let Some(export_format) = export {
+            return handle_export(analytics, export_format, verbose);
+        }
+
+        // Display analytics based on detail level
+        if detailed || global {
+            display_detailed_analytics(analytics);
+        } else {
+            display_basic_analytics(analytics);
+        }
+    }
+
+    Ok(())
+}
+
+/// Show or export shell command analytics for Bash tool calls
+fn execute_stats_commands(
+    claude_dir: ClaudeDirectory,
+    project: Option<String>,
+    collection: Option<String>,
+    limit: usize,
+    export: Option<ExportFormat>,
+    verbose: bool,
+) -> Result<()> {
+    let parser = ConversationParser::new(claude_dir);
+    let mut conversations = match collection {
+        Some(ref name) => collection_conversations(&parser, name)?,
+        None => parser.parse_all_conversations()?,
+    };
+    if let Some(ref project) = project {
+        conversations.retain(|conv| conv.project_path.contains(project.as_str()));
+    }
+    if verbose {
+        eprintln!(
+            "🐚 Analyzing shell commands in {} conversations",
+            conversations.len()
+        );
+    }
+
+    let commands = command_stats(&conversations);
+
+    if let Some(format) = export {
+        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+        let (filename, data) = match format {
+            ExportFormat::Json => (
+                format!("claude_commands_{}.json", timestamp),
+                serde_json::to_string_pretty(&commands)?,
+            ),
+            ExportFormat::Csv => (
+                format!("claude_commands_{}.csv", timestamp),
+                commands_csv(&commands),
+            ),
+        };
+        std::fs::write(&filename, data)?;
+        println!("📄 Shell commands exported to: {}", filename);
+        return Ok(());
+    }
+
+    if commands.is_empty() {
+        println!("❌ No shell commands found");
+        return Ok(());
+    }
+
+    let total_runs: usize = commands.iter().map(|command| command.runs).sum();
+    println!(
+        "🐚 Shell Commands ({} runs of {} distinct commands)",
+        total_runs,
+        commands.len()
+    );
+    println!();
+
+    let shown = &commands[..commands.len().min(limit)];
+    let width = shown
+        .iter()
+        .map(|command| command.command.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Command".len());
+    println!(
+        "   {:<width$}  {:>5}  {:>7}  {:>8}  Projects",
+        "Command", "Runs", "Failed", "Median"
+    );
+    for command in shown {
+        let projects = command
+            .top_projects()
+            .iter()
+            .take(3)
+            .map(|(project, runs)| format!("{} ({})", project, runs))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "   {:<width$}  {:>5}  {:>7}  {:>8}  {}",
+            command.command,
+            command.runs,
+            command
+                .failure_rate()
+                .map_or("-".to_string(), |rate| format!("{:.1}%", rate)),
+            command
+                .median_duration_secs
+                .map_or("-".to_string(), format_latency),
+            projects
+        );
+    }
+    if commands.len() > shown.len() {
+        println!();
+        println!(
+            "   … {} more (use --limit to show them)",
+            commands.len() - shown.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// One row per shell command, with failures by kind and runs per project
+fn commands_csv(commands: &[crate::claude::CommandStats]) -> String {
+    let mut csv = String::from(
+        "Command,Runs,Succeeded,Errors,Rejected,Non-zero Exits,Failure Rate,Median Seconds,Projects\n",
+    );
+    for command in commands {
+        let projects = command
+            .top_projects()
+            .iter()
+            .map(|(project, runs)| format!("{}={}", project, runs))
+            .collect::<Vec<_>>()
+            .join(";");
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            csv_field(&command.command),
+            command.runs,
+            command.outcomes.succeeded,
+            command.outcomes.errored,
+            command.outcomes.rejected,
+            command.outcomes.nonzero_exit,
+            command
+                .failure_rate()
+                .map_or(String::new(), |rate| format!("{:.2}", rate)),
+            command
+                .median_duration_secs
+                .map_or(String::new(), |seconds| format!("{:.1}", seconds)),
+            csv_field(&projects)
+        ));
+    }
+    csv
+}
+
+/// Display basic analytics summary
+fn display_basic_analytics(analytics: &crate::claude::ConversationAnalytics) {
+    let stats = &analytics.basic_stats;
+
+    println!("📊 Conversation Analytics Summary");
+    println!();
+    println!("📈 Basic Statistics:");
+    println!("   Total conversations: {}", stats.total_conversations);
+    println!("   Total messages: {}", stats.total_messages);
+    println!("   User messages: {}", stats.total_user_messages);
+    println!("   Assistant messages: {}", stats.total_assistant_messages);
+    println!("   System messages: {}", stats.total_system_messages);
+    println!("   Tool uses: {}", stats.total_tool_uses);
+    println!(
+        "   Avg. messages per conversation: {:.1}",
+        stats.average_messages_per_conversation
+    );
+
+    if let Some(date_range) = &stats.date_range.earliest {
+        println!("   First conversation: {}", date_range.format("%Y-%m-%d"));
+    }
+    if let Some(date_range) = &stats.date_range.latest {
         println!("   Latest conversation: {}", date_range.format("%Y-%m-%d"));
     }
     if let Some(span) = stats.date_range.span_days {
@@ -598,28 +1401,70 @@ fn display_basic_analytics(analytics: &crate::claude::ConversationAnalytics) {
         .enumerate()
     {
         println!(
-            "   {}. {} - {} uses ({:.1}%)",
+            "   {}. {} - {} uses ({:.1}%), {} success",
             i + 1,
             tool.tool_name,
             tool.usage_count,
-            tool.percentage
+            tool.percentage,
+            format_success_rate(tool.success_rate)
         );
     }
 
-    println!();
-    println!("📁 Top Projects:");
-    for (i, project) in analytics
-        .project_analytics
-        .top_projects
-        .iter()
-        .take(5)
-        .enumerate()
-    {
-        println!(
-            "   {}. {} - {} conversations ({:.1}%)",
-            i + 1,
-            project.project_name,
-            project.conversation_count,
+    let offenders = analytics.tool_analytics.worst_offenders(MIN_RATED_CALLS);
+    if !offenders.is_empty() {
+        println!();
+        println!("⚠️  Least Reliable Tools:");
+        for (tool, counts) in offenders.iter().take(5) {
+            println!(
+                "   {} - {} success of {} calls ({})",
+                tool,
+                format_success_rate(counts.success_rate()),
+                counts.total(),
+                counts.failure_summary()
+            );
+        }
+    }
+
+    let cache = &analytics.cache_analytics;
+    if let Some(hit_ratio) = cache.overall.hit_ratio() {
+        println!();
+        println!("💾 Prompt Cache:");
+        println!("   Hit ratio: {:.1}%", hit_ratio);
+        println!("   Cache reads: {} tokens", cache.overall.cache_read_tokens);
+        println!(
+            "   Cache writes: {} tokens",
+            cache.overall.cache_write_tokens
+        );
+        println!(
+            "   Uncached input: {} tokens",
+            cache.overall.uncached_input_tokens
+        );
+        println!(
+            "   Estimated savings: ${:.2}",
+            cache.overall.estimated_savings
+        );
+        if !cache.poor_reuse.is_empty() {
+            println!(
+                "   Conversations with poor cache reuse: {}",
+                cache.poor_reuse.len()
+            );
+        }
+    }
+
+    println!();
+    println!("📁 Top Projects:");
+    for (i, project) in analytics
+        .project_analytics
+        .top_projects
+        .iter()
+        .take(5)
+        .enumerate()
+    {
+        println!(
+            "   {}. {} - {} conversations ({:.1}%)",
+            i + 1,
+            project.project_name,
+            project.conversation_count,
             project.percentage
         );
     }
@@ -661,6 +1506,111 @@ fn display_detailed_analytics(analytics: &crate::claude::ConversationAnalytics)
         }
     }
 
+    let tools = &analytics.tool_analytics;
+    let mut failing_projects: Vec<_> = tools
+        .project_tool_outcomes
+        .iter()
+        .filter(|(_, counts)| counts.failures() > 0)
+        .collect();
+    if !failing_projects.is_empty() {
+        failing_projects.sort_by(|(a_name, a), (b_name, b)| {
+            b.failures().cmp(&a.failures()).then(a_name.cmp(b_name))
+        });
+        println!();
+        println!("🧯 Tool Failures by Project:");
+        for (project, counts) in failing_projects.iter().take(5) {
+            println!(
+                "   {} - {} success of {} calls ({})",
+                project,
+                format_success_rate(counts.success_rate()),
+                counts.total(),
+                counts.failure_summary()
+            );
+        }
+    }
+
+    let cache = &analytics.cache_analytics;
+    if !cache.per_model.is_empty() {
+        let mut models: Vec<_> = cache.per_model.iter().collect();
+        models.sort_by_key(|(_, usage)| std::cmp::Reverse(usage.prompt_tokens()));
+        println!();
+        println!("💾 Prompt Cache by Model:");
+        for (model, usage) in models {
+            println!(
+                "   {} - {:.1}% hit ratio, {} tokens written, est. ${:.2} saved",
+                model,
+                usage.hit_ratio().unwrap_or(0.0),
+                usage.cache_write_tokens,
+                usage.estimated_savings
+            );
+        }
+    }
+    if !cache.poor_reuse.is_empty() {
+        println!();
+        println!(
+            "🔥 Poor Cache Reuse (under {:.0}% hits):",
+            POOR_CACHE_HIT_RATIO
+        );
+        for session in cache.poor_reuse.iter().take(10) {
+            println!(
+                "   {} ({}) - {:.1}% of {} prompt tokens cached, est. ${:.2}",
+                session.session_id,
+                session.project_path,
+                session.cache_usage.hit_ratio().unwrap_or(0.0),
+                session.cache_usage.prompt_tokens(),
+                session.estimated_cost
+            );
+        }
+    }
+
+    let files = &analytics.file_analytics;
+    if !files.top_files.is_empty() {
+        println!();
+        println!(
+            "🔥 File Hotspots ({} edits across {} files):",
+            files.total_edits, files.files_edited
+        );
+        for file in files.top_files.iter().take(10) {
+            println!(
+                "   {} - {} edits in {} sessions, {} re-edits (up to {} in one session)",
+                file.path, file.edits, file.sessions, file.re_edits, file.max_edits_in_session
+            );
+        }
+
+        let mut projects: Vec<_> = files.top_files_per_project.iter().collect();
+        projects.sort_by_key(|(project, hotspots)| {
+            (
+                std::cmp::Reverse(hotspots.iter().map(|file| file.edits).sum::<usize>()),
+                *project,
+            )
+        });
+        println!("   By project:");
+        for (project, hotspots) in projects.iter().take(5) {
+            println!("     {}:", project);
+            for file in hotspots.iter().take(3) {
+                println!(
+                    "       {} - {} edits, {:.1} per session",
+                    file.path,
+                    file.edits,
+                    file.churn()
+                );
+            }
+        }
+    }
+
+    if !tools.tool_outcomes_over_time.is_empty() {
+        println!();
+        println!("📉 Tool Success Rate by Day:");
+        for (day, counts) in tools.tool_outcomes_over_time.iter().rev().take(7) {
+            println!(
+                "   {} - {} success of {} calls",
+                day,
+                format_success_rate(counts.success_rate()),
+                counts.total()
+            );
+        }
+    }
+
     println!();
     println!("📊 Quality Metrics:");
     let quality = &analytics.quality_metrics;
@@ -693,135 +1643,871 @@ fn display_detailed_analytics(analytics: &crate::claude::ConversationAnalytics)
     println!("   Average: {:.1} messages", conv_dist.mean);
     println!("   Median: {} messages", conv_dist.median);
     println!("   95th percentile: {} messages", conv_dist.percentile_95);
+
+    let responses = &analytics.quality_metrics.response_patterns;
+    if let Some(latency) = &responses.latency {
+        println!();
+        println!(
+            "⏱️  Response Latency ({} turns, {} waiting on permission excluded):",
+            latency.turn.count, responses.permission_wait_turns
+        );
+        println!(
+            "   First reply: {}",
+            latency.first_reply.percentiles_label()
+        );
+        println!("   End of turn: {}", latency.turn.percentiles_label());
+
+        let print_rows = |heading: &str, mut rows: Vec<(String, &LatencyStats)>| {
+            if rows.is_empty() {
+                return;
+            }
+            rows.sort_by(|(a_key, a), (b_key, b)| {
+                b.turn.count.cmp(&a.turn.count).then(a_key.cmp(b_key))
+            });
+            println!("   {}:", heading);
+            for (key, stats) in rows.iter().take(5) {
+                println!(
+                    "     {} - first reply {}; turn p50 {} ({} turns)",
+                    key,
+                    stats.first_reply.percentiles_label(),
+                    format_latency(stats.turn.p50),
+                    stats.turn.count
+                );
+            }
+        };
+        print_rows(
+            "By model",
+            responses
+                .latency_per_model
+                .iter()
+                .map(|(model, stats)| (model.clone(), stats))
+                .collect(),
+        );
+        print_rows(
+            "By project",
+            responses
+                .latency_per_project
+                .iter()
+                .map(|(project, stats)| (project.clone(), stats))
+                .collect(),
+        );
+
+        println!("   By hour of day:");
+        for (hour, stats) in &responses.latency_per_hour {
+            println!(
+                "     {:02}:00 - first reply {}; turn p50 {} ({} turns)",
+                hour,
+                stats.first_reply.percentiles_label(),
+                format_latency(stats.turn.p50),
+                stats.turn.count
+            );
+        }
+    }
+}
+
+/// Handle analytics export
+fn handle_export(
+    analytics: &crate::claude::ConversationAnalytics,
+    format: ExportFormat,
+    verbose: bool,
+) -> Result<()> {
+    let timestamp = analytics.generated_at.format("%Y%m%d_%H%M%S");
+
+    match format {
+        ExportFormat::Json => {
+            let filename = format!("claude_analytics_{}.json", timestamp);
+            let json_data = serde_json::to_string_pretty(analytics)?;
+            let data_size = json_data.len();
+            std::fs::write(&filename, json_data)?;
+            println!("📄 Analytics exported to: {}", filename);
+            if verbose {
+                println!("   Format: JSON");
+                println!("   Size: {} bytes", data_size);
+            }
+        }
+        ExportFormat::Csv => {
+            let filename = format!("claude_analytics_{}.csv", timestamp);
+            let csv_data = generate_csv_export(analytics)?;
+            let row_count = csv_data.lines().count();
+            std::fs::write(&filename, csv_data)?;
+            println!("📄 Analytics exported to: {}", filename);
+            if verbose {
+                println!("   Format: CSV");
+                println!("   Rows: {}", row_count);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Generate CSV export of analytics data
+fn generate_csv_export(analytics: &crate::claude::ConversationAnalytics) -> Result<String> {
+    let mut csv_content = String::new();
+
+    // Basic stats section
+    csv_content.push_str("Section,Metric,Value\n");
+    csv_content.push_str(&format!(
+        "Basic,Total Conversations,{}\n",
+        analytics.basic_stats.total_conversations
+    ));
+    csv_content.push_str(&format!(
+        "Basic,Total Messages,{}\n",
+        analytics.basic_stats.total_messages
+    ));
+    csv_content.push_str(&format!(
+        "Basic,User Messages,{}\n",
+        analytics.basic_stats.total_user_messages
+    ));
+    csv_content.push_str(&format!(
+        "Basic,Assistant Messages,{}\n",
+        analytics.basic_stats.total_assistant_messages
+    ));
+    csv_content.push_str(&format!(
+        "Basic,System Messages,{}\n",
+        analytics.basic_stats.total_system_messages
+    ));
+    csv_content.push_str(&format!(
+        "Basic,Tool Uses,{}\n",
+        analytics.basic_stats.total_tool_uses
+    ));
+    csv_content.push_str(&format!(
+        "Basic,Avg Messages per Conversation,{:.2}\n",
+        analytics.basic_stats.average_messages_per_conversation
+    ));
+
+    // Model usage
+    csv_content.push_str("\nModel,Usage Count,Percentage\n");
+    for model in &analytics.model_analytics.top_models {
+        csv_content.push_str(&format!(
+            "{},{},{:.2}\n",
+            model.model_name, model.usage_count, model.percentage
+        ));
+    }
+
+    // Tool usage
+    csv_content
+        .push_str("\nTool,Usage Count,Percentage,Success Rate,Errors,Rejected,Non-zero Exits\n");
+    for tool in &analytics.tool_analytics.top_tools {
+        let outcomes = analytics
+            .tool_analytics
+            .tool_outcomes
+            .get(&tool.tool_name)
+            .copied()
+            .unwrap_or_default();
+        csv_content.push_str(&format!(
+            "{},{},{:.2},{:.2},{},{},{}\n",
+            tool.tool_name,
+            tool.usage_count,
+            tool.percentage,
+            tool.success_rate
+                .map_or_else(|| "n/a".to_string(), |rate| format!("{:.2}", rate)),
+            outcomes.errored,
+            outcomes.rejected,
+            outcomes.nonzero_exit
+        ));
+    }
+
+    // Prompt cache usage
+    csv_content.push_str(
+        "\nDate,Uncached Input Tokens,Cache Write Tokens,Cache Read Tokens,Hit Ratio,Estimated Savings\n",
+    );
+    for (day, usage) in &analytics.cache_analytics.per_day {
+        csv_content.push_str(&format!(
+            "{},{},{},{},{:.2},{:.4}\n",
+            day,
+            usage.uncached_input_tokens,
+            usage.cache_write_tokens,
+            usage.cache_read_tokens,
+            usage.hit_ratio().unwrap_or(0.0),
+            usage.estimated_savings
+        ));
+    }
+
+    // Response latency, in seconds
+    csv_content.push_str(
+        "\nLatency Group,Key,Turns,First Reply p50,First Reply p90,First Reply p99,Turn p50,Turn p90,Turn p99\n",
+    );
+    let responses = &analytics.quality_metrics.response_patterns;
+    let latency_rows = responses
+        .latency
+        .iter()
+        .map(|stats| ("all", String::new(), stats))
+        .chain(
+            responses
+                .latency_per_model
+                .iter()
+                .map(|(model, stats)| ("model", model.clone(), stats)),
+        )
+        .chain(
+            responses
+                .latency_per_project
+                .iter()
+                .map(|(project, stats)| ("project", project.clone(), stats)),
+        )
+        .chain(
+            responses
+                .latency_per_hour
+                .iter()
+                .map(|(hour, stats)| ("hour", hour.to_string(), stats)),
+        );
+    for (group, key, stats) in latency_rows {
+        csv_content.push_str(&format!(
+            "{},{},{},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1}\n",
+            group,
+            key,
+            stats.turn.count,
+            stats.first_reply.p50,
+            stats.first_reply.p90,
+            stats.first_reply.p99,
+            stats.turn.p50,
+            stats.turn.p90,
+            stats.turn.p99
+        ));
+    }
+
+    // Project usage
+    csv_content.push_str("\nProject,Conversations,Messages,Percentage\n");
+    for project in &analytics.project_analytics.top_projects {
+        csv_content.push_str(&format!(
+            "{},{},{},{:.2}\n",
+            project.project_name,
+            project.conversation_count,
+            project.message_count,
+            project.percentage
+        ));
+    }
+
+    Ok(csv_content)
+}
+
+fn execute_heatmap(
+    claude_dir: ClaudeDirectory,
+    metric: HeatmapMetricArg,
+    year: Option<i32>,
+    svg: Option<PathBuf>,
+    timezone: &Timezone,
+    verbose: bool,
+) -> Result<()> {
+    use crate::claude::{CalendarHeatmap, HeatmapMetric};
+
+    let metric = match metric {
+        HeatmapMetricArg::Conversations => HeatmapMetric::Conversations,
+        HeatmapMetricArg::Messages => HeatmapMetric::Messages,
+        HeatmapMetricArg::Tokens => HeatmapMetric::Tokens,
+        HeatmapMetricArg::Cost => HeatmapMetric::Cost,
+    };
+
+    let parser = ConversationParser::new(claude_dir);
+    let conversations = parser.parse_all_conversations()?;
+    if verbose {
+        eprintln!("📊 Analyzing {} conversations", conversations.len());
+    }
+
+    let mut analytics_engine = AnalyticsEngine::new(conversations).with_timezone(*timezone);
+    let analytics = analytics_engine.generate_analytics()?;
+    let temporal = &analytics.temporal_analysis;
+
+    let heatmap = match year {
+        Some(year) => CalendarHeatmap::for_year(temporal, metric, year),
+        None => CalendarHeatmap::last_year(temporal, metric, timezone.today()),
+    };
+
+    if let Some(path) = svg {
+        std::fs::write(&path, heatmap.to_svg())?;
+        println!("✅ Heatmap written to {}", path.display());
+        return Ok(());
+    }
+
+    println!(
+        "🗓️  Activity by {} ({} to {})",
+        metric.label(),
+        heatmap.first,
+        heatmap.last
+    );
+    println!();
+    for line in heatmap.render_unicode() {
+        println!("{}", line);
+    }
+    println!();
+    println!(
+        "   Total: {} over {} active day(s)",
+        metric.format_value(heatmap.total()),
+        heatmap.active_days()
+    );
+    if let Some((date, value)) = heatmap.busiest_day() {
+        println!(
+            "   Busiest day: {} ({})",
+            date.format("%a %Y-%m-%d"),
+            metric.format_value(value)
+        );
+    }
+
+    Ok(())
+}
+
+fn execute_timesheet(
+    claude_dir: ClaudeDirectory,
+    options: TimesheetOptions,
+    timezone: &Timezone,
+    verbose: bool,
+) -> Result<()> {
+    use crate::claude::work_sessions::format_active_time;
+    use crate::claude::{ical, Timesheet, TimesheetGrouping, WorkSessionEvent};
+
+    let TimesheetOptions {
+        by,
+        idle_minutes,
+        period,
+        from,
+        to,
+        week,
+        quarter,
+        project,
+        format,
+    } = options;
+    let range = if period.is_none() && from.is_none() && week.is_none() && quarter.is_none() {
+        None
+    } else {
+        let period = resolve_timeline_period(
+            period.unwrap_or(TimelinePeriod::SevenDays),
+            from,
+            to,
+            week,
+            quarter,
+            timezone,
+        )?;
+        Some((period.start_time(timezone), period.end_time(timezone)))
+    };
+
+    let grouping = match by {
+        TimesheetGroupingArg::Day => TimesheetGrouping::Day,
+        TimesheetGroupingArg::Week => TimesheetGrouping::Week,
+    };
+    let idle_minutes = idle_minutes.unwrap_or_else(|| {
+        AppConfig::load_hierarchical(None, None)
+            .unwrap_or_default()
+            .work_sessions
+            .idle_threshold_minutes
+    });
+
+    let parser = ConversationParser::new(claude_dir);
+    let mut conversations = parser.parse_all_conversations()?;
+    if let Some(ref project) = project {
+        conversations.retain(|conv| conv.project_path.contains(project.as_str()));
+    }
+    if verbose {
+        eprintln!(
+            "⏱️  Detecting work sessions in {} conversations (idle threshold {} min)",
+            conversations.len(),
+            idle_minutes
+        );
+    }
+
+    let idle_threshold = chrono::Duration::minutes(idle_minutes as i64);
+    let timesheet = Timesheet::build(&conversations, idle_threshold, grouping, range, timezone);
+
+    match format {
+        TimesheetFormat::Ics => {
+            let events =
+                WorkSessionEvent::from_conversations(&conversations, idle_threshold, range);
+            print!("{}", ical::to_ics(&events, chrono::Utc::now()));
+        }
+        TimesheetFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&timesheet)?);
+        }
+        TimesheetFormat::Csv => {
+            println!("period,project,active_minutes,work_sessions");
+            for entry in &timesheet.entries {
+                println!(
+                    "{},{},{:.1},{}",
+                    entry.period,
+                    csv_field(&entry.project),
+                    entry.active_seconds as f64 / 60.0,
+                    entry.work_sessions
+                );
+            }
+        }
+        TimesheetFormat::Human => {
+            let unit = match grouping {
+                TimesheetGrouping::Day => "day",
+                TimesheetGrouping::Week => "week",
+            };
+            println!(
+                "⏱️  Timesheet by {} (idle threshold: {} min)",
+                unit, idle_minutes
+            );
+            if timesheet.entries.is_empty() {
+                println!();
+                println!("No activity found.");
+                return Ok(());
+            }
+
+            let width = timesheet
+                .entries
+                .iter()
+                .map(|entry| entry.project.len())
+                .max()
+                .unwrap_or(0);
+            let mut current_period = None;
+            for entry in &timesheet.entries {
+                if current_period != Some(&entry.period) {
+                    println!();
+                    println!("📅 {}", entry.period);
+                    current_period = Some(&entry.period);
+                }
+                println!(
+                    "   {:<width$}  {:>8}  {} work session(s)",
+                    entry.project,
+                    format_active_time(entry.active_seconds),
+                    entry.work_sessions,
+                    width = width
+                );
+            }
+
+            println!();
+            println!("📊 Totals");
+            for (project, seconds) in timesheet.project_totals() {
+                println!(
+                    "   {:<width$}  {:>8}",
+                    project,
+                    format_active_time(seconds),
+                    width = width
+                );
+            }
+            println!(
+                "   {:<width$}  {:>8}",
+                "Total",
+                format_active_time(timesheet.total_seconds()),
+                width = width
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn execute_digest(
+    claude_dir: ClaudeDirectory,
+    period: DigestPeriod,
+    project: Option<String>,
+    output: Option<PathBuf>,
+    timezone: &Timezone,
+    verbose: bool,
+) -> Result<()> {
+    use crate::claude::Digest;
+
+    let config = TimelineConfig {
+        period: match period {
+            DigestPeriod::Day => TimePeriod::LastDay,
+            DigestPeriod::Week => TimePeriod::LastWeek,
+        },
+        summary_depth: SummaryDepth::Detailed,
+        max_conversations_per_project: None,
+        include_empty_projects: false,
+        timezone: *timezone,
+    };
+
+    let parser = ConversationParser::new(claude_dir);
+    let mut conversations = parser.parse_all_conversations()?;
+    if let Some(ref project) = project {
+        conversations.retain(|conv| conv.project_path.contains(project.as_str()));
+    }
+    if verbose {
+        eprintln!(
+            "📝 Building {} digest from {} conversations",
+            config.period.label(timezone),
+            conversations.len()
+        );
+    }
+
+    let timeline = ActivityTimeline::create_filtered_timeline(conversations.clone(), config);
+    let markdown = Digest::build(&timeline, &conversations).to_markdown();
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, &markdown)?;
+            println!("📝 Digest written to {}", path.display());
+        }
+        None => print!("{}", markdown),
+    }
+
+    Ok(())
+}
+
+fn execute_commits(
+    claude_dir: ClaudeDirectory,
+    options: CommitsOptions,
+    timezone: &Timezone,
+    verbose: bool,
+) -> Result<()> {
+    use crate::claude::git_commits::correlate_commits;
+
+    let CommitsOptions {
+        session,
+        project,
+        since,
+        window_minutes,
+        format,
+    } = options;
+
+    let since = since
+        .as_deref()
+        .map(|since| parse_date_string(since, timezone))
+        .transpose()?;
+
+    let parser = ConversationParser::new(claude_dir);
+    let mut conversations = parser.parse_all_conversations()?;
+    if let Some(ref project) = project {
+        conversations.retain(|conv| conv.project_path.contains(project.as_str()));
+    }
+    if verbose {
+        eprintln!(
+            "🔗 Correlating {} conversations with git history",
+            conversations.len()
+        );
+    }
+
+    let mut linked = correlate_commits(
+        &conversations,
+        chrono::Duration::minutes(window_minutes as i64),
+    );
+    if let Some(since) = since {
+        linked.retain(|commit| commit.commit.timestamp >= since);
+    }
+    if let Some(ref session) = session {
+        linked.retain(|commit| {
+            commit
+                .sessions
+                .iter()
+                .any(|link| link.session_id.starts_with(session.as_str()))
+        });
+    }
+
+    if let CommitsFormat::Json = format {
+        println!("{}", serde_json::to_string_pretty(&linked)?);
+        return Ok(());
+    }
+
+    if linked.is_empty() {
+        println!("❌ No git commits linked to conversations");
+        return Ok(());
+    }
+
+    println!("🔗 {} commit(s) produced with Claude", linked.len());
+    for linked_commit in &linked {
+        let commit = &linked_commit.commit;
+        println!();
+        println!(
+            "{} {}  {}",
+            commit.short_hash(),
+            timezone.format(commit.timestamp, "%Y-%m-%d %H:%M"),
+            commit.subject
+        );
+        println!("   📁 {}", linked_commit.repository);
+        for link in &linked_commit.sessions {
+            let reasons = link
+                .reasons
+                .iter()
+                .map(|reason| reason.label())
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!("   💬 {} ({})", link.session_id, reasons);
+            for file in &link.files {
+                println!("      ✏️  {}", file);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn execute_blocks(
+    claude_dir: ClaudeDirectory,
+    active_only: bool,
+    recent: usize,
+    token_limit: Option<u64>,
+    format: BlocksFormat,
+    timezone: &Timezone,
+    verbose: bool,
+) -> Result<()> {
+    use crate::claude::pricing::format_tokens;
+    use crate::claude::usage_blocks::{largest_completed_block, usage_blocks, BLOCK_HOURS};
+    use crate::claude::work_sessions::format_active_time;
+
+    let parser = ConversationParser::new(claude_dir);
+    let conversations = parser.parse_all_conversations()?;
+    let blocks = usage_blocks(&conversations);
+    if verbose {
+        eprintln!(
+            "⏱️  Found {} usage blocks in {} conversations",
+            blocks.len(),
+            conversations.len()
+        );
+    }
+
+    let now = chrono::Utc::now();
+    let configured_limit = token_limit.is_some();
+    let token_limit = token_limit.or_else(|| largest_completed_block(&blocks, now));
+    let active = blocks.last().filter(|block| block.is_active(now));
+    let projection = active.map(|block| block.projection(now, token_limit));
+    let shown = if active_only {
+        active.map(std::slice::from_ref).unwrap_or_default()
+    } else {
+        &blocks[blocks.len().saturating_sub(recent)..]
+    };
+
+    if let BlocksFormat::Json = format {
+        let report = serde_json::json!({
+            "token_limit": token_limit,
+            "blocks": shown,
+            "active": active.map(|block| serde_json::json!({
+                "block": block,
+                "projection": projection,
+            })),
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    if blocks.is_empty() {
+        println!("❌ No token usage found");
+        return Ok(());
+    }
+
+    if !active_only {
+        println!("⏱️  Usage blocks ({}-hour windows)", BLOCK_HOURS);
+        for block in shown {
+            println!(
+                "   {} - {}  {:>14}  {:>8}  {:>4} msgs{}",
+                timezone.format(block.start, "%Y-%m-%d %H:%M"),
+                timezone.format(block.end, "%H:%M"),
+                format_tokens(block.tokens),
+                format!("${:.2}", block.cost),
+                block.messages,
+                if active.is_some_and(|a| a == block) {
+                    "  ◀ active"
+                } else {
+                    ""
+                }
+            );
+        }
+        println!();
+    }
+
+    let (Some(block), Some(projection)) = (active, projection) else {
+        println!("⚪ No active block");
+        return Ok(());
+    };
+    println!(
+        "🟢 Active block since {} ({} left)",
+        timezone.format(block.start, "%H:%M"),
+        format_active_time(projection.remaining_minutes * 60)
+    );
+    println!(
+        "   Used: {} (${:.2})",
+        format_tokens(block.tokens),
+        block.cost
+    );
+    if let Some(rate) = projection.burn_rate {
+        println!("   Burn rate: {}/min", format_tokens(rate as u64));
+        println!(
+            "   Projected: {} by {}",
+            format_tokens(projection.projected_tokens),
+            timezone.format(block.end, "%H:%M")
+        );
+    }
+    if let (Some(limit), Some(ratio)) = (token_limit, projection.limit_ratio(block.tokens)) {
+        println!(
+            "   Limit: {} ({:.0}% used{})",
+            format_tokens(limit),
+            ratio * 100.0,
+            if configured_limit {
+                ""
+            } else {
+                ", largest previous block"
+            }
+        );
+    }
+    match projection.exhausted_at {
+        Some(at) if at <= now => {
+            println!("   ⚠️  Limit reached at {}", timezone.format(at, "%H:%M"))
+        }
+        Some(at) => println!(
+            "   ⚠️  Projected to run out at {}",
+            timezone.format(at, "%H:%M")
+        ),
+        None => {}
+    }
+
+    Ok(())
 }
 
-/// Handle analytics export
-fn handle_export(
-    analytics: &crate::claude::ConversationAnalytics,
-    format: ExportFormat,
+fn execute_budget(
+    claude_dir: ClaudeDirectory,
+    quiet: bool,
+    format: BudgetFormat,
+    timezone: &Timezone,
     verbose: bool,
 ) -> Result<()> {
-    let timestamp = analytics.generated_at.format("%Y%m%d_%H%M%S");
+    use crate::claude::BudgetStatus;
+    use crate::errors::ClaudeToolsError;
+
+    let config = AppConfig::load_hierarchical(None, None)
+        .map_err(|e| ClaudeToolsError::Config(e.to_string()))?;
+    if config.budgets.is_empty() {
+        return Err(ClaudeToolsError::Config(
+            "No budgets configured: add a \"budgets\" list to the config file (see 'claude-tools budget --help')".to_string(),
+        ));
+    }
+
+    let parser = ConversationParser::new(claude_dir);
+    let conversations = parser.parse_all_conversations()?;
+    if verbose {
+        eprintln!(
+            "💰 Checking {} budget(s) against {} conversations",
+            config.budgets.len(),
+            conversations.len()
+        );
+    }
+
+    let statuses = BudgetStatus::evaluate(
+        &config.budgets,
+        &conversations,
+        chrono::Utc::now(),
+        timezone,
+    );
+    let exceeded = statuses
+        .iter()
+        .filter(|status| status.is_exceeded())
+        .count();
 
     match format {
-        ExportFormat::Json => {
-            let filename = format!("claude_analytics_{}.json", timestamp);
-            let json_data = serde_json::to_string_pretty(analytics)?;
-            let data_size = json_data.len();
-            std::fs::write(&filename, json_data)?;
-            println!("📄 Analytics exported to: {}", filename);
-            if verbose {
-                println!("   Format: JSON");
-                println!("   Size: {} bytes", data_size);
-            }
-        }
-        ExportFormat::Csv => {
-            let filename = format!("claude_analytics_{}.csv", timestamp);
-            let csv_data = generate_csv_export(analytics)?;
-            let row_count = csv_data.lines().count();
-            std::fs::write(&filename, csv_data)?;
-            println!("📄 Analytics exported to: {}", filename);
-            if verbose {
-                println!("   Format: CSV");
-                println!("   Rows: {}", row_count);
+        BudgetFormat::Json => println!("{}", serde_json::to_string_pretty(&statuses)?),
+        BudgetFormat::Human => {
+            const BAR_WIDTH: usize = 20;
+            for status in statuses.iter().filter(|s| !quiet || s.is_exceeded()) {
+                let filled = ((status.percent_used() / 100.0 * BAR_WIDTH as f64).round() as usize)
+                    .min(BAR_WIDTH);
+                let since = status
+                    .period_start
+                    .map(|start| format!(" since {}", timezone.format(start, "%Y-%m-%d")))
+                    .unwrap_or_default();
+                println!(
+                    "{} {:<24} {}{} {}{}",
+                    if status.is_exceeded() { "🔴" } else { "🟢" },
+                    status.budget.label(),
+                    "█".repeat(filled),
+                    "░".repeat(BAR_WIDTH - filled),
+                    status.summary(),
+                    since
+                );
             }
         }
     }
 
+    if exceeded > 0 {
+        return Err(ClaudeToolsError::BudgetExceeded { exceeded });
+    }
     Ok(())
 }
 
-/// Generate CSV export of analytics data
-fn generate_csv_export(analytics: &crate::claude::ConversationAnalytics) -> Result<String> {
-    let mut csv_content = String::new();
-
-    // Basic stats section
-    csv_content.push_str("Section,Metric,Value\n");
-    csv_content.push_str(&format!(
-        "Basic,Total Conversations,{}\n",
-        analytics.basic_stats.total_conversations
-    ));
-    csv_content.push_str(&format!(
-        "Basic,Total Messages,{}\n",
-        analytics.basic_stats.total_messages
-    ));
-    csv_content.push_str(&format!(
-        "Basic,User Messages,{}\n",
-        analytics.basic_stats.total_user_messages
-    ));
-    csv_content.push_str(&format!(
-        "Basic,Assistant Messages,{}\n",
-        analytics.basic_stats.total_assistant_messages
-    ));
-    csv_content.push_str(&format!(
-        "Basic,System Messages,{}\n",
-        analytics.basic_stats.total_system_messages
-    ));
-    csv_content.push_str(&format!(
-        "Basic,Tool Uses,{}\n",
-        analytics.basic_stats.total_tool_uses
-    ));
-    csv_content.push_str(&format!(
-        "Basic,Avg Messages per Conversation,{:.2}\n",
-        analytics.basic_stats.average_messages_per_conversation
-    ));
-
-    // Model usage
-    csv_content.push_str("\nModel,Usage Count,Percentage\n");
-    for model in &analytics.model_analytics.top_models {
-        csv_content.push_str(&format!(
-            "{},{},{:.2}\n",
-            model.model_name, model.usage_count, model.percentage
-        ));
-    }
-
-    // Tool usage
-    csv_content.push_str("\nTool,Usage Count,Percentage\n");
-    for tool in &analytics.tool_analytics.top_tools {
-        csv_content.push_str(&format!(
-            "{},{},{:.2}\n",
-            tool.tool_name, tool.usage_count, tool.percentage
-        ));
+/// Quote a CSV field if it contains a delimiter, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
     }
+}
 
-    // Project usage
-    csv_content.push_str("\nProject,Conversations,Messages,Percentage\n");
-    for project in &analytics.project_analytics.top_projects {
-        csv_content.push_str(&format!(
-            "{},{},{},{:.2}\n",
-            project.project_name,
-            project.conversation_count,
-            project.message_count,
-            project.percentage
-        ));
+/// Turn the timeline period arguments into a `TimePeriod`; explicit ranges win over --period
+fn resolve_timeline_period(
+    period: TimelinePeriod,
+    from: Option<String>,
+    to: Option<String>,
+    week: Option<String>,
+    quarter: Option<String>,
+    timezone: &Timezone,
+) -> Result<TimePeriod> {
+    use crate::errors::ClaudeToolsError;
+
+    if let Some(week) = week {
+        return TimePeriod::parse_iso_week(&week).map_err(ClaudeToolsError::Config);
+    }
+    if let Some(quarter) = quarter {
+        return TimePeriod::parse_quarter(&quarter).map_err(ClaudeToolsError::Config);
+    }
+    if let Some(from) = from {
+        let start = parse_date_string(&from, timezone)?;
+        let end = match to {
+            Some(to) => {
+                let end = parse_date_string(&to, timezone)?;
+                // A bare date means "through the end of that day"
+                if !to.contains(':') && timezone.naive(end).time() == chrono::NaiveTime::MIN {
+                    end + chrono::Duration::days(1) - chrono::Duration::nanoseconds(1)
+                } else {
+                    end
+                }
+            }
+            None => chrono::Utc::now(),
+        };
+        if end < start {
+            return Err(ClaudeToolsError::Config(format!(
+                "--to ({}) is before --from ({})",
+                end.format("%Y-%m-%d %H:%M"),
+                start.format("%Y-%m-%d %H:%M")
+            )));
+        }
+        return Ok(TimePeriod::Custom { start, end });
     }
 
-    Ok(csv_content)
+    Ok(match period {
+        TimelinePeriod::Day => TimePeriod::LastDay,
+        TimelinePeriod::TwoDay => TimePeriod::LastTwoDay,
+        TimelinePeriod::SevenDays => TimePeriod::LastWeek,
+        TimelinePeriod::ThirtyDays => TimePeriod::LastMonth,
+        TimelinePeriod::ThisWeek => TimePeriod::ThisWeek,
+        TimelinePeriod::LastWeek => TimePeriod::PreviousWeek,
+        TimelinePeriod::ThisMonth => TimePeriod::ThisMonth,
+        TimelinePeriod::LastMonth => TimePeriod::PreviousMonth,
+    })
 }
 
 fn execute_timeline(
     claude_dir: ClaudeDirectory,
-    period: TimelinePeriod,
-    detailed: bool,
-    format: OutputFormat,
-    export: Option<ExportFormat>,
-    output: Option<String>,
-    max_conversations: usize,
-    include_empty: bool,
+    options: TimelineOptions,
+    timezone: &Timezone,
     verbose: bool,
 ) -> Result<()> {
+    let TimelineOptions {
+        period,
+        from,
+        to,
+        week,
+        quarter,
+        detailed,
+        format,
+        export,
+        output,
+        max_conversations,
+        include_empty,
+        commits,
+        compare,
+    } = options;
+    let timeline_period = resolve_timeline_period(period, from, to, week, quarter, timezone)?;
+
     if verbose {
-        eprintln!("Generating activity timeline for {:?} period...", period);
+        eprintln!(
+            "Generating activity timeline for {} ({} to {})...",
+            timeline_period.label(timezone),
+            timeline_period
+                .start_time(timezone)
+                .format("%Y-%m-%d %H:%M"),
+            timeline_period.end_time(timezone).format("%Y-%m-%d %H:%M")
+        );
     }
 
-    // Convert CLI period to internal period
-    let timeline_period = match period {
-        TimelinePeriod::Day => TimePeriod::LastDay,
-        TimelinePeriod::TwoDay => TimePeriod::LastTwoDay,
-        TimelinePeriod::Week => TimePeriod::LastWeek,
-        TimelinePeriod::Month => TimePeriod::LastMonth,
-    };
-
     // Create timeline configuration
     let config = TimelineConfig {
         period: timeline_period,
@@ -832,6 +2518,7 @@ fn execute_timeline(
         },
         max_conversations_per_project: Some(max_conversations),
         include_empty_projects: include_empty,
+        timezone: *timezone,
     };
 
     // Parse conversations and generate timeline
@@ -845,11 +2532,46 @@ fn execute_timeline(
         );
     }
 
-    let timeline = ActivityTimeline::create_filtered_timeline(conversations, config);
+    let linked_commits = commits.then(|| {
+        crate::claude::git_commits::correlate_commits(
+            &conversations,
+            chrono::Duration::minutes(crate::claude::git_commits::DEFAULT_COMMIT_WINDOW_MINUTES),
+        )
+    });
 
-    // Handle export first if requested
     if let Some(export_format) = export {
-        return handle_timeline_export(&timeline, export_format, output, verbose);
+        return handle_timeline_export(
+            conversations,
+            config,
+            linked_commits,
+            export_format,
+            output,
+            verbose,
+        );
+    }
+
+    let previous_timeline = compare.map(|TimelineCompareArg::Previous| {
+        ActivityTimeline::create_filtered_timeline(
+            conversations.clone(),
+            TimelineConfig {
+                period: config.period.previous(timezone),
+                ..config.clone()
+            },
+        )
+    });
+
+    let mut timeline = ActivityTimeline::create_filtered_timeline(conversations, config);
+    if let Some(previous_timeline) = previous_timeline {
+        let comparison = TimelineComparison::between(&timeline, &previous_timeline);
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&comparison)?),
+            OutputFormat::Markdown => display_timeline_comparison_markdown(&comparison),
+            OutputFormat::Human | OutputFormat::Text => display_timeline_comparison(&comparison),
+        }
+        return Ok(());
+    }
+    if let Some(linked_commits) = linked_commits {
+        timeline.attach_agent_commits(&linked_commits);
     }
 
     // Display timeline based on format
@@ -865,7 +2587,10 @@ fn execute_timeline(
 
 /// Display timeline in human-readable format
 fn display_timeline_human(timeline: &ActivityTimeline, detailed: bool) {
-    println!("📊 Activity Timeline - {}", timeline.config.period.label());
+    println!(
+        "📊 Activity Timeline - {}",
+        timeline.config.period.label(&timeline.config.timezone)
+    );
     println!(
         "   Generated: {}",
         timeline.generated_at.format("%Y-%m-%d %H:%M:%S")
@@ -902,6 +2627,9 @@ fn display_timeline_human(timeline: &ActivityTimeline, detailed: bool) {
             "   📊 {} conversations, {} messages",
             project.stats.conversation_count, project.stats.total_messages
         );
+        if let Some(commits) = project.agent_commits {
+            println!("   🔗 {} agent-assisted commit(s)", commits);
+        }
 
         if detailed {
             println!(
@@ -962,6 +2690,134 @@ fn display_timeline_human(timeline: &ActivityTimeline, detailed: bool) {
     }
 }
 
+/// Display a period-over-period comparison as a diff table
+fn display_timeline_comparison(comparison: &TimelineComparison) {
+    println!(
+        "📊 {} vs {}",
+        comparison.current_period.label(&comparison.timezone),
+        comparison.previous_period.label(&comparison.timezone)
+    );
+    println!();
+
+    let rows = [
+        ("Active projects", comparison.active_projects),
+        ("Conversations", comparison.conversations),
+        ("Messages", comparison.messages),
+        ("Tokens", comparison.tokens),
+        ("Tool calls", comparison.tool_calls),
+    ];
+    println!("   {:<16} {:>10} {:>10}  Change", "", "Previous", "Current");
+    for (label, delta) in rows {
+        println!(
+            "   {:<16} {:>10} {:>10}  {}",
+            label,
+            delta.previous,
+            delta.current,
+            delta.change_label()
+        );
+    }
+    println!();
+
+    if comparison.projects.is_empty() {
+        println!("💤 No activity in either period");
+        return;
+    }
+
+    println!("📁 Projects:");
+    println!(
+        "   {:<32} {:<7} {:>16} {:>18} {:>14}",
+        "", "Status", "Messages", "Tokens", "Tool calls"
+    );
+    for project in &comparison.projects {
+        let marker = match project.change {
+            ProjectChange::Appeared => "+",
+            ProjectChange::WentQuiet => "-",
+            ProjectChange::Continued => " ",
+        };
+        println!(
+            " {} {:<32} {:<7} {:>16} {:>18} {:>14}",
+            marker,
+            project.project_path,
+            project.change.label(),
+            project.messages.change_label(),
+            project.tokens.change_label(),
+            project.tool_calls.change_label()
+        );
+        if !project.new_topics.is_empty() {
+            println!("      ↑ Topics: {}", project.new_topics.join(", "));
+        }
+        if !project.dropped_topics.is_empty() {
+            println!("      ↓ Topics: {}", project.dropped_topics.join(", "));
+        }
+    }
+
+    let changed_tools: Vec<_> = comparison
+        .tools
+        .iter()
+        .filter(|tool| tool.uses.change() != 0)
+        .take(5)
+        .collect();
+    if !changed_tools.is_empty() {
+        println!();
+        println!("🛠️  Tool changes:");
+        for tool in changed_tools {
+            println!(
+                "   {:<16} {:>6} → {:<6} {}",
+                tool.name,
+                tool.uses.previous,
+                tool.uses.current,
+                tool.uses.change_label()
+            );
+        }
+    }
+}
+
+/// Display a period-over-period comparison as Markdown tables
+fn display_timeline_comparison_markdown(comparison: &TimelineComparison) {
+    println!(
+        "# {} vs {}",
+        comparison.current_period.label(&comparison.timezone),
+        comparison.previous_period.label(&comparison.timezone)
+    );
+    println!();
+    println!("| Metric | Previous | Current | Change |");
+    println!("|---|---:|---:|---:|");
+    for (label, delta) in [
+        ("Active projects", comparison.active_projects),
+        ("Conversations", comparison.conversations),
+        ("Messages", comparison.messages),
+        ("Tokens", comparison.tokens),
+        ("Tool calls", comparison.tool_calls),
+    ] {
+        println!(
+            "| {} | {} | {} | {} |",
+            label,
+            delta.previous,
+            delta.current,
+            delta.change_label()
+        );
+    }
+
+    if comparison.projects.is_empty() {
+        return;
+    }
+    println!();
+    println!("| Project | Status | Messages | Tokens | Tool calls | New topics | Dropped topics |");
+    println!("|---|---|---:|---:|---:|---|---|");
+    for project in &comparison.projects {
+        println!(
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            project.project_path,
+            project.change.label(),
+            project.messages.change_label(),
+            project.tokens.change_label(),
+            project.tool_calls.change_label(),
+            project.new_topics.join(", "),
+            project.dropped_topics.join(", ")
+        );
+    }
+}
+
 /// Display timeline in JSON format
 fn display_timeline_json(timeline: &ActivityTimeline) -> Result<()> {
     let json = serde_json::to_string_pretty(timeline)?;
@@ -971,7 +2827,10 @@ fn display_timeline_json(timeline: &ActivityTimeline) -> Result<()> {
 
 /// Display timeline in markdown format
 fn display_timeline_markdown(timeline: &ActivityTimeline, detailed: bool) {
-    println!("# Activity Timeline - {}", timeline.config.period.label());
+    println!(
+        "# Activity Timeline - {}",
+        timeline.config.period.label(&timeline.config.timezone)
+    );
     println!();
     println!(
         "**Generated:** {}",
@@ -1009,6 +2868,9 @@ fn display_timeline_markdown(timeline: &ActivityTimeline, detailed: bool) {
         println!();
         println!("- **Conversations:** {}", project.stats.conversation_count);
         println!("- **Messages:** {}", project.stats.total_messages);
+        if let Some(commits) = project.agent_commits {
+            println!("- **Agent-assisted commits:** {}", commits);
+        }
 
         if detailed {
             println!(
@@ -1049,7 +2911,10 @@ fn display_timeline_markdown(timeline: &ActivityTimeline, detailed: bool) {
 
 /// Display timeline in plain text format
 fn display_timeline_text(timeline: &ActivityTimeline, detailed: bool) {
-    println!("Activity Timeline - {}", timeline.config.period.label());
+    println!(
+        "Activity Timeline - {}",
+        timeline.config.period.label(&timeline.config.timezone)
+    );
     println!(
         "Generated: {}",
         timeline.generated_at.format("%Y-%m-%d %H:%M:%S")
@@ -1085,6 +2950,9 @@ fn display_timeline_text(timeline: &ActivityTimeline, detailed: bool) {
             "   {} conversations, {} messages",
             project.stats.conversation_count, project.stats.total_messages
         );
+        if let Some(commits) = project.agent_commits {
+            println!("   {} agent-assisted commit(s)", commits);
+        }
 
         if detailed {
             println!(
@@ -1125,33 +2993,73 @@ fn display_timeline_text(timeline: &ActivityTimeline, detailed: bool) {
 
 /// Handle timeline export functionality
 fn handle_timeline_export(
-    timeline: &ActivityTimeline,
-    export_format: ExportFormat,
+    conversations: Vec<crate::claude::Conversation>,
+    config: TimelineConfig,
+    linked_commits: Option<Vec<crate::claude::LinkedCommit>>,
+    export_format: TimelineExportFormat,
     output: Option<String>,
     verbose: bool,
 ) -> Result<()> {
-    let timestamp = timeline.generated_at.format("%Y%m%d_%H%M%S");
+    use crate::claude::{ical, WorkSessionEvent};
 
-    let (filename, data) = match export_format {
-        ExportFormat::Json => {
-            let filename = output.unwrap_or_else(|| format!("timeline_{}.json", timestamp));
-            let data = serde_json::to_string_pretty(timeline)?;
-            (filename, data)
+    let (period, timezone) = (config.period, config.timezone);
+    let build_timeline = |conversations, config| {
+        let mut timeline = ActivityTimeline::create_filtered_timeline(conversations, config);
+        if let Some(ref linked_commits) = linked_commits {
+            timeline.attach_agent_commits(linked_commits);
         }
-        ExportFormat::Csv => {
-            let filename = output.unwrap_or_else(|| format!("timeline_{}.csv", timestamp));
-            let data = generate_timeline_csv_export(timeline)?;
-            (filename, data)
+        timeline
+    };
+    let now = chrono::Utc::now();
+    let timestamp = now.format("%Y%m%d_%H%M%S");
+
+    let (filename, data, exported, details) = match export_format {
+        TimelineExportFormat::Json => {
+            let timeline = build_timeline(conversations, config);
+            (
+                output.unwrap_or_else(|| format!("timeline_{}.json", timestamp)),
+                serde_json::to_string_pretty(&timeline)?,
+                "📄 Timeline".to_string(),
+                format!("Projects: {}", timeline.projects.len()),
+            )
+        }
+        TimelineExportFormat::Csv => {
+            let timeline = build_timeline(conversations, config);
+            (
+                output.unwrap_or_else(|| format!("timeline_{}.csv", timestamp)),
+                generate_timeline_csv_export(&timeline)?,
+                "📄 Timeline".to_string(),
+                format!("Projects: {}", timeline.projects.len()),
+            )
+        }
+        TimelineExportFormat::Ics => {
+            // Work sessions need every message timestamp, which the timeline summarizes away
+            let idle_minutes = AppConfig::load_hierarchical(None, None)
+                .unwrap_or_default()
+                .work_sessions
+                .idle_threshold_minutes;
+            let events = WorkSessionEvent::from_conversations(
+                &conversations,
+                chrono::Duration::minutes(idle_minutes as i64),
+                Some((period.start_time(&timezone), period.end_time(&timezone))),
+            );
+            (
+                output.unwrap_or_else(|| format!("work_sessions_{}.ics", timestamp)),
+                ical::to_ics(&events, now),
+                format!("📅 {} work session(s)", events.len()),
+                format!("Idle threshold: {} min", idle_minutes),
+            )
         }
     };
 
     std::fs::write(&filename, &data)?;
 
-    println!("📄 Timeline exported to: {}", filename);
+    println!("{} exported to: {}", exported, filename);
     if verbose {
         println!("   Format: {:?}", export_format);
+        println!("   Period: {}", period.label(&timezone));
         println!("   Size: {} bytes", data.len());
-        println!("   Projects: {}", timeline.projects.len());
+        println!("   {}", details);
     }
 
     Ok(())
@@ -1203,7 +3111,11 @@ fn generate_timeline_csv_export(timeline: &ActivityTimeline) -> Result<String> {
     Ok(csv_content)
 }
 
-fn execute_interactive(claude_dir: ClaudeDirectory, verbose: bool) -> Result<()> {
+fn execute_interactive(
+    claude_dir: ClaudeDirectory,
+    timezone: Timezone,
+    verbose: bool,
+) -> Result<()> {
     if verbose {
         eprintln!("Starting interactive mode");
     }
@@ -1216,7 +3128,7 @@ fn execute_interactive(claude_dir: ClaudeDirectory, verbose: bool) -> Result<()>
     let mut terminal = Terminal::new(backend)?;
 
     // Create app and event handler
-    let mut app = App::new(claude_dir)?;
+    let mut app = App::new(claude_dir, timezone)?;
     let events = EventHandler::new(Duration::from_millis(250));
 
     // Main event loop
@@ -1266,7 +3178,9 @@ fn run_app<B: ratatui::backend::Backend>(
             }
             Ok(Event::ConfigChanged) => {
                 // Configuration change events - reload config
-                match AppConfig::load_from_file(AppConfig::default_config_path().unwrap_or_default()) {
+                match AppConfig::load_from_file(
+                    AppConfig::default_config_path().unwrap_or_default(),
+                ) {
                     Ok(config) => {
                         if let Err(e) = app.update_config(config) {
                             eprintln!("Failed to update config: {}", e);
@@ -1285,55 +3199,43 @@ fn run_app<B: ratatui::backend::Backend>(
     Ok(())
 }
 
-/// Handle conversation export functionality
-fn handle_conversation_export(
-    conversation: &crate::claude::Conversation,
-    export_format: ConversationExportFormat,
-    output: Option<String>,
+/// Export settings for writing conversations to `output_path`
+fn conversation_export_config(
+    export_format: &ConversationExportFormat,
+    output_path: PathBuf,
     include_metadata: bool,
     include_tools: bool,
     include_timestamps: bool,
-    verbose: bool,
-) -> Result<()> {
-    // Convert CLI export format to internal format
-    let internal_format = match export_format {
-        ConversationExportFormat::Markdown => crate::claude::export::ExportFormat::Markdown,
-        ConversationExportFormat::Html => crate::claude::export::ExportFormat::Html,
-        ConversationExportFormat::Pdf => crate::claude::export::ExportFormat::Pdf,
-        ConversationExportFormat::Json => crate::claude::export::ExportFormat::Json,
-    };
-
-    // Determine output path
-    let output_path = match output {
-        Some(path) => PathBuf::from(path),
-        None => {
-            let extension = match export_format {
-                ConversationExportFormat::Markdown => "md",
-                ConversationExportFormat::Html => "html",
-                ConversationExportFormat::Pdf => "pdf",
-                ConversationExportFormat::Json => "json",
-            };
-            PathBuf::from(format!(
-                "conversation_{}.{}",
-                &conversation.session_id[..8],
-                extension
-            ))
-        }
-    };
-
-    // Create export configuration
-    let config = ExportConfig {
-        output_path: output_path.clone(),
-        format: internal_format,
+    timezone: Timezone,
+) -> ExportConfig {
+    ExportConfig {
+        output_path,
+        format: internal_export_format(export_format),
         include_metadata,
         include_tool_usage: include_tools,
         include_timestamps,
         template_path: None,
+        title: None,
+        timezone,
+    }
+}
+
+/// Handle conversation export functionality
+fn handle_conversation_export(
+    conversation: &crate::claude::Conversation,
+    config: ExportConfig,
+    verbose: bool,
+) -> Result<()> {
+    let config = ExportConfig {
         title: Some(format!("Conversation: {}", conversation.session_id)),
+        ..config
     };
 
     if verbose {
-        eprintln!("Exporting conversation to: {}", output_path.display());
+        eprintln!(
+            "Exporting conversation to: {}",
+            config.output_path.display()
+        );
     }
 
     // Create exporter and export
@@ -1356,6 +3258,57 @@ fn handle_conversation_export(
     }
 }
 
+/// Convert a CLI export format to the internal format
+fn internal_export_format(
+    format: &ConversationExportFormat,
+) -> crate::claude::export::ExportFormat {
+    match format {
+        ConversationExportFormat::Markdown => crate::claude::export::ExportFormat::Markdown,
+        ConversationExportFormat::Html => crate::claude::export::ExportFormat::Html,
+        ConversationExportFormat::Pdf => crate::claude::export::ExportFormat::Pdf,
+        ConversationExportFormat::Json => crate::claude::export::ExportFormat::Json,
+    }
+}
+
+/// Export every conversation in a collection into the directory `config.output_path`
+fn execute_collection_export(
+    claude_dir: ClaudeDirectory,
+    collection: &str,
+    config: ExportConfig,
+    verbose: bool,
+) -> Result<()> {
+    let parser = ConversationParser::new(claude_dir);
+    let conversations = collection_conversations(&parser, collection)?;
+    if conversations.is_empty() {
+        println!("❌ Collection '{}' has no conversations", collection);
+        return Ok(());
+    }
+
+    let output_dir = config.output_path.clone();
+    std::fs::create_dir_all(&output_dir)?;
+
+    for conversation in &conversations {
+        let output_path = output_dir.join(format!(
+            "conversation_{}.{}",
+            conversation.session_id,
+            config.format.extension()
+        ));
+        let config = ExportConfig {
+            output_path,
+            ..config.clone()
+        };
+        handle_conversation_export(conversation, config, verbose)?;
+    }
+
+    println!(
+        "📚 Exported {} conversation(s) from '{}' to {}",
+        conversations.len(),
+        collection,
+        output_dir.display()
+    );
+    Ok(())
+}
+
 fn execute_mcp(action: McpAction, verbose: bool) -> Result<()> {
     match action {
         McpAction::List {
@@ -1663,8 +3616,9 @@ fn display_servers_markdown(servers: &[McpServer], detailed: bool) {
 /// Parse natural language date strings into DateTime<Utc>
 fn parse_date_string(
     date_str: &str,
+    timezone: &Timezone,
 ) -> std::result::Result<chrono::DateTime<chrono::Utc>, crate::errors::ClaudeToolsError> {
-    use chrono::{Duration, NaiveDate, TimeZone, Utc};
+    use chrono::{Duration, NaiveDate, Utc};
 
     let date_str = date_str.trim().to_lowercase();
 
@@ -1710,7 +3664,7 @@ fn parse_date_string(
     // Try ISO 8601 format (YYYY-MM-DD)
     if let Ok(naive_date) = NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
         if let Some(datetime) = naive_date.and_hms_opt(0, 0, 0) {
-            return Ok(Utc.from_utc_datetime(&datetime));
+            return Ok(timezone.to_utc(datetime));
         }
     }
 
@@ -1718,7 +3672,7 @@ fn parse_date_string(
     if let Ok(naive_datetime) =
         chrono::NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%d %H:%M:%S")
     {
-        return Ok(Utc.from_utc_datetime(&naive_datetime));
+        return Ok(timezone.to_utc(naive_datetime));
     }
 
     // Try other common formats
@@ -1735,12 +3689,12 @@ fn parse_date_string(
     for format in &formats {
         if format.contains("%H") {
             if let Ok(naive_datetime) = chrono::NaiveDateTime::parse_from_str(&date_str, format) {
-                return Ok(Utc.from_utc_datetime(&naive_datetime));
+                return Ok(timezone.to_utc(naive_datetime));
             }
         } else {
             if let Ok(naive_date) = NaiveDate::parse_from_str(&date_str, format) {
                 if let Some(datetime) = naive_date.and_hms_opt(0, 0, 0) {
-                    return Ok(Utc.from_utc_datetime(&datetime));
+                    return Ok(timezone.to_utc(datetime));
                 }
             }
         }
@@ -1,3 +1,4 @@
+use crate::claude::{Budget, RankingWeights};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -16,6 +17,19 @@ pub struct AppConfig {
     pub timeline: TimelineConfig,
     /// User interface preferences
     pub ui: UiConfig,
+    /// Search ranking settings
+    #[serde(default)]
+    pub search: SearchConfig,
+    /// Work-session detection settings
+    #[serde(default)]
+    pub work_sessions: WorkSessionConfig,
+    /// Usage budgets checked by `claude-tools budget` and the TUI status bar
+    #[serde(default)]
+    pub budgets: Vec<Budget>,
+    /// Time zone for days and hours in every command, export and view:
+    /// `local`, `UTC` or an IANA name. System local time when unset.
+    #[serde(default)]
+    pub timezone: Option<String>,
 }
 
 /// Real-time update configuration
@@ -59,6 +73,35 @@ pub struct UiConfig {
     pub status_message_duration_ms: u64,
 }
 
+/// Search ranking (see `RankingWeights`) and matching configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    /// Multiplier for summed TF-IDF term scores
+    pub term_weight: f64,
+    /// Score added for boolean query terms found in the summary
+    pub summary_weight: f64,
+    /// Maximum recency boost for conversations updated today
+    pub recency_boost: f64,
+    /// Days over which the recency boost decays to nothing
+    pub recency_window_days: f64,
+    /// Multiplier for conversations within the preferred length range
+    pub length_boost: f64,
+    /// Preferred conversation length range, in messages
+    pub length_min_messages: usize,
+    pub length_max_messages: usize,
+    /// Stem English words so `parsing` also finds `parses`
+    pub stemming: bool,
+}
+
+/// Work-session detection configuration (timesheets)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkSessionConfig {
+    /// Gap between messages, in minutes, after which a work session ends
+    pub idle_threshold_minutes: u64,
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -66,6 +109,50 @@ impl Default for AppConfig {
             realtime: RealtimeConfig::default(),
             timeline: TimelineConfig::default(),
             ui: UiConfig::default(),
+            search: SearchConfig::default(),
+            work_sessions: WorkSessionConfig::default(),
+            budgets: Vec::new(),
+            timezone: None,
+        }
+    }
+}
+
+impl Default for WorkSessionConfig {
+    fn default() -> Self {
+        Self {
+            idle_threshold_minutes: crate::claude::work_sessions::DEFAULT_IDLE_THRESHOLD_MINUTES
+                as u64,
+        }
+    }
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        let weights = RankingWeights::default();
+        Self {
+            term_weight: weights.term_weight,
+            summary_weight: weights.summary_weight,
+            recency_boost: weights.recency_boost,
+            recency_window_days: weights.recency_window_days,
+            length_boost: weights.length_boost,
+            length_min_messages: weights.length_min_messages,
+            length_max_messages: weights.length_max_messages,
+            stemming: true,
+        }
+    }
+}
+
+impl SearchConfig {
+    /// Ranking weights for the search engine
+    pub fn ranking_weights(&self) -> RankingWeights {
+        RankingWeights {
+            term_weight: self.term_weight,
+            summary_weight: self.summary_weight,
+            recency_boost: self.recency_boost,
+            recency_window_days: self.recency_window_days,
+            length_boost: self.length_boost,
+            length_min_messages: self.length_min_messages,
+            length_max_messages: self.length_max_messages,
         }
     }
 }
@@ -215,6 +302,63 @@ impl AppConfig {
             ));
         }
 
+        if let Some(timezone) = &self.timezone {
+            crate::claude::Timezone::parse(timezone).map_err(|e| anyhow::anyhow!(e))?;
+        }
+
+        // Validate search ranking config
+        let search = &self.search;
+        let weights = [
+            ("term_weight", search.term_weight),
+            ("summary_weight", search.summary_weight),
+            ("recency_boost", search.recency_boost),
+            ("length_boost", search.length_boost),
+        ];
+        for (name, value) in weights {
+            if !value.is_finite() || value < 0.0 {
+                return Err(anyhow::anyhow!(
+                    "Search {} must be a non-negative number, got: {}",
+                    name,
+                    value
+                ));
+            }
+        }
+
+        if !search.recency_window_days.is_finite() || search.recency_window_days <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "Search recency window must be positive, got: {} days",
+                search.recency_window_days
+            ));
+        }
+
+        if search.length_min_messages > search.length_max_messages {
+            return Err(anyhow::anyhow!(
+                "Search length range is empty: {}..{} messages",
+                search.length_min_messages,
+                search.length_max_messages
+            ));
+        }
+
+        // Validate budgets
+        for budget in &self.budgets {
+            if !budget.limit.is_finite() || budget.limit <= 0.0 {
+                return Err(anyhow::anyhow!(
+                    "Budget '{}' limit must be positive, got: {}",
+                    budget.label(),
+                    budget.limit
+                ));
+            }
+        }
+
+        // Validate work-session config
+        let idle_minutes = self.work_sessions.idle_threshold_minutes;
+        if !(1..=1440).contains(&idle_minutes) {
+            return Err(anyhow::anyhow!(
+                "Work-session idle threshold must be between 1 and 1440 minutes, got: {}",
+                idle_minutes
+            ));
+        }
+
         Ok(())
     }
 
@@ -302,6 +446,18 @@ impl AppConfig {
             realtime: Self::merge_realtime_config(base.realtime, override_config.realtime),
             timeline: Self::merge_timeline_config(base.timeline, override_config.timeline),
             ui: Self::merge_ui_config(base.ui, override_config.ui),
+            search: Self::merge_search_config(base.search, override_config.search),
+            work_sessions: Self::merge_work_session_config(
+                base.work_sessions,
+                override_config.work_sessions,
+            ),
+            // Budgets are replaced as a whole rather than merged one by one
+            budgets: if override_config.budgets.is_empty() {
+                base.budgets
+            } else {
+                override_config.budgets
+            },
+            timezone: override_config.timezone.or(base.timezone),
         }
     }
 
@@ -337,6 +493,23 @@ impl AppConfig {
         }
     }
 
+    /// Merge work-session configurations
+    fn merge_work_session_config(
+        base: WorkSessionConfig,
+        override_config: WorkSessionConfig,
+    ) -> WorkSessionConfig {
+        let default = WorkSessionConfig::default();
+        WorkSessionConfig {
+            idle_threshold_minutes: if override_config.idle_threshold_minutes
+                != default.idle_threshold_minutes
+            {
+                override_config.idle_threshold_minutes
+            } else {
+                base.idle_threshold_minutes
+            },
+        }
+    }
+
     /// Merge timeline configurations
     fn merge_timeline_config(base: TimelineConfig, override_config: TimelineConfig) -> TimelineConfig {
         let default = TimelineConfig::default();
@@ -387,6 +560,59 @@ impl AppConfig {
         }
     }
 
+    /// Merge search configurations
+    fn merge_search_config(base: SearchConfig, override_config: SearchConfig) -> SearchConfig {
+        let default = SearchConfig::default();
+        SearchConfig {
+            term_weight: if override_config.term_weight != default.term_weight {
+                override_config.term_weight
+            } else {
+                base.term_weight
+            },
+            summary_weight: if override_config.summary_weight != default.summary_weight {
+                override_config.summary_weight
+            } else {
+                base.summary_weight
+            },
+            recency_boost: if override_config.recency_boost != default.recency_boost {
+                override_config.recency_boost
+            } else {
+                base.recency_boost
+            },
+            recency_window_days: if override_config.recency_window_days
+                != default.recency_window_days
+            {
+                override_config.recency_window_days
+            } else {
+                base.recency_window_days
+            },
+            length_boost: if override_config.length_boost != default.length_boost {
+                override_config.length_boost
+            } else {
+                base.length_boost
+            },
+            length_min_messages: if override_config.length_min_messages
+                != default.length_min_messages
+            {
+                override_config.length_min_messages
+            } else {
+                base.length_min_messages
+            },
+            length_max_messages: if override_config.length_max_messages
+                != default.length_max_messages
+            {
+                override_config.length_max_messages
+            } else {
+                base.length_max_messages
+            },
+            stemming: if override_config.stemming != default.stemming {
+                override_config.stemming
+            } else {
+                base.stemming
+            },
+        }
+    }
+
     /// Migrate configuration from older versions
     pub fn migrate(mut self) -> Result<Self> {
         let current_version = "1.0";
@@ -432,6 +658,7 @@ impl AppConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::claude::{BudgetMetric, BudgetPeriod};
     use tempfile::TempDir;
 
     #[test]
@@ -443,6 +670,15 @@ mod tests {
         assert_eq!(config.ui.theme, "default");
     }
 
+    #[test]
+    fn test_config_without_search_section() {
+        let mut value = serde_json::to_value(AppConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("search");
+
+        let config: AppConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.search.ranking_weights(), RankingWeights::default());
+    }
+
     #[test]
     fn test_config_validation() {
         let config = AppConfig::default();
@@ -462,6 +698,26 @@ mod tests {
         let mut invalid_config = config.clone();
         invalid_config.ui.default_view = "InvalidView".to_string();
         assert!(invalid_config.validate().is_err());
+
+        // Test invalid search ranking weights
+        let mut invalid_config = config.clone();
+        invalid_config.search.term_weight = -1.0;
+        assert!(invalid_config.validate().is_err());
+
+        let mut invalid_config = config.clone();
+        invalid_config.search.length_min_messages = 100;
+        assert!(invalid_config.validate().is_err());
+
+        // Test non-positive budget limit
+        let mut invalid_config = config.clone();
+        invalid_config.budgets.push(Budget {
+            name: None,
+            metric: BudgetMetric::Tokens,
+            period: BudgetPeriod::Week,
+            project: None,
+            limit: 0.0,
+        });
+        assert!(invalid_config.validate().is_err());
     }
 
     #[test]
@@ -585,6 +841,16 @@ mod tests {
                 show_status_messages: true,
                 status_message_duration_ms: 3000,
             },
+            search: SearchConfig::default(),
+            work_sessions: WorkSessionConfig::default(),
+            budgets: vec![Budget {
+                name: None,
+                metric: BudgetMetric::Cost,
+                period: BudgetPeriod::Day,
+                project: None,
+                limit: 10.0,
+            }],
+            timezone: Some("Europe/Berlin".to_string()),
         };
 
         let override_config = AppConfig {
@@ -608,6 +874,16 @@ mod tests {
                 show_status_messages: false, // Override
                 status_message_duration_ms: 3000, // Same as default, so base should be kept
             },
+            search: SearchConfig {
+                recency_boost: 0.0, // Override
+                stemming: false,    // Override
+                ..SearchConfig::default()
+            },
+            work_sessions: WorkSessionConfig {
+                idle_threshold_minutes: 45, // Override
+            },
+            budgets: Vec::new(), // Unset, so base should be kept
+            timezone: None,      // Unset, so base should be kept
         };
 
         let merged = AppConfig::merge_configs(base_config.clone(), override_config);
@@ -620,6 +896,9 @@ mod tests {
         assert_eq!(merged.timeline.max_conversations, Some(100)); // Override
         assert_eq!(merged.ui.default_view, "Timeline"); // Override
         assert!(!merged.ui.show_status_messages); // Override
+        assert_eq!(merged.search.recency_boost, 0.0); // Override
+        assert!(!merged.search.stemming); // Override
+        assert_eq!(merged.work_sessions.idle_threshold_minutes, 45); // Override
 
         // Check that base values were kept when override matched default
         assert_eq!(merged.realtime.debounce_ms, base_config.realtime.debounce_ms);
@@ -628,6 +907,8 @@ mod tests {
         assert_eq!(merged.timeline.enable_caching, base_config.timeline.enable_caching);
         assert_eq!(merged.ui.theme, base_config.ui.theme);
         assert_eq!(merged.ui.status_message_duration_ms, base_config.ui.status_message_duration_ms);
+        assert_eq!(merged.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(merged.budgets, base_config.budgets);
     }
 
     #[test]
@@ -17,6 +17,9 @@ pub enum ClaudeToolsError {
     #[error("Configuration error: {0}")]
     Config(String),
 
+    #[error("{exceeded} usage budget(s) exceeded")]
+    BudgetExceeded { exceeded: usize },
+
     #[error("General error: {0}")]
     General(#[from] anyhow::Error),
 }
@@ -1,6 +1,7 @@
 use clap::Parser;
-use claude_tools::claude::ClaudeDirectory;
+use claude_tools::claude::{ClaudeDirectory, Timezone};
 use claude_tools::cli::{execute_command, Cli};
+use claude_tools::config::AppConfig;
 use claude_tools::errors::ClaudeToolsError;
 
 fn main() {
@@ -43,6 +44,12 @@ fn main() {
                 eprintln!("   • Check if Claude Code is currently running and try again");
                 std::process::exit(1);
             }
+            ClaudeToolsError::BudgetExceeded { exceeded } => {
+                // The report is already printed; a distinct code lets cron jobs and
+                // hooks react (1 is any other error, 2 a command-line usage error)
+                eprintln!("⚠️  {} usage budget(s) exceeded", exceeded);
+                std::process::exit(3);
+            }
             ClaudeToolsError::General(err) => {
                 eprintln!("❌ Error: {}", err);
                 eprintln!("💡 Run with --verbose for more details");
@@ -64,8 +71,21 @@ fn run(cli: Cli) -> Result<(), ClaudeToolsError> {
         eprintln!("📁 Using Claude directory: {}", claude_dir.path.display());
     }
 
+    // Time zone for bucketing: --tz, then the configured timezone, then local time
+    let timezone = match cli.tz.or_else(|| {
+        AppConfig::load_hierarchical(None, None)
+            .ok()
+            .and_then(|config| config.timezone)
+    }) {
+        Some(name) => Timezone::parse(&name).map_err(ClaudeToolsError::Config)?,
+        None => Timezone::default(),
+    };
+    if cli.verbose {
+        eprintln!("🕒 Using time zone: {}", timezone);
+    }
+
     // Execute the command
-    execute_command(claude_dir, cli.command, cli.verbose)?;
+    execute_command(claude_dir, cli.command, timezone, cli.verbose)?;
 
     Ok(())
 }
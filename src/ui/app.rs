@@ -1,20 +1,29 @@
+use crate::claude::latency::format_latency;
+use crate::claude::pricing::format_tokens;
+use crate::claude::tool_outcomes::{format_success_rate, MIN_RATED_CALLS};
+use crate::claude::usage_blocks::{largest_completed_block, usage_blocks, BLOCK_HOURS};
+use crate::claude::work_sessions::format_active_time;
 use crate::claude::{
-    ActivityTimeline, ActivityTrend, AnalyticsEngine, ClaudeDirectory, Conversation,
-    ConversationAnalytics, ConversationExporter, ConversationParser, ExportConfig, ExportFormat,
-    HighlightType, MatchHighlight, MessageRole, RankingIndicator, SearchEngine, SearchMode, SearchQuery,
-    SearchResult, SummaryDepth, TimePeriod, TimelineCache, TimelineConfig,
+    ActivityTimeline, ActivityTrend, AnalyticsEngine, BudgetStatus, CalendarHeatmap,
+    ClaudeDirectory, Conversation, ConversationAnalytics, ConversationExporter, ConversationParser,
+    ExportConfig, ExportFormat, FacetCount, FacetKind, FileIndex, HeatmapMetric, HighlightType,
+    LinkedCommit, MatchHighlight, MatchSource, MessageRole, ProjectChange, RankingIndicator,
+    ScoreExplanation, SearchEngine, SearchFacets, SearchMode, SearchQuery, SearchResult,
+    SearchStore, SummaryDepth, TimePeriod, TimelineCache, TimelineComparison, TimelineConfig,
+    Timezone, UsageBlock,
 };
 use crate::config::AppConfig;
 use crate::errors::ClaudeToolsError;
 use crate::mcp::{DiscoveryResult, McpServer, ServerDiscovery, ServerStatus};
 use crate::ui::conversation_display::ConversationRenderer;
 use crate::ui::{UpdateManager, UpdateScope};
+use chrono::{Duration, NaiveDate};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 use std::collections::HashSet;
@@ -104,6 +113,38 @@ pub struct App {
     current_search_result_index: usize,
     /// Search navigation active
     search_navigation_active: bool,
+    /// Query behind the current search results, including applied facet filters
+    active_search_query: Option<SearchQuery>,
+    /// Facet counts over the current search results
+    search_facets: Option<SearchFacets>,
+    /// Facet filters applied on top of the typed query
+    applied_facets: Vec<(FacetKind, String)>,
+    /// Facet side panel list state
+    facet_list_state: ListState,
+    /// Whether keyboard focus is in the facet side panel
+    facet_panel_focused: bool,
+    /// Whether search results show their score breakdown
+    explain_scores: bool,
+    /// Score breakdowns for the current search results (when explaining)
+    score_explanations: Vec<ScoreExplanation>,
+    /// Saved searches and search history (None if the store couldn't be loaded)
+    search_store: Option<SearchStore>,
+    /// History picker in the search bar (Some while open)
+    search_picker_state: Option<ListState>,
+    /// Index of files touched by tool calls, built on first use
+    file_index: Option<FileIndex>,
+    /// Files touched by the open conversation, listed in the file picker
+    file_picker_entries: Vec<String>,
+    /// File picker in conversation detail (Some while open)
+    file_picker_state: Option<ListState>,
+    /// Git commits linked to conversations, correlated on first use
+    linked_commits: Option<Vec<LinkedCommit>>,
+    /// Commits produced by the open conversation (Some while the panel is open)
+    commit_panel: Option<Vec<LinkedCommit>>,
+    /// Metric the calendar heatmap is shaded by
+    heatmap_metric: HeatmapMetric,
+    /// Day selected in the calendar heatmap (Some while selecting days)
+    heatmap_selected: Option<NaiveDate>,
     /// Analytics engine
     analytics_engine: Option<AnalyticsEngine>,
     /// Cached analytics data
@@ -128,6 +169,8 @@ pub struct App {
     mcp_auto_refresh: bool,
     /// Activity timeline data
     activity_timeline: Option<ActivityTimeline>,
+    /// Time zone days and times are shown in
+    timezone: Timezone,
     /// Timeline configuration
     timeline_config: TimelineConfig,
     /// Timeline scroll position
@@ -138,6 +181,8 @@ pub struct App {
     timeline_projects: Vec<String>,
     /// Expanded timeline projects (for showing/hiding details)
     timeline_expanded_projects: std::collections::HashSet<String>,
+    /// Comparison with the previous period (Some while shown beside the timeline)
+    timeline_comparison: Option<TimelineComparison>,
     /// Timeline cache manager
     timeline_cache: Option<TimelineCache>,
     /// Timeline loading state
@@ -162,6 +207,12 @@ pub struct App {
     update_manager: UpdateManager,
     /// Whether auto-refresh is currently active
     auto_refresh_enabled: bool,
+    /// Configured budgets exceeded in their current period, as `label: summary`
+    exceeded_budgets: Vec<String>,
+    /// Most recent 5-hour usage block, shown as a gauge while it's active
+    latest_usage_block: Option<UsageBlock>,
+    /// Tokens per block the gauge is measured against
+    usage_block_limit: Option<u64>,
 }
 
 impl App {
@@ -174,7 +225,7 @@ impl App {
     }
 
     /// Create a new application
-    pub fn new(claude_dir: ClaudeDirectory) -> Result<Self, ClaudeToolsError> {
+    pub fn new(claude_dir: ClaudeDirectory, timezone: Timezone) -> Result<Self, ClaudeToolsError> {
         let timeline_cache = Self::initialize_timeline_cache(&claude_dir);
         let parser = ConversationParser::new(claude_dir);
         let conversations = parser.parse_all_conversations()?;
@@ -184,19 +235,24 @@ impl App {
             list_state.select(Some(0));
         }
 
+        // Load configuration hierarchically
+        let app_config =
+            AppConfig::load_hierarchical(None, None).unwrap_or_else(|_| AppConfig::default());
+
         // Build search engine
-        let search_engine = parser.build_search_engine()?;
+        let mut search_engine = SearchEngine::new()
+            .with_stemming(app_config.search.stemming)
+            .with_ranking_weights(app_config.search.ranking_weights());
+        search_engine.build_index(conversations.clone())?;
 
         // Initialize MCP server discovery
         let server_discovery = ServerDiscovery::new();
         let mcp_server_list_state = ListState::default();
 
-        // Load configuration hierarchically
-        let app_config = AppConfig::load_hierarchical(None, None)
-            .unwrap_or_else(|_| AppConfig::default());
-
         // Initialize auto-refresh based on config
         let auto_refresh_enabled = app_config.realtime.enabled;
+        let exceeded_budgets = Self::exceeded_budgets(&app_config, &conversations, &timezone);
+        let (latest_usage_block, usage_block_limit) = Self::latest_usage_block(&conversations);
 
         Ok(Self {
             state: AppState::ConversationList,
@@ -216,6 +272,22 @@ impl App {
             current_search_mode: SearchMode::Text,
             current_search_result_index: 0,
             search_navigation_active: false,
+            active_search_query: None,
+            search_facets: None,
+            applied_facets: Vec::new(),
+            facet_list_state: ListState::default(),
+            facet_panel_focused: false,
+            explain_scores: false,
+            score_explanations: Vec::new(),
+            search_store: SearchStore::load_default().ok(),
+            search_picker_state: None,
+            file_index: None,
+            file_picker_entries: Vec::new(),
+            file_picker_state: None,
+            linked_commits: None,
+            commit_panel: None,
+            heatmap_metric: HeatmapMetric::default(),
+            heatmap_selected: None,
             analytics_engine: None,
             analytics_data: None,
             analytics_scroll: 0,
@@ -233,11 +305,16 @@ impl App {
             mcp_scroll: 0,
             mcp_auto_refresh: true,
             activity_timeline: None,
-            timeline_config: TimelineConfig::default(),
+            timezone,
+            timeline_config: TimelineConfig {
+                timezone,
+                ..TimelineConfig::default()
+            },
             timeline_scroll: 0,
             timeline_project_index: 0,
             timeline_projects: Vec::new(),
             timeline_expanded_projects: HashSet::new(),
+            timeline_comparison: None,
             timeline_cache,
             timeline_loading: false,
             in_conversation_search_query: String::new(),
@@ -250,9 +327,37 @@ impl App {
             app_config,
             update_manager: UpdateManager::new(),
             auto_refresh_enabled,
+            exceeded_budgets,
+            latest_usage_block,
+            usage_block_limit,
         })
     }
 
+    /// The latest usage block and the largest completed block's tokens as its limit
+    fn latest_usage_block(conversations: &[Conversation]) -> (Option<UsageBlock>, Option<u64>) {
+        let mut blocks = usage_blocks(conversations);
+        let limit = largest_completed_block(&blocks, chrono::Utc::now());
+        (blocks.pop(), limit)
+    }
+
+    /// Budgets from the config that are over their limit
+    fn exceeded_budgets(
+        app_config: &AppConfig,
+        conversations: &[Conversation],
+        timezone: &Timezone,
+    ) -> Vec<String> {
+        BudgetStatus::evaluate(
+            &app_config.budgets,
+            conversations,
+            chrono::Utc::now(),
+            timezone,
+        )
+        .into_iter()
+        .filter(|status| status.is_exceeded())
+        .map(|status| format!("{}: {}", status.budget.label(), status.summary()))
+        .collect()
+    }
+
     /// Handle key events
     pub fn handle_key_event(&mut self, key: KeyEvent) {
         match self.state {
@@ -271,6 +376,11 @@ impl App {
 
     /// Handle key events in conversation list mode
     fn handle_list_key_event(&mut self, key: KeyEvent) {
+        if self.facet_panel_focused {
+            self.handle_facet_key_event(key);
+            return;
+        }
+
         match key.code {
             KeyCode::Char('q') | KeyCode::Esc => {
                 // If we're viewing conversations from timeline, return to timeline
@@ -311,6 +421,12 @@ impl App {
                     self.previous_search_result();
                 }
             }
+            KeyCode::Char('f') => {
+                self.focus_facet_panel();
+            }
+            KeyCode::Char('x') => {
+                self.toggle_score_explanations();
+            }
             KeyCode::Char('?') | KeyCode::Char('h') => {
                 self.state = AppState::Help;
             }
@@ -333,8 +449,54 @@ impl App {
         }
     }
 
+    /// Handle key events while the facet side panel has focus
+    fn handle_facet_key_event(&mut self, key: KeyEvent) {
+        let entry_count = self.facet_entries().len();
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('f') | KeyCode::Char('q') => {
+                self.facet_panel_focused = false;
+            }
+            KeyCode::Char('j') | KeyCode::Down if entry_count > 0 => {
+                let next = self
+                    .facet_list_state
+                    .selected()
+                    .map_or(0, |i| (i + 1) % entry_count);
+                self.facet_list_state.select(Some(next));
+            }
+            KeyCode::Char('k') | KeyCode::Up if entry_count > 0 => {
+                let previous = self
+                    .facet_list_state
+                    .selected()
+                    .map_or(0, |i| (i + entry_count - 1) % entry_count);
+                self.facet_list_state.select(Some(previous));
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                self.apply_selected_facet();
+            }
+            KeyCode::Backspace => {
+                self.clear_facet_filters();
+            }
+            _ => {}
+        }
+    }
+
     /// Handle key events in conversation detail mode
     fn handle_detail_key_event(&mut self, key: KeyEvent) {
+        if self.file_picker_state.is_some() {
+            self.handle_file_picker_key_event(key);
+            return;
+        }
+        if self.commit_panel.is_some() {
+            if matches!(
+                key.code,
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('C')
+            ) {
+                self.commit_panel = None;
+            }
+            return;
+        }
+
         match key.code {
             KeyCode::Char('q') | KeyCode::Esc | KeyCode::Backspace => {
                 self.state = AppState::ConversationList;
@@ -372,6 +534,15 @@ impl App {
             KeyCode::Char('e') => {
                 self.start_export();
             }
+            KeyCode::Char('s') => {
+                self.show_similar_conversations();
+            }
+            KeyCode::Char('F') => {
+                self.open_file_picker();
+            }
+            KeyCode::Char('C') => {
+                self.open_commit_panel();
+            }
             KeyCode::Char('/') => {
                 self.start_in_conversation_search();
             }
@@ -384,7 +555,18 @@ impl App {
 
     /// Handle key events in search mode
     fn handle_search_key_event(&mut self, key: KeyEvent) {
+        if self.search_picker_state.is_some() {
+            self.handle_search_picker_key_event(key);
+            return;
+        }
+
         match key.code {
+            KeyCode::Up => {
+                self.open_search_picker();
+            }
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.open_search_picker();
+            }
             KeyCode::Enter => {
                 self.execute_search();
                 self.state = AppState::ConversationList;
@@ -403,6 +585,62 @@ impl App {
         }
     }
 
+    /// Handle key events while the search history picker is open
+    fn handle_search_picker_key_event(&mut self, key: KeyEvent) {
+        let entry_count = self.search_picker_entries().len();
+        let Some(picker) = self.search_picker_state.as_mut() else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc => {
+                self.search_picker_state = None;
+            }
+            KeyCode::Up | KeyCode::Char('k') if entry_count > 0 => {
+                let previous = picker
+                    .selected()
+                    .map_or(0, |i| (i + entry_count - 1) % entry_count);
+                picker.select(Some(previous));
+            }
+            KeyCode::Down | KeyCode::Char('j') if entry_count > 0 => {
+                let next = picker.selected().map_or(0, |i| (i + 1) % entry_count);
+                picker.select(Some(next));
+            }
+            KeyCode::Enter => {
+                self.run_picked_search();
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle key events while the touched-files picker is open
+    fn handle_file_picker_key_event(&mut self, key: KeyEvent) {
+        let entry_count = self.file_picker_entries.len();
+        let Some(picker) = self.file_picker_state.as_mut() else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('F') => {
+                self.file_picker_state = None;
+            }
+            KeyCode::Up | KeyCode::Char('k') if entry_count > 0 => {
+                let previous = picker
+                    .selected()
+                    .map_or(0, |i| (i + entry_count - 1) % entry_count);
+                picker.select(Some(previous));
+            }
+            KeyCode::Down | KeyCode::Char('j') if entry_count > 0 => {
+                let next = picker.selected().map_or(0, |i| (i + 1) % entry_count);
+                picker.select(Some(next));
+            }
+            KeyCode::Enter => {
+                self.show_file_conversations();
+            }
+            _ => {}
+        }
+    }
+
     /// Handle key events in in-conversation search mode
     fn handle_in_conversation_search_key_event(&mut self, key: KeyEvent) {
         match key.code {
@@ -432,6 +670,10 @@ impl App {
 
     /// Handle key events in analytics mode
     fn handle_analytics_key_event(&mut self, key: KeyEvent) {
+        if self.heatmap_selected.is_some() {
+            self.handle_heatmap_key_event(key);
+            return;
+        }
         match key.code {
             KeyCode::Char('q') | KeyCode::Esc => {
                 self.state = AppState::ConversationList;
@@ -461,12 +703,175 @@ impl App {
             KeyCode::Char('r') => {
                 self.refresh_analytics();
             }
+            KeyCode::Char('H') => {
+                self.open_heatmap();
+            }
+            KeyCode::Char('v') => {
+                self.heatmap_metric = self.heatmap_metric.next();
+            }
             _ => {}
         }
     }
 
+    /// Handle key events while selecting a day in the calendar heatmap
+    fn handle_heatmap_key_event(&mut self, key: KeyEvent) {
+        let Some(selected) = self.heatmap_selected else {
+            return;
+        };
+        let step = match key.code {
+            KeyCode::Char('h') | KeyCode::Left => -7,
+            KeyCode::Char('l') | KeyCode::Right => 7,
+            KeyCode::Char('k') | KeyCode::Up => -1,
+            KeyCode::Char('j') | KeyCode::Down => 1,
+            KeyCode::Char('v') => {
+                self.heatmap_metric = self.heatmap_metric.next();
+                return;
+            }
+            KeyCode::Enter => {
+                self.show_heatmap_day_conversations(selected);
+                return;
+            }
+            KeyCode::Char('q') | KeyCode::Char('H') | KeyCode::Esc => {
+                self.heatmap_selected = None;
+                return;
+            }
+            _ => return,
+        };
+
+        let today = self.timezone.today();
+        let first = today - Duration::days(363);
+        self.heatmap_selected = Some((selected + Duration::days(step)).clamp(first, today));
+    }
+
+    /// Start selecting days in the calendar heatmap, beginning with today
+    fn open_heatmap(&mut self) {
+        if self.analytics_data.is_none() {
+            if let Err(e) = self.generate_analytics() {
+                self.error_message = Some(format!("Analytics error: {}", e));
+                return;
+            }
+        }
+        self.heatmap_selected = Some(self.timezone.today());
+        self.status_message = Some(
+            "Heatmap: h/l weeks, j/k days, v metric, Enter conversations, Esc done".to_string(),
+        );
+    }
+
+    /// List the conversations started on a heatmap day
+    fn show_heatmap_day_conversations(&mut self, date: NaiveDate) {
+        self.search_results = self
+            .conversations
+            .iter()
+            .filter(|conv| conv.started_at.map(|start| self.timezone.date(start)) == Some(date))
+            .cloned()
+            .collect();
+        if self.search_results.is_empty() {
+            self.status_message = Some(format!("No conversations on {}", date));
+            return;
+        }
+
+        if self.state == AppState::Timeline {
+            self.viewing_timeline_conversations = true;
+        }
+        self.heatmap_selected = None;
+        self.advanced_search_results.clear();
+        self.reset_facets();
+        self.search_navigation_active = false;
+        self.conversation_list_state.select(Some(0));
+        self.state = AppState::ConversationList;
+        self.status_message = Some(format!(
+            "{} conversation(s) on {}",
+            self.search_results.len(),
+            date
+        ));
+    }
+
+    /// Calendar heatmap of the last year as styled lines, highlighting the selected day
+    fn heatmap_lines(&self, analytics: &ConversationAnalytics) -> Vec<Line<'static>> {
+        let heatmap = CalendarHeatmap::last_year(
+            &analytics.temporal_analysis,
+            self.heatmap_metric,
+            self.timezone.today(),
+        );
+        const COLORS: [Color; CalendarHeatmap::LEVELS] = [
+            Color::DarkGray,
+            Color::Rgb(14, 68, 41),
+            Color::Rgb(0, 109, 50),
+            Color::Rgb(38, 166, 65),
+            Color::Rgb(57, 211, 83),
+        ];
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(
+                    "🗓  Activity Heatmap",
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!(
+                        " ({} per day, v: metric, H: select days)",
+                        heatmap.metric.label()
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]),
+            Line::from(Span::styled(
+                format!("       {}", heatmap.month_labels()),
+                Style::default().fg(Color::DarkGray),
+            )),
+        ];
+
+        for weekday in 0..7 {
+            let mut spans = vec![Span::styled(
+                format!("   {:<4}", CalendarHeatmap::weekday_label(weekday)),
+                Style::default().fg(Color::DarkGray),
+            )];
+            for week in 0..heatmap.week_count() {
+                let span = match heatmap.date_at(week, weekday) {
+                    Some(date) => {
+                        let level = heatmap.level(date);
+                        let style = if self.heatmap_selected == Some(date) {
+                            Style::default().fg(Color::Black).bg(Color::Yellow)
+                        } else {
+                            Style::default().fg(COLORS[level])
+                        };
+                        Span::styled(CalendarHeatmap::SHADES[level].to_string(), style)
+                    }
+                    None => Span::raw(" "),
+                };
+                spans.push(span);
+            }
+            lines.push(Line::from(spans));
+        }
+
+        let summary = match self.heatmap_selected {
+            Some(date) => format!(
+                "   ► {}: {}",
+                date.format("%a %Y-%m-%d"),
+                heatmap.metric.format_value(heatmap.value(date))
+            ),
+            None => format!(
+                "   {} over {} active days",
+                heatmap.metric.format_value(heatmap.total()),
+                heatmap.active_days()
+            ),
+        };
+        lines.push(Line::from(Span::styled(
+            summary,
+            Style::default().fg(Color::Yellow),
+        )));
+        lines.push(Line::from(""));
+        lines
+    }
+
     /// Handle key events in timeline mode
     fn handle_timeline_key_event(&mut self, key: KeyEvent) {
+        if self.heatmap_selected.is_some() {
+            self.handle_heatmap_key_event(key);
+            return;
+        }
         match key.code {
             KeyCode::Char('q') | KeyCode::Esc => {
                 if let Some(previous_state) = self.previous_state_before_timeline.take() {
@@ -530,6 +935,42 @@ impl App {
                     ..self.timeline_config.clone()
                 });
             }
+            KeyCode::Char('w') => {
+                self.update_timeline_config(TimelineConfig {
+                    period: TimePeriod::ThisWeek,
+                    ..self.timeline_config.clone()
+                });
+            }
+            KeyCode::Char('W') => {
+                self.update_timeline_config(TimelineConfig {
+                    period: TimePeriod::PreviousWeek,
+                    ..self.timeline_config.clone()
+                });
+            }
+            KeyCode::Char('m') => {
+                self.update_timeline_config(TimelineConfig {
+                    period: TimePeriod::ThisMonth,
+                    ..self.timeline_config.clone()
+                });
+            }
+            KeyCode::Char('M') => {
+                self.update_timeline_config(TimelineConfig {
+                    period: TimePeriod::PreviousMonth,
+                    ..self.timeline_config.clone()
+                });
+            }
+            KeyCode::Char('Q') => {
+                self.update_timeline_config(TimelineConfig {
+                    period: TimePeriod::current_quarter(&self.timezone),
+                    ..self.timeline_config.clone()
+                });
+            }
+            KeyCode::Char('[') => {
+                self.shift_timeline_period(-1);
+            }
+            KeyCode::Char(']') => {
+                self.shift_timeline_period(1);
+            }
             KeyCode::Char('b') => {
                 self.update_timeline_config(TimelineConfig {
                     summary_depth: SummaryDepth::Brief,
@@ -552,6 +993,12 @@ impl App {
                 // Clear cache
                 self.clear_timeline_cache();
             }
+            KeyCode::Char('H') => {
+                self.open_heatmap();
+            }
+            KeyCode::Char('p') => {
+                self.toggle_timeline_comparison();
+            }
             _ => {}
         }
     }
@@ -823,6 +1270,7 @@ impl App {
         if self.search_query.is_empty() {
             self.search_results.clear();
             self.advanced_search_results.clear();
+            self.reset_facets();
             self.status_message = Some("Search cleared".to_string());
         } else {
             // Determine search mode based on query pattern
@@ -847,45 +1295,369 @@ impl App {
                 SearchMode::Advanced => SearchQuery::text(&self.search_query),
             };
 
-            // Execute advanced search
-            match self.search_engine.search(&query) {
-                Ok(results) => {
-                    // Convert SearchResult to Conversation for compatibility
-                    self.search_results = results
-                        .iter()
-                        .map(|result| result.conversation.clone())
-                        .collect();
+            self.applied_facets.clear();
+            self.current_search_mode = search_mode;
+            self.run_search_query(query.clone());
+            self.record_search_history(query);
+        }
+    }
+
+    /// Persist a search to history
+    fn record_search_history(&mut self, query: SearchQuery) {
+        let result_count = self.search_results.len();
+        if let Some(store) = self.search_store.as_mut() {
+            store.record_history(&self.search_query, query, result_count);
+            if let Err(e) = store.save() {
+                self.error_message = Some(format!("Failed to save search history: {}", e));
+            }
+        }
+    }
 
-                    self.advanced_search_results = results;
-                    self.current_search_mode = search_mode;
+    /// Saved searches (★) followed by recent history, as (label, query text, query)
+    fn search_picker_entries(&self) -> Vec<(String, String, SearchQuery)> {
+        let Some(store) = self.search_store.as_ref() else {
+            return Vec::new();
+        };
 
-                    let total_matches: usize = self
-                        .advanced_search_results
-                        .iter()
-                        .map(|r| r.match_count)
-                        .sum();
+        let saved = store.saved.iter().map(|(name, saved)| {
+            (
+                format!("★ {} → {}", name, saved.query_text),
+                saved.query_text.clone(),
+                saved.query.clone(),
+            )
+        });
+        let history = store.recent_history().into_iter().map(|entry| {
+            (
+                format!(
+                    "{}  {} ({})",
+                    entry.searched_at.format("%m-%d %H:%M"),
+                    entry.query_text,
+                    entry.result_count
+                ),
+                entry.query_text.clone(),
+                entry.query.clone(),
+            )
+        });
 
-                    self.status_message = Some(format!(
-                        "Found {} conversation(s) with {} total matches",
-                        self.search_results.len(),
-                        total_matches
-                    ));
+        saved.chain(history).collect()
+    }
 
-                    if !self.search_results.is_empty() {
-                        self.conversation_list_state.select(Some(0));
-                        self.search_navigation_active = true;
-                        self.current_search_result_index = 0;
-                    } else {
-                        self.search_navigation_active = false;
-                    }
-                }
-                Err(e) => {
-                    self.error_message = Some(format!("Search error: {}", e));
+    /// Open the search history picker
+    fn open_search_picker(&mut self) {
+        if self.search_picker_entries().is_empty() {
+            self.status_message = Some("No search history yet".to_string());
+            return;
+        }
+        let mut picker = ListState::default();
+        picker.select(Some(0));
+        self.search_picker_state = Some(picker);
+    }
+
+    /// Re-run the search selected in the history picker, filters included
+    fn run_picked_search(&mut self) {
+        let entries = self.search_picker_entries();
+        let selected = self
+            .search_picker_state
+            .take()
+            .and_then(|picker| picker.selected())
+            .and_then(|i| entries.into_iter().nth(i));
+        let Some((_, query_text, query)) = selected else {
+            return;
+        };
+
+        self.search_query = query_text;
+        self.current_search_mode = if query.search_mode == SearchMode::Regex {
+            SearchMode::Regex
+        } else {
+            SearchMode::Text
+        };
+        self.applied_facets.clear();
+        self.run_search_query(query.clone());
+        self.record_search_history(query);
+        self.state = AppState::ConversationList;
+    }
+
+    /// Run a search query, replacing the current results and facet counts
+    fn run_search_query(&mut self, query: SearchQuery) {
+        match self.search_engine.search_with_facets(&query) {
+            Ok((results, facets)) => {
+                // Convert SearchResult to Conversation for compatibility
+                self.search_results = results
+                    .iter()
+                    .map(|result| result.conversation.clone())
+                    .collect();
+
+                self.advanced_search_results = results;
+                self.search_facets = Some(facets);
+                self.active_search_query = Some(query);
+                self.facet_list_state.select(Some(0));
+                self.refresh_score_explanations();
+
+                let total_matches: usize = self
+                    .advanced_search_results
+                    .iter()
+                    .map(|r| r.match_count)
+                    .sum();
+
+                self.status_message = Some(format!(
+                    "Found {} conversation(s) with {} total matches",
+                    self.search_results.len(),
+                    total_matches
+                ));
+
+                if !self.search_results.is_empty() {
+                    self.conversation_list_state.select(Some(0));
+                    self.search_navigation_active = true;
+                    self.current_search_result_index = 0;
+                } else {
+                    self.search_navigation_active = false;
                 }
             }
+            Err(e) => {
+                self.error_message = Some(format!("Search error: {}", e));
+            }
+        }
+    }
+
+    /// Toggle score breakdowns under each search result
+    fn toggle_score_explanations(&mut self) {
+        self.explain_scores = !self.explain_scores;
+        self.refresh_score_explanations();
+        self.status_message = Some(if self.explain_scores {
+            "Score explanations on".to_string()
+        } else {
+            "Score explanations off".to_string()
+        });
+    }
+
+    /// Recompute score breakdowns for the current results
+    fn refresh_score_explanations(&mut self) {
+        self.score_explanations = match (&self.active_search_query, self.explain_scores) {
+            (Some(query), true) => self
+                .advanced_search_results
+                .iter()
+                .map(|result| self.search_engine.explain(result, query))
+                .collect(),
+            _ => Vec::new(),
+        };
+    }
+
+    /// Facet values of the current search, flattened in panel order
+    fn facet_entries(&self) -> Vec<(FacetKind, FacetCount)> {
+        self.search_facets
+            .as_ref()
+            .map(|facets| {
+                facets
+                    .groups()
+                    .into_iter()
+                    .flat_map(|(kind, values)| values.iter().map(move |v| (kind, v.clone())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Move keyboard focus to the facet side panel
+    fn focus_facet_panel(&mut self) {
+        if self.facet_entries().is_empty() {
+            self.status_message = Some("No facets - run a search with / first".to_string());
+            return;
+        }
+        self.facet_panel_focused = true;
+        if self.facet_list_state.selected().is_none() {
+            self.facet_list_state.select(Some(0));
+        }
+    }
+
+    /// Narrow the current search by the selected facet value
+    fn apply_selected_facet(&mut self) {
+        let entries = self.facet_entries();
+        let Some((kind, facet)) = self
+            .facet_list_state
+            .selected()
+            .and_then(|i| entries.get(i).cloned())
+        else {
+            return;
+        };
+        let Some(query) = self.active_search_query.clone() else {
+            return;
+        };
+
+        match query.with_facet(kind, &facet.value) {
+            Ok(query) => {
+                self.applied_facets.push((kind, facet.value));
+                self.run_search_query(query);
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Facet error: {}", e));
+            }
+        }
+    }
+
+    /// Drop applied facet filters and rerun the typed query
+    fn clear_facet_filters(&mut self) {
+        if self.applied_facets.is_empty() {
+            return;
+        }
+        self.applied_facets.clear();
+        self.execute_search();
+    }
+
+    /// Forget facet state, e.g. when the list no longer shows search results
+    fn reset_facets(&mut self) {
+        self.active_search_query = None;
+        self.score_explanations.clear();
+        self.search_facets = None;
+        self.applied_facets.clear();
+        self.facet_panel_focused = false;
+        self.facet_list_state.select(None);
+    }
+
+    /// Replace the conversation list with conversations similar to the open one
+    fn show_similar_conversations(&mut self) {
+        let Some(session_id) = self
+            .selected_conversation
+            .as_ref()
+            .map(|conv| conv.session_id.clone())
+        else {
+            return;
+        };
+
+        match self.search_engine.similar_to_session(&session_id, 20) {
+            Ok(similar) if similar.is_empty() => {
+                self.status_message = Some("No similar conversations found".to_string());
+            }
+            Ok(similar) => {
+                self.search_results = similar
+                    .iter()
+                    .map(|item| item.conversation.clone())
+                    .collect();
+                self.advanced_search_results.clear();
+                self.reset_facets();
+                self.search_navigation_active = false;
+                self.conversation_list_state.select(Some(0));
+                self.state = AppState::ConversationList;
+                self.detail_scroll = 0;
+
+                self.status_message = Some(format!(
+                    "{} conversation(s) similar to {} (best match {:.2})",
+                    similar.len(),
+                    session_id.chars().take(8).collect::<String>(),
+                    similar[0].similarity
+                ));
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Similarity error: {}", e));
+            }
         }
     }
 
+    /// Open the picker of files touched by the open conversation
+    fn open_file_picker(&mut self) {
+        let Some(session_id) = self
+            .selected_conversation
+            .as_ref()
+            .map(|conv| conv.session_id.clone())
+        else {
+            return;
+        };
+
+        let index = self
+            .file_index
+            .get_or_insert_with(|| FileIndex::build(&self.conversations));
+        self.file_picker_entries = index
+            .files_for_session(&session_id)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        if self.file_picker_entries.is_empty() {
+            self.status_message = Some("No files touched in this conversation".to_string());
+            return;
+        }
+        let mut picker = ListState::default();
+        picker.select(Some(0));
+        self.file_picker_state = Some(picker);
+    }
+
+    /// Open the panel of git commits produced by the open conversation
+    fn open_commit_panel(&mut self) {
+        use crate::claude::git_commits::{correlate_commits, DEFAULT_COMMIT_WINDOW_MINUTES};
+
+        let Some(session_id) = self
+            .selected_conversation
+            .as_ref()
+            .map(|conv| conv.session_id.clone())
+        else {
+            return;
+        };
+
+        let linked = self.linked_commits.get_or_insert_with(|| {
+            correlate_commits(
+                &self.conversations,
+                Duration::minutes(DEFAULT_COMMIT_WINDOW_MINUTES),
+            )
+        });
+        let commits: Vec<LinkedCommit> = linked
+            .iter()
+            .filter(|commit| commit.has_session(&session_id))
+            .cloned()
+            .collect();
+
+        if commits.is_empty() {
+            self.status_message = Some("No git commits linked to this conversation".to_string());
+            return;
+        }
+        self.commit_panel = Some(commits);
+    }
+
+    /// Show every conversation that touched the file selected in the picker
+    fn show_file_conversations(&mut self) {
+        let selected = self
+            .file_picker_state
+            .take()
+            .and_then(|picker| picker.selected())
+            .and_then(|i| self.file_picker_entries.get(i).cloned());
+        let (Some(path), Some(index)) = (selected, self.file_index.as_ref()) else {
+            return;
+        };
+
+        let Some(file) = index
+            .find(&path, false)
+            .into_iter()
+            .find(|m| m.path == path)
+        else {
+            return;
+        };
+        self.search_results = file
+            .sessions
+            .iter()
+            .filter_map(|session| {
+                self.conversations
+                    .iter()
+                    .find(|conv| conv.session_id == session.session_id)
+                    .cloned()
+            })
+            .collect();
+        self.advanced_search_results.clear();
+        self.reset_facets();
+        self.search_navigation_active = false;
+        self.conversation_list_state.select(Some(0));
+        self.state = AppState::ConversationList;
+        self.detail_scroll = 0;
+
+        let modified = file
+            .sessions
+            .iter()
+            .filter(|session| session.operations.iter().any(|op| op.is_modification()))
+            .count();
+        self.status_message = Some(format!(
+            "{} conversation(s) touched {} ({} modified it)",
+            file.sessions.len(),
+            path,
+            modified
+        ));
+    }
+
     /// Refresh conversations from directory
     fn refresh_conversations(&mut self) {
         // Clear any existing status/error messages for immediate visual feedback
@@ -898,6 +1670,12 @@ impl App {
                 
                 // Update conversations data
                 self.conversations = conversations;
+                self.exceeded_budgets =
+                    Self::exceeded_budgets(&self.app_config, &self.conversations, &self.timezone);
+                (self.latest_usage_block, self.usage_block_limit) =
+                    Self::latest_usage_block(&self.conversations);
+                self.file_index = None;
+                self.linked_commits = None;
                 
                 // Clear search state to ensure refreshed conversations are visible
                 self.search_results.clear();
@@ -1014,10 +1792,17 @@ impl App {
 
         match self.state {
             AppState::ConversationList | AppState::Search => {
-                self.render_conversation_list(frame, chunks[0]);
+                let list_area = self.render_usage_block_gauge(frame, chunks[0]);
+                self.render_conversation_list(frame, list_area);
             }
             AppState::ConversationDetail => {
                 self.render_conversation_detail(frame, chunks[0]);
+                if self.file_picker_state.is_some() {
+                    self.render_file_picker(frame, chunks[0]);
+                }
+                if self.commit_panel.is_some() {
+                    self.render_commit_panel(frame, chunks[0]);
+                }
             }
             AppState::InConversationSearch => {
                 self.render_conversation_detail(frame, chunks[0]);
@@ -1052,6 +1837,9 @@ impl App {
 
         if self.state == AppState::Search {
             self.render_search_input(frame, chunks[1]);
+            if self.search_picker_state.is_some() {
+                self.render_search_picker(frame, chunks[1]);
+            }
         }
     }
 
@@ -1100,6 +1888,8 @@ impl App {
                     Line::from("  /          Start search mode"),
                     Line::from("  n          Next search result (when searching)"),
                     Line::from("  N          Previous search result (when searching)"),
+                    Line::from("  f          Focus facet panel (Enter filters, ⌫ clears)"),
+                    Line::from("  x          Toggle score explanations"),
                     Line::from(""),
                     Line::from("🔧 Actions:"),
                     Line::from("  r          Refresh conversation list"),
@@ -1174,6 +1964,9 @@ impl App {
                     Line::from("🔧 Actions:"),
                     Line::from("  q / Esc    Return to conversation list"),
                     Line::from("  e          Export conversation to file"),
+                    Line::from("  s          Show similar conversations"),
+                    Line::from("  F          Files touched; Enter lists sessions that touched one"),
+                    Line::from("  C          Git commits produced by this session"),
                     Line::from("  /          Search within conversation"),
                 ]);
 
@@ -1212,6 +2005,7 @@ impl App {
                     Line::from("  Enter      Execute search"),
                     Line::from("  Esc        Cancel and return to list"),
                     Line::from("  Backspace  Delete characters"),
+                    Line::from("  ↑ / Ctrl+R Pick from saved searches and history"),
                     Line::from(""),
                     Line::from("🎯 Advanced Search:"),
                     Line::from("  regex:pattern    Use regular expressions"),
@@ -1301,9 +2095,17 @@ impl App {
                     Line::from("  PgUp       Page up (fast scroll)"),
                     Line::from(""),
                     Line::from("🔧 Actions:"),
+                    Line::from("  H          Select days in the activity heatmap"),
+                    Line::from("  v          Cycle heatmap metric (messages/tokens/cost/...)"),
                     Line::from("  e          Export analytics to JSON file"),
                     Line::from("  r          Refresh analytics data"),
                     Line::from("  q / Esc    Return to conversation list"),
+                    Line::from(""),
+                    Line::from("🗓  Heatmap Day Selection:"),
+                    Line::from("  h / l      Previous/next week"),
+                    Line::from("  j / k      Next/previous day"),
+                    Line::from("  Enter      List conversations from the selected day"),
+                    Line::from("  Esc        Leave day selection"),
                 ]);
             }
 
@@ -1333,8 +2135,12 @@ impl App {
                     Line::from("⏰ Time Period Filters:"),
                     Line::from("  1          Past 24 hours"),
                     Line::from("  2          Past 48 hours"),
-                    Line::from("  7          Past week"),
-                    Line::from("  3          Past month"),
+                    Line::from("  7          Past 7 days"),
+                    Line::from("  3          Past 30 days"),
+                    Line::from("  w / W      This week / last week"),
+                    Line::from("  m / M      This month / last month"),
+                    Line::from("  Q          This quarter"),
+                    Line::from("  [ / ]      Previous/next week or quarter"),
                     Line::from(""),
                     Line::from("📄 Summary Depth:"),
                     Line::from("  b          Brief summaries"),
@@ -1344,6 +2150,8 @@ impl App {
                     Line::from("🔧 Actions:"),
                     Line::from("  r          Refresh timeline data"),
                     Line::from("  C          Clear timeline cache"),
+                    Line::from("  H          Activity heatmap (Enter lists a day's conversations)"),
+                    Line::from("  p          Compare side by side with the previous period"),
                     Line::from("  q / Esc    Return to conversation list"),
                 ]);
 
@@ -1354,7 +2162,7 @@ impl App {
                         Span::styled(
                             format!(
                                 "{} - {} projects, {} conversations",
-                                timeline.config.period.label(),
+                                timeline.config.period.label(&timeline.config.timezone),
                                 timeline.projects.len(),
                                 timeline.total_stats.total_conversations
                             ),
@@ -1492,15 +2300,20 @@ impl App {
 
         let items: Vec<ListItem> = conversations
             .iter()
-            .map(|conv| {
+            .enumerate()
+            .map(|(idx, conv)| {
                 let summary = conv.summary.as_deref().unwrap_or("No summary");
                 let project = &conv.project_path;
                 let message_count = conv.messages.len();
 
-                let content = format!(
+                let mut content = format!(
                     "📄 {} ({})\n   📁 {}\n   💬 {} messages",
                     summary, conv.session_id, project, message_count
                 );
+                if let Some(explanation) = self.score_explanations.get(idx) {
+                    content.push_str("\n   🧮 ");
+                    content.push_str(&Self::format_explanation(explanation));
+                }
 
                 ListItem::new(content).style(Style::default().fg(Color::White))
             })
@@ -1509,13 +2322,32 @@ impl App {
         let title = if self.search_results.is_empty() {
             format!("Conversations ({})", self.conversations.len())
         } else {
+            let filters: String = self
+                .applied_facets
+                .iter()
+                .map(|(kind, value)| format!(" [{}={}]", kind.label(), value))
+                .collect();
             format!(
-                "Search Results ({}) - Query: '{}'",
+                "Search Results ({}) - Query: '{}'{}",
                 self.search_results.len(),
-                self.search_query
+                self.search_query,
+                filters
             )
         };
 
+        // Facet side panel alongside search results
+        let facet_entries = self.facet_entries();
+        let area = if facet_entries.is_empty() {
+            area
+        } else {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(36)])
+                .split(area);
+            self.render_facet_panel(frame, columns[1], &facet_entries);
+            columns[0]
+        };
+
         let list = List::new(items)
             .block(
                 Block::default()
@@ -1533,6 +2365,92 @@ impl App {
         frame.render_stateful_widget(list, area, &mut self.conversation_list_state);
     }
 
+    /// One-line score breakdown for the conversation list
+    fn format_explanation(explanation: &ScoreExplanation) -> String {
+        let terms: Vec<String> = explanation
+            .terms
+            .iter()
+            .map(|term| {
+                format!(
+                    "{} {:.3} (tf {:.3}×idf {:.2})",
+                    term.term, term.contribution, term.tf, term.idf
+                )
+            })
+            .collect();
+
+        let mut line = format!("{:.3}", explanation.score);
+        if explanation.mode == "text" {
+            line.push_str(&format!(
+                " = Σ[{}] ×{:.2} ×recency {:.2} ×length {:.2}",
+                terms.join(", "),
+                explanation.term_weight,
+                explanation.recency_factor,
+                explanation.length_factor
+            ));
+        } else if !terms.is_empty() {
+            line.push_str(&format!(" {} [{}]", explanation.mode, terms.join(", ")));
+        } else {
+            line.push_str(&format!(" ({})", explanation.mode));
+        }
+        if !explanation.filters.is_empty() {
+            line.push_str(&format!(" • {}", explanation.filters.join("; ")));
+        }
+        line
+    }
+
+    /// Render facet counts for the current search as a selectable side panel
+    fn render_facet_panel(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        entries: &[(FacetKind, FacetCount)],
+    ) {
+        let items: Vec<ListItem> = entries
+            .iter()
+            .map(|(kind, facet)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:<8}", kind.label()),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(facet.value.clone()),
+                    Span::styled(
+                        format!(" ({})", facet.count),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let border_color = if self.facet_panel_focused {
+            Color::Yellow
+        } else {
+            Color::White
+        };
+        let title = if self.facet_panel_focused {
+            "Facets (Enter: filter, ⌫: clear)"
+        } else {
+            "Facets (f)"
+        };
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(border_color)),
+            )
+            .highlight_style(if self.facet_panel_focused {
+                Style::default()
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(Color::Yellow)
+            } else {
+                Style::default()
+            });
+
+        frame.render_stateful_widget(list, area, &mut self.facet_list_state);
+    }
+
     /// Render conversation detail with enhanced markdown formatting
     fn render_conversation_detail(&mut self, frame: &mut Frame, area: Rect) {
         // Clear the area first to prevent text overlap during refresh
@@ -1578,6 +2496,7 @@ impl App {
                             end: match_info.end_pos,
                             matched_text: match_info.matched_text.clone(),
                             highlight_type: HighlightType::InConversationSearch,
+                            source: MatchSource::Text,
                         })
                         .collect();
                     
@@ -1607,6 +2526,106 @@ impl App {
         }
     }
 
+    /// Panel comparing the timeline with the previous period
+    fn timeline_comparison_panel(comparison: &TimelineComparison) -> Paragraph<'static> {
+        let change_style = |change: i64| match change {
+            c if c > 0 => Style::default().fg(Color::Green),
+            c if c < 0 => Style::default().fg(Color::Red),
+            _ => Style::default().fg(Color::DarkGray),
+        };
+
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!(
+                    "vs {}",
+                    comparison.previous_period.label(&comparison.timezone)
+                ),
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+        for (label, delta) in [
+            ("Projects", comparison.active_projects),
+            ("Conversations", comparison.conversations),
+            ("Messages", comparison.messages),
+            ("Tokens", comparison.tokens),
+            ("Tool calls", comparison.tool_calls),
+        ] {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<14}", label), Style::default().fg(Color::Cyan)),
+                Span::raw(format!("{:>8} → {:<8} ", delta.previous, delta.current)),
+                Span::styled(delta.change_label(), change_style(delta.change())),
+            ]));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "📁 Projects",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for project in &comparison.projects {
+            let (marker, style) = match project.change {
+                ProjectChange::Appeared => ("+ ", Style::default().fg(Color::Green)),
+                ProjectChange::WentQuiet => ("- ", Style::default().fg(Color::Red)),
+                ProjectChange::Continued => ("  ", Style::default().fg(Color::White)),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{}{}", marker, project.project_path), style),
+                Span::styled(
+                    format!(" {} msgs", project.messages.change_label()),
+                    change_style(project.messages.change()),
+                ),
+            ]));
+            if !project.new_topics.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    format!("    ↑ {}", project.new_topics.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            if !project.dropped_topics.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    format!("    ↓ {}", project.dropped_topics.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+
+        let changed_tools: Vec<_> = comparison
+            .tools
+            .iter()
+            .filter(|tool| tool.uses.change() != 0)
+            .take(5)
+            .collect();
+        if !changed_tools.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "🔧 Tools",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            for tool in changed_tools {
+                lines.push(Line::from(vec![
+                    Span::raw(format!("  {:<14}", tool.name)),
+                    Span::styled(tool.uses.change_label(), change_style(tool.uses.change())),
+                ]));
+            }
+        }
+
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Period Comparison (p to close)")
+                    .border_style(Style::default().fg(Color::Magenta)),
+            )
+            .wrap(Wrap { trim: true })
+    }
+
     /// Get messages that should be visible based on scroll position and screen height
     fn get_visible_messages<'a>(
         &self,
@@ -1763,8 +2782,8 @@ impl App {
                     auto_refresh_indicator, 
                     if self.auto_refresh_enabled { "ON" } else { "OFF" })
             },
-            AppState::ConversationDetail => "Press q to go back, j/k to scroll, e to export, / or Ctrl+F to search".to_string(),
-            AppState::Search => format!("Search: {}_", self.search_query),
+            AppState::ConversationDetail => "Press q to go back, j/k to scroll, e to export, s for similar, F for files, C for commits, / or Ctrl+F to search".to_string(),
+            AppState::Search => format!("Search: {}_ • ↑: history", self.search_query),
             AppState::InConversationSearch => {
                 let match_info = if self.in_conversation_search_matches.is_empty() {
                     "No matches".to_string()
@@ -1776,10 +2795,10 @@ impl App {
                 format!("In-conversation search: {}_ • {} • Ctrl+N/P: navigate, Esc: exit", 
                     self.in_conversation_search_query, match_info)
             },
-            AppState::Analytics => "Press j/k to scroll, e to export, r to refresh, q to go back".to_string(),
+            AppState::Analytics => "Press j/k to scroll, H for heatmap days, v for metric, e to export, r to refresh, q to go back".to_string(),
             AppState::Timeline => {
                 let cache_status = if self.timeline_cache.is_some() { " • Cache enabled" } else { "" };
-                format!("j/k: navigate, Enter: view conversations, Tab: expand, 1/2/7/3: time periods, C: clear cache, q: back{}", cache_status)
+                format!("j/k: navigate, Enter: view conversations, Tab: expand, 1/2/7/3: time periods, w/W/m/M/Q: calendar, [/]: step, H: heatmap, C: clear cache, q: back{}", cache_status)
             },
             AppState::Export => "Select format with j/k, Enter to export, q to cancel".to_string(),
             AppState::McpServerDashboard => "Press j/k to navigate, r to refresh, d for health checks, ? for help, q to go back".to_string(),
@@ -1791,6 +2810,14 @@ impl App {
             status_text = msg.clone();
         }
 
+        if !self.exceeded_budgets.is_empty() {
+            status_text = format!(
+                "⚠️ Over budget: {} • {}",
+                self.exceeded_budgets.join(", "),
+                status_text
+            );
+        }
+
         if let Some(ref msg) = self.error_message {
             status_text = format!("Error: {}", msg);
         }
@@ -1803,6 +2830,8 @@ impl App {
             )
             .style(Style::default().fg(if self.error_message.is_some() {
                 Color::Red
+            } else if !self.exceeded_budgets.is_empty() {
+                Color::Yellow
             } else {
                 Color::White
             }));
@@ -1810,6 +2839,69 @@ impl App {
         frame.render_widget(status, area);
     }
 
+    /// Render a gauge for the active 5-hour usage block at the top of `area`,
+    /// returning the space left below it
+    fn render_usage_block_gauge(&mut self, frame: &mut Frame, area: Rect) -> Rect {
+        let now = chrono::Utc::now();
+        let Some(block) = self
+            .latest_usage_block
+            .as_ref()
+            .filter(|block| block.is_active(now))
+        else {
+            return area;
+        };
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+
+        let projection = block.projection(now, self.usage_block_limit);
+        let mut label = format!(
+            "{} • {} left",
+            format_tokens(block.tokens),
+            format_active_time(projection.remaining_minutes * 60)
+        );
+        // Against the token limit when there is one, otherwise the time elapsed
+        let ratio = match projection.limit_ratio(block.tokens) {
+            Some(ratio) => {
+                label.push_str(&format!(" • {:.0}% of limit", ratio * 100.0));
+                ratio
+            }
+            None => {
+                let elapsed = (now - block.start).num_seconds() as f64;
+                elapsed / (block.end - block.start).num_seconds() as f64
+            }
+        };
+        if let Some(rate) = projection.burn_rate {
+            label.push_str(&format!(" • {}/min", format_tokens(rate as u64)));
+        }
+        if let Some(at) = projection.exhausted_at {
+            label.push_str(&format!(
+                " • limit at {}",
+                self.timezone.format(at, "%H:%M")
+            ));
+        }
+        let color = if ratio >= 0.9 {
+            Color::Red
+        } else if ratio >= 0.7 {
+            Color::Yellow
+        } else {
+            Color::Green
+        };
+
+        let gauge = Gauge::default()
+            .block(
+                Block::default()
+                    .title(format!("⏱️ {}-hour usage block", BLOCK_HOURS))
+                    .borders(Borders::ALL),
+            )
+            .gauge_style(Style::default().fg(color))
+            .ratio(ratio.clamp(0.0, 1.0))
+            .label(label);
+        frame.render_widget(gauge, rows[0]);
+        rows[1]
+    }
+
     /// Render search input
     fn render_search_input(&mut self, frame: &mut Frame, area: Rect) {
         let search_area = Rect {
@@ -1826,6 +2918,139 @@ impl App {
         frame.render_widget(search_paragraph, search_area);
     }
 
+    /// Render the search history picker just above the search bar
+    fn render_search_picker(&mut self, frame: &mut Frame, search_bar: Rect) {
+        let entries = self.search_picker_entries();
+        let height = (entries.len() as u16 + 2).min(12).min(search_bar.y);
+        let width = search_bar.width.min(80);
+        let area = Rect {
+            x: search_bar.x,
+            y: search_bar.y - height,
+            width,
+            height,
+        };
+
+        let items: Vec<ListItem> = entries
+            .into_iter()
+            .map(|(label, _, _)| ListItem::new(label))
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title("Search history (↑/↓ select, Enter run, Esc close)")
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(Color::Yellow)),
+            )
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(Color::Yellow),
+            );
+
+        frame.render_widget(Clear, area);
+        if let Some(picker) = self.search_picker_state.as_mut() {
+            frame.render_stateful_widget(list, area, picker);
+        }
+    }
+
+    /// Render the touched-files picker over the conversation detail
+    fn render_file_picker(&mut self, frame: &mut Frame, area: Rect) {
+        let area = centered_rect(80, 60, area);
+        let items: Vec<ListItem> = self
+            .file_picker_entries
+            .iter()
+            .map(|path| ListItem::new(path.clone()))
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(format!(
+                        "Files touched ({}) - Enter: sessions that touched it, Esc: close",
+                        self.file_picker_entries.len()
+                    ))
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(Color::Cyan)),
+            )
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(Color::Cyan),
+            );
+
+        frame.render_widget(Clear, area);
+        if let Some(picker) = self.file_picker_state.as_mut() {
+            frame.render_stateful_widget(list, area, picker);
+        }
+    }
+
+    /// Render the commits produced by the open conversation over its detail
+    fn render_commit_panel(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(commits) = &self.commit_panel else {
+            return;
+        };
+        let session_id = self
+            .selected_conversation
+            .as_ref()
+            .map(|conv| conv.session_id.as_str())
+            .unwrap_or_default();
+        let tz = self.timezone;
+
+        let mut lines = Vec::new();
+        for linked in commits {
+            let commit = &linked.commit;
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{} ", commit.short_hash()),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(
+                    format!("{}  ", tz.format(commit.timestamp, "%Y-%m-%d %H:%M")),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled(
+                    commit.subject.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+            ]));
+            if let Some(link) = linked
+                .sessions
+                .iter()
+                .find(|link| link.session_id == session_id)
+            {
+                let reasons = link
+                    .reasons
+                    .iter()
+                    .map(|reason| reason.label())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                lines.push(Line::from(format!("   {} - {}", commit.author, reasons)));
+                for file in &link.files {
+                    lines.push(Line::from(Span::styled(
+                        format!("   ✏️  {}", file),
+                        Style::default().fg(Color::Cyan),
+                    )));
+                }
+            }
+            lines.push(Line::from(""));
+        }
+
+        let area = centered_rect(80, 60, area);
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(format!(
+                        "Commits produced by this session ({}) - Esc: close",
+                        commits.len()
+                    ))
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(Color::Green)),
+            )
+            .wrap(Wrap { trim: false });
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+    }
+
     /// Render in-conversation search input overlay
     fn render_in_conversation_search_input(&mut self, frame: &mut Frame, area: Rect) {
         // Create a centered search input at the bottom of the screen
@@ -1912,7 +3137,8 @@ impl App {
     /// Generate analytics data
     fn generate_analytics(&mut self) -> Result<(), ClaudeToolsError> {
         if self.analytics_engine.is_none() {
-            self.analytics_engine = Some(AnalyticsEngine::new(self.conversations.clone()));
+            self.analytics_engine =
+                Some(AnalyticsEngine::new(self.conversations.clone()).with_timezone(self.timezone));
         }
 
         if let Some(ref mut engine) = self.analytics_engine {
@@ -2066,6 +3292,9 @@ impl App {
         self.timeline_projects.sort();
 
         self.activity_timeline = Some(timeline);
+        if self.timeline_comparison.is_some() {
+            self.timeline_comparison = self.build_timeline_comparison();
+        }
         self.status_message = Some("Timeline refreshed successfully".to_string());
         self.timeline_loading = false;
     }
@@ -2076,6 +3305,40 @@ impl App {
         self.refresh_timeline();
     }
 
+    /// Show or hide the comparison with the previous period
+    fn toggle_timeline_comparison(&mut self) {
+        if self.timeline_comparison.take().is_none() {
+            self.timeline_comparison = self.build_timeline_comparison();
+        }
+    }
+
+    /// Compare the current timeline with the period before it
+    fn build_timeline_comparison(&self) -> Option<TimelineComparison> {
+        let current = self.activity_timeline.as_ref()?;
+        let previous = ActivityTimeline::create_filtered_timeline(
+            self.conversations.clone(),
+            TimelineConfig {
+                period: self.timeline_config.period.previous(&self.timezone),
+                ..self.timeline_config.clone()
+            },
+        );
+        Some(TimelineComparison::between(current, &previous))
+    }
+
+    /// Step the timeline to the previous/next ISO week or quarter
+    fn shift_timeline_period(&mut self, steps: i32) {
+        match self.timeline_config.period.shifted(steps, &self.timezone) {
+            Some(period) => self.update_timeline_config(TimelineConfig {
+                period,
+                ..self.timeline_config.clone()
+            }),
+            None => {
+                self.status_message =
+                    Some("[ and ] step through weeks (w/W) and quarters (Q)".to_string());
+            }
+        }
+    }
+
     /// Clear timeline cache
     fn clear_timeline_cache(&mut self) {
         if let Some(ref cache) = self.timeline_cache {
@@ -2187,6 +3450,8 @@ impl App {
             }
         }
 
+        self.search_engine
+            .set_ranking_weights(config.search.ranking_weights());
         self.app_config = config;
 
         // Notify about auto-refresh status change
@@ -2305,6 +3570,8 @@ impl App {
                     if !project_conversations.is_empty() {
                         // Filter main conversation list to this project's conversations
                         self.conversations = project_conversations;
+                        self.file_index = None;
+                        self.linked_commits = None;
 
                         // Reset list state and select first conversation
                         self.conversation_list_state = ratatui::widgets::ListState::default();
@@ -2385,6 +3652,7 @@ impl App {
                 include_timestamps: true,
                 template_path: None,
                 title: Some(format!("Conversation: {}", conversation.session_id)),
+                timezone: self.timezone,
             };
 
             // Create exporter and export
@@ -2434,6 +3702,9 @@ impl App {
             ]));
             content.push(Line::from(""));
 
+            // Calendar heatmap
+            content.extend(self.heatmap_lines(analytics));
+
             // Basic Statistics Section
             content.push(Line::from(vec![Span::styled(
                 "📈 Basic Statistics",
@@ -2527,15 +3798,105 @@ impl App {
                 .enumerate()
             {
                 content.push(Line::from(format!(
-                    "   {}. {} - {} uses ({:.1}%)",
+                    "   {}. {} - {} uses ({:.1}%), {} success",
                     i + 1,
                     tool.tool_name,
                     tool.usage_count,
-                    tool.percentage
+                    tool.percentage,
+                    format_success_rate(tool.success_rate)
                 )));
             }
             content.push(Line::from(""));
 
+            // Least Reliable Tools Section
+            let offenders = analytics.tool_analytics.worst_offenders(MIN_RATED_CALLS);
+            if !offenders.is_empty() {
+                content.push(Line::from(vec![Span::styled(
+                    "⚠️  Least Reliable Tools",
+                    Style::default()
+                        .fg(Color::LightRed)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for (tool, counts) in offenders.iter().take(5) {
+                    content.push(Line::from(format!(
+                        "   {} - {} success of {} calls ({})",
+                        tool,
+                        format_success_rate(counts.success_rate()),
+                        counts.total(),
+                        counts.failure_summary()
+                    )));
+                }
+                content.push(Line::from(""));
+            }
+
+            // File Hotspots Section
+            let files = &analytics.file_analytics;
+            if !files.top_files.is_empty() {
+                content.push(Line::from(vec![Span::styled(
+                    "🔥 File Hotspots",
+                    Style::default()
+                        .fg(Color::LightMagenta)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for file in files.top_files.iter().take(5) {
+                    content.push(Line::from(format!(
+                        "   {} - {} edits in {} sessions ({:.1} per session)",
+                        file.path,
+                        file.edits,
+                        file.sessions,
+                        file.churn()
+                    )));
+                }
+                let mut projects: Vec<_> = files.top_files_per_project.iter().collect();
+                projects.sort_by_key(|(project, hotspots)| {
+                    (
+                        std::cmp::Reverse(hotspots.iter().map(|file| file.edits).sum::<usize>()),
+                        *project,
+                    )
+                });
+                for (project, hotspots) in projects.iter().take(3) {
+                    if let Some(file) = hotspots.first() {
+                        content.push(Line::from(format!(
+                            "   {} hotspot: {} ({} edits, {} re-edits)",
+                            project, file.path, file.edits, file.re_edits
+                        )));
+                    }
+                }
+                content.push(Line::from(""));
+            }
+
+            // Prompt Cache Section
+            let cache = &analytics.cache_analytics;
+            if let Some(hit_ratio) = cache.overall.hit_ratio() {
+                content.push(Line::from(vec![Span::styled(
+                    "💾 Prompt Cache",
+                    Style::default()
+                        .fg(Color::LightCyan)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                content.push(Line::from(format!(
+                    "   Hit ratio: {:.1}% ({} tokens read, {} written)",
+                    hit_ratio, cache.overall.cache_read_tokens, cache.overall.cache_write_tokens
+                )));
+                content.push(Line::from(format!(
+                    "   Estimated savings: ${:.2}",
+                    cache.overall.estimated_savings
+                )));
+                for session in cache.poor_reuse.iter().take(3) {
+                    content.push(Line::from(vec![Span::styled(
+                        format!(
+                            "   Poor reuse: {} ({}) - {:.1}% of {} prompt tokens cached",
+                            session.session_id,
+                            session.project_path,
+                            session.cache_usage.hit_ratio().unwrap_or(0.0),
+                            session.cache_usage.prompt_tokens()
+                        ),
+                        Style::default().fg(Color::LightRed),
+                    )]));
+                }
+                content.push(Line::from(""));
+            }
+
             // Top Projects Section
             content.push(Line::from(vec![Span::styled(
                 "📁 Top Projects",
@@ -2560,6 +3921,73 @@ impl App {
             }
             content.push(Line::from(""));
 
+            // Slash Commands Section
+            let patterns = &analytics.prompt_patterns;
+            if !patterns.slash_commands.is_empty() {
+                content.push(Line::from(vec![Span::styled(
+                    "⌨️  Slash Commands",
+                    Style::default()
+                        .fg(Color::LightGreen)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for command in patterns.slash_commands.iter().take(8) {
+                    let top_project = command
+                        .projects
+                        .iter()
+                        .max_by_key(|(_, uses)| **uses)
+                        .map(|(project, _)| project.as_str())
+                        .unwrap_or("");
+                    content.push(Line::from(vec![
+                        Span::raw(format!("   {} ", command.name)),
+                        Span::styled(
+                            format!("[{}]", command.kind.label()),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        Span::raw(format!(
+                            " - {} uses in {} sessions, last {}, mostly {}",
+                            command.uses,
+                            command.sessions,
+                            self.timezone.format(command.last_used, "%Y-%m-%d"),
+                            top_project
+                        )),
+                    ]));
+                }
+                let recent_days: Vec<String> = patterns
+                    .slash_commands_per_day
+                    .iter()
+                    .rev()
+                    .take(7)
+                    .map(|(day, commands)| {
+                        format!("{} {}", &day[5..], commands.values().sum::<usize>())
+                    })
+                    .collect();
+                if !recent_days.is_empty() {
+                    content.push(Line::from(format!(
+                        "   Recent days: {}",
+                        recent_days.join(" · ")
+                    )));
+                }
+                content.push(Line::from(""));
+            }
+
+            // Repeated Prompts Section
+            if !patterns.repeated_prompts.is_empty() {
+                content.push(Line::from(vec![Span::styled(
+                    "🔁 Repeated Prompts",
+                    Style::default()
+                        .fg(Color::LightBlue)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for repeated in patterns.repeated_prompts.iter().take(5) {
+                    let prompt: String = repeated.prompt.chars().take(60).collect();
+                    content.push(Line::from(format!(
+                        "   \"{}\" - {} times in {} sessions",
+                        prompt, repeated.count, repeated.sessions
+                    )));
+                }
+                content.push(Line::from(""));
+            }
+
             // Temporal Analysis Section
             content.push(Line::from(vec![Span::styled(
                 "🕒 Peak Usage Hours",
@@ -2607,6 +4035,51 @@ impl App {
             )));
             content.push(Line::from(""));
 
+            // Response Latency Section
+            let responses = &quality.response_patterns;
+            if let Some(latency) = &responses.latency {
+                content.push(Line::from(vec![Span::styled(
+                    "⏱️  Response Latency",
+                    Style::default()
+                        .fg(Color::LightYellow)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                content.push(Line::from(format!(
+                    "   First reply: {}",
+                    latency.first_reply.percentiles_label()
+                )));
+                content.push(Line::from(format!(
+                    "   End of turn: {}",
+                    latency.turn.percentiles_label()
+                )));
+                let mut models: Vec<_> = responses.latency_per_model.iter().collect();
+                models.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.turn.count));
+                for (model, stats) in models.iter().take(3) {
+                    content.push(Line::from(format!(
+                        "   {} - first reply p50 {}, turn p50 {}",
+                        model,
+                        format_latency(stats.first_reply.p50),
+                        format_latency(stats.turn.p50)
+                    )));
+                }
+                if let Some((hour, stats)) = responses
+                    .latency_per_hour
+                    .iter()
+                    .max_by(|(_, a), (_, b)| a.first_reply.p50.total_cmp(&b.first_reply.p50))
+                {
+                    content.push(Line::from(format!(
+                        "   Slowest hour: {:02}:00 (first reply p50 {})",
+                        hour,
+                        format_latency(stats.first_reply.p50)
+                    )));
+                }
+                content.push(Line::from(format!(
+                    "   {} turns, {} waiting on permission excluded",
+                    latency.turn.count, responses.permission_wait_turns
+                )));
+                content.push(Line::from(""));
+            }
+
             // Conversation Length Distribution
             content.push(Line::from(vec![Span::styled(
                 "📈 Conversation Length Distribution",
@@ -2637,7 +4110,7 @@ impl App {
             content.push(Line::from(vec![
                 Span::styled("⌨️  Controls: ", Style::default().fg(Color::DarkGray)),
                 Span::styled(
-                    "j/k=scroll, g/G=top/bottom, e=export, r=refresh, q=back",
+                    "j/k=scroll, g/G=top/bottom, H=heatmap days, v=metric, e=export, r=refresh, q=back",
                     Style::default().fg(Color::DarkGray),
                 ),
             ]));
@@ -2692,9 +4165,19 @@ impl App {
     }
 
     /// Render timeline dashboard
-    fn render_timeline_dashboard(&mut self, frame: &mut Frame, area: Rect) {
+    fn render_timeline_dashboard(&mut self, frame: &mut Frame, mut area: Rect) {
         // Clear the area first to prevent text overlap during refresh
         frame.render_widget(Clear, area);
+
+        // Previous period side by side with the current one
+        if let (Some(comparison), false) = (&self.timeline_comparison, self.timeline_loading) {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .split(area);
+            frame.render_widget(Self::timeline_comparison_panel(comparison), columns[1]);
+            area = columns[0];
+        }
         
         // Show loading state if timeline is being generated
         if self.timeline_loading {
@@ -2751,7 +4234,10 @@ impl App {
                         .add_modifier(Modifier::BOLD),
                 ),
                 Span::styled(
-                    format!(" ({})", timeline.config.period.label()),
+                    format!(
+                        " ({})",
+                        timeline.config.period.label(&timeline.config.timezone)
+                    ),
                     Style::default().fg(Color::DarkGray),
                 ),
             ]));
@@ -2799,6 +4285,13 @@ impl App {
             }
             content.push(Line::from(""));
 
+            // Calendar heatmap while selecting days
+            if self.heatmap_selected.is_some() {
+                if let Some(ref analytics) = self.analytics_data {
+                    content.extend(self.heatmap_lines(analytics));
+                }
+            }
+
             // Project listing with navigation indicators
             content.push(Line::from(vec![
                 Span::styled(
@@ -3040,7 +4533,7 @@ impl App {
                 "   1: 24h  2: 48h  7: Week  3: Month  b/d/c: Brief/Detailed/Comprehensive",
             ));
             content.push(Line::from(
-                "   C: Clear cache  q/Esc: Return to conversation list",
+                "   C: Clear cache  p: Compare with previous period  q/Esc: Return to conversation list",
             ));
 
             // Create scrollable paragraph
@@ -3581,7 +5074,7 @@ mod tests {
             path: temp_dir.path().to_path_buf(),
         };
         
-        App::new(claude_dir).unwrap()
+        App::new(claude_dir, Timezone::Utc).unwrap()
     }
 
     #[test]
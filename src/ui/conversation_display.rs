@@ -1,5 +1,5 @@
 use crate::claude::conversation::{ConversationMessage, MessageRole};
-use crate::claude::search::{HighlightType, MatchHighlight};
+use crate::claude::search::{HighlightType, MatchHighlight, MatchSource};
 use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Parser, Tag, TagEnd};
 use ratatui::{
     style::{Color, Modifier, Style},
@@ -56,14 +56,31 @@ impl ConversationRenderer {
         lines.push(Line::from(""));
 
         // Render message content with markdown and highlights
+        let text_highlights: Vec<MatchHighlight> = highlights
+            .iter()
+            .filter(|highlight| highlight.source == MatchSource::Text)
+            .cloned()
+            .collect();
         let content_lines =
-            self.render_markdown_content_with_highlights(&message.content, highlights);
+            self.render_markdown_content_with_highlights(&message.content, &text_highlights);
         lines.extend(content_lines);
 
         // Add tool uses if any
         if !message.tool_uses.is_empty() {
             lines.push(Line::from(""));
-            lines.extend(self.render_tool_uses(&message.tool_uses));
+            lines.extend(self.render_tool_uses(&message.tool_uses, highlights));
+        }
+
+        // Add tool results that matched a search, or a line per result for
+        // messages that only carry results
+        if highlights
+            .iter()
+            .any(|highlight| matches!(highlight.source, MatchSource::ToolResult(_)))
+        {
+            lines.push(Line::from(""));
+            lines.extend(self.render_matched_tool_results(&message.tool_results, highlights));
+        } else if message.role == MessageRole::Tool {
+            lines.extend(self.render_tool_result_summaries(&message.tool_results));
         }
 
         // Add bottom separator
@@ -101,6 +118,13 @@ impl ConversationRenderer {
                     .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD),
             ),
+            MessageRole::Tool => (
+                "🔧",
+                "Tool",
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            ),
         };
 
         let timestamp = message.timestamp.format("%H:%M:%S").to_string();
@@ -350,10 +374,11 @@ impl ConversationRenderer {
         lines
     }
 
-    /// Render tool uses section  
+    /// Render tool uses section, expanding tool blocks that matched a search
     fn render_tool_uses(
         &self,
         tool_uses: &[crate::claude::conversation::ToolUse],
+        highlights: &[MatchHighlight],
     ) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
 
@@ -376,6 +401,21 @@ impl ConversationRenderer {
                 ),
             ]));
 
+            // Show the searchable fields of a matched tool block with highlights
+            let block_highlights =
+                Self::highlights_for_source(highlights, MatchSource::ToolInput(i));
+            if !block_highlights.is_empty() {
+                let block_lines = self
+                    .render_plain_text_with_highlights(
+                        &tool_use.searchable_text(),
+                        &block_highlights,
+                    )
+                    .into_iter()
+                    .map(Self::into_owned_line);
+                lines.extend(block_lines);
+                continue;
+            }
+
             // Show tool parameters (truncated if too long)
             let params_str = tool_use.input.to_string();
             let truncated = if params_str.len() > 100 {
@@ -396,6 +436,99 @@ impl ConversationRenderer {
         lines
     }
 
+    /// Render the tool results of a message that contain search matches
+    fn render_matched_tool_results(
+        &self,
+        tool_results: &[crate::claude::conversation::ToolResult],
+        highlights: &[MatchHighlight],
+    ) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+
+        lines.push(Line::from(Span::styled(
+            "📤 Matching Tool Results".to_string(),
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        )));
+
+        for (i, result) in tool_results.iter().enumerate() {
+            let block_highlights =
+                Self::highlights_for_source(highlights, MatchSource::ToolResult(i));
+            if block_highlights.is_empty() {
+                continue;
+            }
+
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{}. {}{}",
+                    i + 1,
+                    result.tool_use_id,
+                    if result.is_error { " (error)" } else { "" }
+                ),
+                Style::default().fg(Color::Magenta),
+            )));
+            lines.extend(
+                self.render_plain_text_with_highlights(&result.content, &block_highlights)
+                    .into_iter()
+                    .map(Self::into_owned_line),
+            );
+        }
+
+        lines
+    }
+
+    /// Render one line per tool result with the start of its output
+    fn render_tool_result_summaries(
+        &self,
+        tool_results: &[crate::claude::conversation::ToolResult],
+    ) -> Vec<Line<'static>> {
+        tool_results
+            .iter()
+            .map(|result| {
+                let style = if result.is_error {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                let mut summary = result.summary();
+                if summary.chars().count() > self.terminal_width.saturating_sub(4) {
+                    summary = summary
+                        .chars()
+                        .take(self.terminal_width.saturating_sub(7))
+                        .collect::<String>()
+                        + "...";
+                }
+                Line::from(vec![
+                    Span::styled("📤 ".to_string(), Style::default().fg(Color::Magenta)),
+                    Span::styled(summary, style),
+                ])
+            })
+            .collect()
+    }
+
+    /// Highlights that refer to a specific part of a message
+    fn highlights_for_source(
+        highlights: &[MatchHighlight],
+        source: MatchSource,
+    ) -> Vec<MatchHighlight> {
+        highlights
+            .iter()
+            .filter(|highlight| highlight.source == source)
+            .cloned()
+            .collect()
+    }
+
+    /// Convert a borrowed line into an owned one
+    fn into_owned_line(line: Line<'_>) -> Line<'static> {
+        Line::from(
+            line.spans
+                .into_iter()
+                .map(|span| Span::styled(span.content.into_owned(), span.style))
+                .collect::<Vec<_>>(),
+        )
+    }
+
     /// Wrap text to terminal width
     fn wrap_text(&self, text: &str) -> Vec<String> {
         let options = Options::new(self.terminal_width)
@@ -437,6 +570,7 @@ impl ConversationRenderer {
                                 end: line_end_pos,
                                 matched_text: highlight.matched_text.clone(),
                                 highlight_type: highlight.highlight_type.clone(),
+                                source: highlight.source,
                             })
                         } else {
                             None
@@ -556,19 +690,12 @@ fn syntect_style_to_ratatui_color(style: SyntectStyle) -> Color {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::claude::conversation::ToolUse;
     use chrono::Utc;
 
     fn create_test_message() -> ConversationMessage {
-        ConversationMessage {
-            uuid: "test-uuid".to_string(),
-            parent_uuid: None,
-            role: MessageRole::Assistant,
-            content: "# Hello\n\nThis is a **test** message with `code` and:\n\n```rust\nfn main() {\n    println!(\"Hello, world!\");\n}\n```".to_string(),
-            timestamp: Utc::now(),
-            model: Some("claude-3-5-sonnet".to_string()),
-            tool_uses: vec![],
-        }
+        ConversationMessage::assistant(Utc::now())
+            .with_content("# Hello\n\nThis is a **test** message with `code` and:\n\n```rust\nfn main() {\n    println!(\"Hello, world!\");\n}\n```")
+            .with_model("claude-3-5-sonnet")
     }
 
     #[test]
@@ -607,6 +734,7 @@ mod tests {
                 end: 34,
                 matched_text: "codebase".to_string(),
                 highlight_type: HighlightType::InConversationSearch,
+                source: MatchSource::Text,
             },
             MatchHighlight {
                 message_index: 0,
@@ -614,6 +742,7 @@ mod tests {
                 end: 42,
                 matched_text: "codebase".to_string(),
                 highlight_type: HighlightType::InConversationSearch,
+                source: MatchSource::Text,
             },
             MatchHighlight {
                 message_index: 0,
@@ -621,6 +750,7 @@ mod tests {
                 end: 50,
                 matched_text: "codebase".to_string(),
                 highlight_type: HighlightType::InConversationSearch,
+                source: MatchSource::Text,
             },
         ];
 
@@ -647,7 +777,7 @@ mod tests {
 
     #[test]
     fn test_duplicate_highlights_fix() {
-        use crate::claude::search::{MatchHighlight, HighlightType};
+        use crate::claude::search::{HighlightType, MatchHighlight, MatchSource};
         use ratatui::style::Style;
         
         let renderer = ConversationRenderer::new(80);
@@ -663,6 +793,7 @@ mod tests {
                 end: 12,   // The function will find occurrences in the text line
                 matched_text: "codebase".to_string(),
                 highlight_type: HighlightType::InConversationSearch,
+                source: MatchSource::Text,
             },
             MatchHighlight {
                 message_index: 0,
@@ -670,6 +801,7 @@ mod tests {
                 end: 51,
                 matched_text: "codebase".to_string(),
                 highlight_type: HighlightType::InConversationSearch,
+                source: MatchSource::Text,
             },
             MatchHighlight {
                 message_index: 0,
@@ -677,6 +809,7 @@ mod tests {
                 end: 85,
                 matched_text: "codebase".to_string(),
                 highlight_type: HighlightType::InConversationSearch,
+                source: MatchSource::Text,
             },
         ];
         
@@ -1,8 +1,109 @@
 use assert_cmd::prelude::*;
 use predicates::prelude::*;
+use serde_json::{json, Value};
+use std::path::Path;
 use std::process::Command;
 use tempfile::TempDir;
 
+/// Write `<claude_dir>/projects/<project>/<session_id>.jsonl`, one entry per line
+fn write_session(claude_dir: &Path, project: &str, session_id: &str, lines: &[Value]) {
+    let project_dir = claude_dir.join("projects").join(project);
+    std::fs::create_dir_all(&project_dir).unwrap();
+    let jsonl: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+    std::fs::write(project_dir.join(format!("{}.jsonl", session_id)), jsonl).unwrap();
+}
+
+/// A prompt typed by the user
+fn user_line(session_id: &str, uuid: &str, timestamp: &str, content: &str) -> Value {
+    json!({
+        "type": "user",
+        "parentUuid": null,
+        "sessionId": session_id,
+        "uuid": uuid,
+        "timestamp": timestamp,
+        "message": { "role": "user", "content": content },
+    })
+}
+
+/// An assistant message with the given content blocks
+fn assistant_line(session_id: &str, uuid: &str, timestamp: &str, content: Value) -> Value {
+    json!({
+        "type": "assistant",
+        "parentUuid": null,
+        "sessionId": session_id,
+        "uuid": uuid,
+        "timestamp": timestamp,
+        "message": {
+            "id": format!("m-{}", uuid),
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-sonnet-4",
+            "content": content,
+            "stop_reason": null,
+            "stop_sequence": null,
+        },
+    })
+}
+
+/// An assistant message replying with text
+fn text_line(session_id: &str, uuid: &str, timestamp: &str, text: &str) -> Value {
+    assistant_line(
+        session_id,
+        uuid,
+        timestamp,
+        json!([{ "type": "text", "text": text }]),
+    )
+}
+
+/// An assistant message making one tool call, with ID `t-<uuid>`
+fn tool_use_line(session_id: &str, uuid: &str, timestamp: &str, name: &str, input: Value) -> Value {
+    assistant_line(
+        session_id,
+        uuid,
+        timestamp,
+        json!([{ "type": "tool_use", "id": format!("t-{}", uuid), "name": name, "input": input }]),
+    )
+}
+
+/// A user message carrying the result of a tool call
+fn tool_result_line(
+    session_id: &str,
+    uuid: &str,
+    timestamp: &str,
+    tool_use_id: &str,
+    content: &str,
+    is_error: bool,
+) -> Value {
+    json!({
+        "type": "user",
+        "parentUuid": null,
+        "sessionId": session_id,
+        "uuid": uuid,
+        "timestamp": timestamp,
+        "message": {
+            "role": "user",
+            "content": [{
+                "type": "tool_result",
+                "tool_use_id": tool_use_id,
+                "content": content,
+                "is_error": is_error,
+            }],
+        },
+    })
+}
+
+/// A line with the working directory the session ran in
+fn with_cwd(mut line: Value, cwd: &str) -> Value {
+    line["cwd"] = json!(cwd);
+    line
+}
+
+/// An assistant line with token usage
+fn with_usage(mut line: Value, usage: Value) -> Value {
+    line["message"]["usage"] = usage;
+    line
+}
+
 #[test]
 fn test_help_output() {
     let mut cmd = Command::cargo_bin("claude-tools").unwrap();
@@ -104,6 +205,7 @@ fn test_search_placeholder() {
     std::fs::create_dir(claude_dir.join("projects")).unwrap();
 
     let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
     cmd.args([
         "--claude-dir",
         claude_dir.to_str().unwrap(),
@@ -115,3 +217,1018 @@ fn test_search_placeholder() {
         "No conversations found matching: test query",
     ));
 }
+
+#[test]
+fn test_search_json_format_empty() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    std::fs::create_dir(&claude_dir).unwrap();
+    std::fs::create_dir(claude_dir.join("projects")).unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        claude_dir.to_str().unwrap(),
+        "search",
+        "test query",
+        "--format",
+        "json",
+    ]);
+
+    cmd.assert().success().stdout(predicate::str::diff("[]\n"));
+}
+
+#[test]
+fn test_similar_unknown_conversation() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    std::fs::create_dir(&claude_dir).unwrap();
+    std::fs::create_dir(claude_dir.join("projects")).unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args([
+        "--claude-dir",
+        claude_dir.to_str().unwrap(),
+        "similar",
+        "missing-session",
+    ]);
+
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Conversation not found: missing-session",
+    ));
+}
+
+#[test]
+fn test_search_json_facets_empty() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    std::fs::create_dir(&claude_dir).unwrap();
+    std::fs::create_dir(claude_dir.join("projects")).unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        claude_dir.to_str().unwrap(),
+        "search",
+        "test query",
+        "--format",
+        "json",
+        "--facets",
+    ]);
+
+    // The results stay a plain array on stdout; facets go to stderr
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff("[]\n"))
+        .stderr(predicate::str::contains("\"projects\": []"));
+}
+
+#[test]
+fn test_search_save_and_run() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    std::fs::create_dir(&claude_dir).unwrap();
+    std::fs::create_dir(claude_dir.join("projects")).unwrap();
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "search",
+        "deploy",
+        "--tool",
+        "Bash",
+        "--save",
+        "deploys",
+    ]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args(["--claude-dir", dir, "search", "--saved"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("deploys → 'deploy'"))
+        .stdout(predicate::str::contains("tool contains 'Bash'"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "search",
+        "--run",
+        "deploys",
+        "--format",
+        "json",
+    ]);
+    cmd.assert().success().stdout(predicate::str::diff("[]\n"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args(["--claude-dir", dir, "list", "--collection", "missing"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No collection named 'missing'"));
+
+    // A corrupt store still lets searches run, and is left as it was
+    let store_path = temp_dir
+        .path()
+        .join(".claude")
+        .join("claude-tools-searches.json");
+    std::fs::write(&store_path, "{not json").unwrap();
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args(["--claude-dir", dir, "search", "deploy", "--format", "json"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff("[]\n"))
+        .stderr(predicate::str::contains("Could not load saved searches"));
+    assert_eq!(std::fs::read_to_string(&store_path).unwrap(), "{not json");
+}
+
+#[test]
+fn test_search_limits_hits_per_conversation() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    let lines: Vec<Value> = (1..=5)
+        .map(|i| {
+            user_line(
+                "sess-1",
+                &format!("u{}", i),
+                &format!("2024-06-01T10:0{}:00Z", i),
+                &format!("deploy attempt {}", i),
+            )
+        })
+        .collect();
+    write_session(&claude_dir, "-repo", "sess-1", &lines);
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args(["--claude-dir", dir, "--tz", "UTC", "search", "deploy"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+    assert_eq!(stdout.matches("🎯").count(), 3);
+    assert!(stdout.contains("u3 User 2024-06-01 10:03:00"));
+    assert!(stdout.contains("… 2 more matching message(s)"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "search",
+        "deploy",
+        "--max-hits",
+        "0",
+    ]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+    assert_eq!(stdout.matches("🎯").count(), 5);
+    assert!(!stdout.contains("more matching message"));
+}
+
+#[test]
+fn test_files_touched_by_sessions() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    let edit = tool_use_line(
+        "sess-1",
+        "a1",
+        "2024-06-01T10:00:00Z",
+        "Edit",
+        json!({ "file_path": "src/ui/app.rs", "old_string": "a", "new_string": "b" }),
+    );
+    write_session(&claude_dir, "-repo", "sess-1", &[with_cwd(edit, "/repo")]);
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args(["--claude-dir", dir, "files", "ui/app.rs", "--modified"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("/repo/src/ui/app.rs"))
+        .stdout(predicate::str::contains("sess-1"))
+        .stdout(predicate::str::contains("Edit"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args(["--claude-dir", dir, "files", "*.toml"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "No conversations touched files matching",
+    ));
+}
+
+#[test]
+fn test_timeline_calendar_periods() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    std::fs::create_dir_all(claude_dir.join("projects")).unwrap();
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "timeline",
+        "--quarter",
+        "2024-Q2",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Activity Timeline - 2024 Q2"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args(["--claude-dir", dir, "timeline", "--week", "2024-W99"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid ISO week '2024-W99'"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args(["--claude-dir", dir, "timeline", "--to", "2024-06-01"]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn test_heatmap_year_and_svg() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    write_session(
+        &claude_dir,
+        "-repo",
+        "sess-1",
+        &[user_line("sess-1", "u1", "2024-06-03T10:00:00Z", "hello")],
+    );
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "heatmap",
+        "--year",
+        "2024",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2024-01-01 to 2024-12-31"))
+        .stdout(predicate::str::contains("Busiest day: Mon 2024-06-03"));
+
+    let svg_path = temp_dir.path().join("heatmap.svg");
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "heatmap",
+        "--year",
+        "2024",
+        "--svg",
+    ])
+    .arg(&svg_path);
+    cmd.assert().success();
+    let svg = std::fs::read_to_string(&svg_path).unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains("2024-06-03: 1 messages"));
+}
+
+#[test]
+fn test_timesheet_splits_idle_gaps() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    // 20 minutes of work, then the session is left open overnight
+    write_session(
+        &claude_dir,
+        "-repo",
+        "sess-1",
+        &[
+            user_line("sess-1", "u1", "2024-06-03T09:00:00Z", "start"),
+            user_line("sess-1", "u2", "2024-06-03T09:20:00Z", "more"),
+            user_line("sess-1", "u3", "2024-06-04T08:00:00Z", "next day"),
+        ],
+    );
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "timesheet",
+        "--format",
+        "csv",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "period,project,active_minutes,work_sessions",
+        ))
+        .stdout(predicate::str::contains("2024-06-03,-repo,20.0,1"))
+        .stdout(predicate::str::contains("2024-06-04,-repo,0.0,1"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "timesheet",
+        "--by",
+        "week",
+    ]);
+    cmd.args(["--from", "2024-06-03", "--to", "2024-06-03"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("📅 2024-W23"))
+        .stdout(predicate::str::contains("20m"));
+}
+
+#[test]
+fn test_timesheet_buckets_days_in_time_zone() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    // An evening session in Los Angeles, after midnight UTC
+    write_session(
+        &claude_dir,
+        "-repo",
+        "sess-1",
+        &[
+            user_line("sess-1", "u1", "2024-06-04T03:30:00Z", "start"),
+            user_line("sess-1", "u2", "2024-06-04T03:45:00Z", "more"),
+        ],
+    );
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args(["--claude-dir", dir, "--tz", "America/Los_Angeles"]);
+    cmd.args(["timesheet", "--format", "csv"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2024-06-03,-repo,15.0,1"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "timesheet",
+        "--format",
+        "csv",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2024-06-04,-repo,15.0,1"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "Mars/Olympus_Mons",
+        "timesheet",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown time zone"));
+}
+
+#[test]
+fn test_commits_linked_to_sessions() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .current_dir(&repo)
+            .args(args)
+            .env("GIT_AUTHOR_DATE", "2024-06-03T09:30:00Z")
+            .env("GIT_COMMITTER_DATE", "2024-06-03T09:30:00Z")
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "-q"]);
+    git(&["config", "user.name", "Dev"]);
+    git(&["config", "user.email", "dev@example.com"]);
+    std::fs::write(repo.join("login.rs"), "fn login() {}\n").unwrap();
+    git(&["add", "login.rs"]);
+    git(&["commit", "-q", "-m", "Fix login"]);
+
+    let claude_dir = temp_dir.path().join("claude");
+    let cwd = repo.to_str().unwrap();
+    let lines = [
+        user_line("sess-1", "u1", "2024-06-03T09:00:00Z", "fix login"),
+        tool_use_line(
+            "sess-1",
+            "a1",
+            "2024-06-03T09:10:00Z",
+            "Edit",
+            json!({ "file_path": "login.rs" }),
+        ),
+    ];
+    let lines: Vec<Value> = lines.into_iter().map(|line| with_cwd(line, cwd)).collect();
+    write_session(&claude_dir, "-repo", "sess-1", &lines);
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "commits",
+        "--session",
+        "sess-1",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1 commit(s) produced with Claude"))
+        .stdout(predicate::str::contains("Fix login"))
+        .stdout(predicate::str::contains("active before, touched files"))
+        .stdout(predicate::str::contains("login.rs"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args(["--claude-dir", dir, "--tz", "UTC", "timeline"]);
+    cmd.args(["--from", "2024-06-03", "--to", "2024-06-03", "--commits"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1 agent-assisted commit(s)"));
+}
+
+#[test]
+fn test_stats_tool_success_rates() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    let tool_use = |uuid: &str, name: &str| {
+        tool_use_line("sess-1", uuid, "2024-06-01T10:00:00Z", name, json!({}))
+    };
+    let tool_result = |uuid: &str, id: &str, content: &str, is_error: bool| {
+        tool_result_line(
+            "sess-1",
+            uuid,
+            "2024-06-01T10:01:00Z",
+            id,
+            content,
+            is_error,
+        )
+    };
+    let lines = [
+        tool_use("a1", "Bash"),
+        tool_result("r1", "t-a1", "Exit code 1\nerror: build failed", true),
+        tool_use("a2", "Bash"),
+        tool_result("r2", "t-a2", "ok", false),
+        tool_use("a3", "Bash"),
+        tool_result("r3", "t-a3", "ok", false),
+        tool_use("a4", "Edit"),
+        tool_result(
+            "r4",
+            "t-a4",
+            "The user doesn't want to proceed with this tool use.",
+            true,
+        ),
+        // No result yet, so no success rate
+        tool_use("a5", "Read"),
+    ];
+    write_session(&claude_dir, "-repo", "sess-1", &lines);
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args(["--claude-dir", dir, "--tz", "UTC", "stats", "--global"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Bash - 3 uses (60.0%), 66.7% success",
+        ))
+        .stdout(predicate::str::contains(
+            "Edit - 1 uses (20.0%), 0.0% success",
+        ))
+        .stdout(predicate::str::contains(
+            "Read - 1 uses (20.0%), n/a success",
+        ))
+        .stdout(predicate::str::contains("Least Reliable Tools"))
+        .stdout(predicate::str::contains(
+            "Bash - 66.7% success of 3 calls (1 non-zero exit)",
+        ))
+        .stdout(predicate::str::contains(
+            "-repo - 50.0% success of 4 calls (1 rejected, 1 non-zero exit)",
+        ))
+        .stdout(predicate::str::contains(
+            "2024-06-01 - 50.0% success of 4 calls",
+        ));
+}
+
+#[test]
+fn test_stats_prompt_cache_usage() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    let reply = |uuid: &str, timestamp: &str, cache_write: u32, cache_read: u32| {
+        let mut line = with_usage(
+            text_line("sess-1", uuid, timestamp, "Working"),
+            json!({
+                "input_tokens": 100_000,
+                "output_tokens": 500,
+                "cache_creation_input_tokens": cache_write,
+                "cache_read_input_tokens": cache_read,
+            }),
+        );
+        line["message"]["model"] = json!("claude-sonnet-4-20250514");
+        line
+    };
+    let lines = [
+        reply("a1", "2024-06-01T23:30:00Z", 1_000_000, 0),
+        reply("a2", "2024-06-01T23:40:00Z", 0, 900_000),
+    ];
+    write_session(&claude_dir, "-repo", "sess-1", &lines);
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "stats",
+        "--global",
+        "--detailed",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Hit ratio: 42.9%"))
+        .stdout(predicate::str::contains("Cache reads: 900000 tokens"))
+        .stdout(predicate::str::contains("Uncached input: 200000 tokens"))
+        .stdout(predicate::str::contains("Estimated savings: $1.68"))
+        .stdout(predicate::str::contains(
+            "claude-sonnet-4-20250514 - 42.9% hit ratio, 1000000 tokens written, est. $1.68 saved",
+        ))
+        .stdout(predicate::str::contains(
+            "sess-1 (-repo) - 42.9% of 2100000 prompt tokens cached",
+        ));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args(["--claude-dir", dir, "--tz", "UTC", "stats", "sess-1"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Prompt cache hit ratio: 42.9% (900000 of 2100000 prompt tokens, est. $1.68 saved)",
+    ));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.current_dir(temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "stats",
+        "--global",
+        "--export",
+        "csv",
+    ]);
+    cmd.assert().success();
+    let csv_path = std::fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "csv"))
+        .unwrap();
+    let csv = std::fs::read_to_string(csv_path).unwrap();
+    assert!(csv.contains("2024-06-01,200000,1000000,900000,42.86,1.6800\n"));
+}
+
+#[test]
+fn test_stats_slash_commands_and_repeated_prompts() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    let first = [
+        user_line("sess-1", "u1", "2024-06-01T23:10:00Z", "/review"),
+        user_line("sess-1", "u2", "2024-06-01T23:20:00Z", "Run the   tests"),
+        user_line(
+            "sess-1",
+            "u3",
+            "2024-06-01T23:30:00Z",
+            "<command-name>/project:deploy</command-name>\n<command-args>prod</command-args>",
+        ),
+    ];
+    let second = [
+        user_line("sess-2", "v1", "2024-06-01T23:40:00Z", "/review"),
+        user_line("sess-2", "v2", "2024-06-01T23:50:00Z", "run the tests"),
+    ];
+    write_session(&claude_dir, "-repo", "sess-1", &first);
+    write_session(&claude_dir, "-repo", "sess-2", &second);
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.current_dir(temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "stats",
+        "--global",
+        "--export",
+        "json",
+    ]);
+    cmd.assert().success();
+    let json_path = std::fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .unwrap();
+    let analytics: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(json_path).unwrap()).unwrap();
+    let patterns = &analytics["prompt_patterns"];
+
+    let commands = &patterns["slash_commands"];
+    assert_eq!(commands[0]["name"], "/review");
+    assert_eq!(commands[0]["uses"], 2);
+    assert_eq!(commands[0]["sessions"], 2);
+    assert_eq!(commands[1]["name"], "/project:deploy");
+    assert_eq!(commands[1]["kind"], "project");
+    assert_eq!(commands[1]["projects"]["-repo"], 1);
+    // Days are bucketed in the pinned zone, so late-evening uses stay on June 1st
+    assert_eq!(
+        patterns["slash_commands_per_day"],
+        serde_json::json!({ "2024-06-01": { "/project:deploy": 1, "/review": 2 } })
+    );
+
+    let repeated = patterns["repeated_prompts"].as_array().unwrap();
+    assert_eq!(repeated.len(), 1);
+    assert_eq!(repeated[0]["prompt"], "run the tests");
+    assert_eq!(repeated[0]["count"], 2);
+    assert_eq!(repeated[0]["sessions"], 2);
+    assert_eq!(repeated[0]["last_used"], "2024-06-01T23:50:00Z");
+}
+
+#[test]
+fn test_stats_response_latency() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    let lines = [
+        user_line("sess-1", "u1", "2024-06-01T10:00:00Z", "Explain the parser"),
+        text_line("sess-1", "a1", "2024-06-01T10:00:04Z", "It reads JSONL"),
+        user_line("sess-1", "u2", "2024-06-01T10:01:00Z", "Rename the module"),
+        tool_use_line("sess-1", "a2", "2024-06-01T10:01:02Z", "Edit", json!({})),
+        tool_result_line(
+            "sess-1",
+            "r1",
+            "2024-06-01T10:03:00Z",
+            "t-a2",
+            "The user doesn't want to proceed with this tool use.",
+            true,
+        ),
+        text_line("sess-1", "a3", "2024-06-01T10:03:01Z", "Understood"),
+        user_line(
+            "sess-1",
+            "u3",
+            "2024-06-01T10:05:00Z",
+            "Summarize the changes",
+        ),
+        text_line("sess-1", "a4", "2024-06-01T10:05:10Z", "Nothing changed"),
+    ];
+    write_session(&claude_dir, "-repo", "sess-1", &lines);
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args(["--claude-dir", dir, "--tz", "UTC", "stats", "--detailed"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Response Latency (2 turns, 1 waiting on permission excluded)",
+        ))
+        .stdout(predicate::str::contains(
+            "First reply: p50 4.0s, p90 10.0s, p99 10.0s",
+        ))
+        .stdout(predicate::str::contains(
+            "claude-sonnet-4 - first reply p50 4.0s, p90 10.0s, p99 10.0s; turn p50 4.0s (2 turns)",
+        ))
+        .stdout(predicate::str::contains("10:00 - first reply"));
+}
+
+#[test]
+fn test_stats_file_hotspots() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    let edit = |session: &str, uuid: &str, tool: &str, path: &str| {
+        let input = json!({ "file_path": path });
+        with_cwd(
+            tool_use_line(session, uuid, "2024-06-01T10:00:00Z", tool, input),
+            "/repo",
+        )
+    };
+    write_session(
+        &claude_dir,
+        "-repo",
+        "sess-1",
+        &[
+            edit("sess-1", "a1", "Edit", "src/parser.rs"),
+            edit("sess-1", "a2", "Edit", "src/parser.rs"),
+            edit("sess-1", "a3", "Read", "src/lib.rs"),
+        ],
+    );
+    write_session(
+        &claude_dir,
+        "-repo",
+        "sess-2",
+        &[edit("sess-2", "b1", "Write", "/repo/src/parser.rs")],
+    );
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args(["--claude-dir", dir, "stats", "--detailed"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "File Hotspots (3 edits across 1 files)",
+        ))
+        .stdout(predicate::str::contains(
+            "/repo/src/parser.rs - 3 edits in 2 sessions, 1 re-edits (up to 2 in one session)",
+        ))
+        .stdout(predicate::str::contains("/repo/src/lib.rs").not())
+        .stdout(predicate::str::contains(
+            "/repo/src/parser.rs - 3 edits, 1.5 per session",
+        ));
+}
+
+#[test]
+fn test_stats_commands() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    let call = |uuid: &str, timestamp: &str, command: &str| {
+        tool_use_line(
+            "sess-1",
+            uuid,
+            timestamp,
+            "Bash",
+            json!({ "command": command }),
+        )
+    };
+    let result = |uuid: &str, timestamp: &str, tool_use_id: &str, content: &str| {
+        tool_result_line("sess-1", uuid, timestamp, tool_use_id, content, false)
+    };
+    let lines = [
+        call("a1", "2024-06-01T10:00:00Z", "cargo test --workspace"),
+        result(
+            "r1",
+            "2024-06-01T10:00:40Z",
+            "t-a1",
+            "Exit code 101\ntest failed",
+        ),
+        call("a2", "2024-06-01T10:01:00Z", "cd crates/api && cargo test"),
+        result("r2", "2024-06-01T10:01:20Z", "t-a2", "test result: ok"),
+        call("a3", "2024-06-01T10:02:00Z", "git status"),
+        result("r3", "2024-06-01T10:02:01Z", "t-a3", "clean"),
+    ];
+    write_session(&claude_dir, "-repo", "sess-1", &lines);
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args(["--claude-dir", dir, "stats", "commands"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Shell Commands (3 runs of 2 distinct commands)",
+        ))
+        .stdout(predicate::str::contains(
+            "cargo test      2    50.0%     40.0s  -repo (2)",
+        ))
+        .stdout(predicate::str::contains(
+            "git status      1     0.0%      1.0s  -repo (1)",
+        ));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.current_dir(temp_dir.path());
+    cmd.args(["--claude-dir", dir, "stats", "commands", "--export", "csv"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Shell commands exported to"));
+    let export = std::fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "csv"))
+        .unwrap();
+    let csv = std::fs::read_to_string(export).unwrap();
+    assert!(csv.contains("cargo test,2,1,0,0,1,50.00,40.0,-repo=2"));
+}
+
+#[test]
+fn test_timeline_compare_previous_week() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    write_session(
+        &claude_dir,
+        "-repo",
+        "sess-1",
+        &[
+            user_line("sess-1", "u1", "2024-06-04T10:00:00Z", "hello"),
+            user_line("sess-1", "u2", "2024-06-04T10:05:00Z", "again"),
+        ],
+    );
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args(["--claude-dir", dir, "--tz", "UTC", "timeline"]);
+    cmd.args(["--week", "2024-W23", "--compare", "previous"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Week 2024-W23 vs Week 2024-W22"))
+        .stdout(predicate::str::contains("+2 (new)"))
+        .stdout(predicate::str::contains("+ -repo"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args(["--claude-dir", dir, "--tz", "UTC", "timeline"]);
+    cmd.args([
+        "--week",
+        "2024-W24",
+        "--compare",
+        "previous",
+        "--format",
+        "json",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"change\": \"went_quiet\""));
+}
+
+#[test]
+fn test_timeline_export_ics() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    let lines = [
+        user_line("sess-1", "u1", "2024-06-03T09:00:00Z", "fix the build"),
+        user_line("sess-1", "u2", "2024-06-03T09:45:00Z", "thanks"),
+    ];
+    let lines: Vec<Value> = lines
+        .into_iter()
+        .map(|line| with_cwd(line, "/repo"))
+        .collect();
+    write_session(&claude_dir, "-repo", "sess-1", &lines);
+    let dir = claude_dir.to_str().unwrap();
+    let ics_path = temp_dir.path().join("sessions.ics");
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "timeline",
+        "--week",
+        "2024-W23",
+    ]);
+    cmd.args(["--export", "ics", "--output"]).arg(&ics_path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2 work session(s) exported"));
+
+    let ics = std::fs::read_to_string(&ics_path).unwrap();
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+    assert!(ics.contains("DTSTART:20240603T094500Z"));
+    assert!(ics.contains("X-CLAUDE-SESSION-ID:sess-1"));
+}
+
+#[test]
+fn test_digest_without_activity() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    std::fs::create_dir_all(claude_dir.join("projects")).unwrap();
+    let output = temp_dir.path().join("standup.md");
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args(["--claude-dir", claude_dir.to_str().unwrap(), "digest"]);
+    cmd.args(["--period", "week", "--output"]).arg(&output);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Digest written to"));
+
+    let markdown = std::fs::read_to_string(&output).unwrap();
+    assert!(markdown.starts_with("# Standup Digest: Past 7 days"));
+    assert!(markdown.contains("No activity in this period."));
+}
+
+#[test]
+fn test_budget_exit_code() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    let reply = with_usage(
+        text_line("sess-1", "a1", "2024-06-01T10:00:00Z", "Done"),
+        json!({ "input_tokens": 1000, "output_tokens": 500 }),
+    );
+    write_session(&claude_dir, "-repo", "sess-1", &[reply]);
+    let dir = claude_dir.to_str().unwrap();
+
+    // No budgets configured yet
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path())
+        .current_dir(temp_dir.path());
+    cmd.args(["--claude-dir", dir, "budget"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No budgets configured"));
+
+    let config_dir = temp_dir.path().join(".claude");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("claude-tools-config.json"),
+        r#"{
+  "version": "1.0",
+  "realtime": {"enabled": false, "debounce_ms": 500, "watch_conversations": true, "watch_mcp_configs": true, "refresh_interval_seconds": 30},
+  "timeline": {"default_period": "48h", "summary_depth": "detailed", "max_conversations": null, "enable_caching": true},
+  "ui": {"default_view": "ConversationList", "theme": "default", "show_status_messages": true, "status_message_duration_ms": 3000},
+  "budgets": [
+    {"metric": "tokens", "period": "total", "project": "repo", "limit": 1000},
+    {"name": "spend", "metric": "cost", "period": "total", "limit": 5.0}
+  ]
+}"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path())
+        .current_dir(temp_dir.path());
+    cmd.args(["--claude-dir", dir, "budget"]);
+    cmd.assert()
+        .code(3)
+        .stdout(predicate::str::contains("total tokens (repo)"))
+        .stdout(predicate::str::contains(
+            "1.5k tokens of 1.0k tokens (150%)",
+        ))
+        .stdout(predicate::str::contains("$0.01 of $5.00 (0%)"))
+        .stderr(predicate::str::contains("1 usage budget(s) exceeded"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.env("HOME", temp_dir.path())
+        .current_dir(temp_dir.path());
+    cmd.args(["--claude-dir", dir, "budget", "--quiet"]);
+    cmd.assert()
+        .code(3)
+        .stdout(predicate::str::contains("spend").not());
+}
+
+#[test]
+fn test_blocks_splits_five_hour_windows() {
+    let temp_dir = TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("claude");
+    let reply = |uuid: &str, timestamp: &str, tokens: u32| {
+        with_usage(
+            text_line("sess-1", uuid, timestamp, "ok"),
+            json!({ "input_tokens": tokens, "output_tokens": 0 }),
+        )
+    };
+    let lines = [
+        reply("a1", "2024-06-01T09:30:00Z", 1000),
+        reply("a2", "2024-06-01T10:30:00Z", 2000),
+        reply("a3", "2024-06-01T14:10:00Z", 500),
+    ];
+    write_session(&claude_dir, "-repo", "sess-1", &lines);
+    let dir = claude_dir.to_str().unwrap();
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args(["--claude-dir", dir, "--tz", "UTC", "blocks"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Usage blocks (5-hour windows)"))
+        .stdout(predicate::str::contains("2024-06-01 09:00 - 14:00"))
+        .stdout(predicate::str::contains("3.0k tokens"))
+        .stdout(predicate::str::contains("2024-06-01 14:00 - 19:00"))
+        .stdout(predicate::str::contains("No active block"));
+
+    let mut cmd = Command::cargo_bin("claude-tools").unwrap();
+    cmd.args([
+        "--claude-dir",
+        dir,
+        "--tz",
+        "UTC",
+        "blocks",
+        "--format",
+        "json",
+    ]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["blocks"].as_array().unwrap().len(), 2);
+    assert_eq!(report["token_limit"], 3000);
+    assert!(report["active"].is_null());
+}